@@ -0,0 +1,15 @@
+//! Information about the current block. Mocked out on the host, so tests can
+//! control the passage of time.
+
+/// Returns the timestamp of the current block, in seconds.
+#[cfg(target_arch = "wasm32")]
+pub fn timestamp() -> u64 {
+    stylus_sdk::block::timestamp()
+}
+
+/// Returns the timestamp of the current block, in seconds. Set by
+/// [crate::host::with_storage], and moved with [crate::host::advance_time].
+#[cfg(not(target_arch = "wasm32"))]
+pub fn timestamp() -> u64 {
+    crate::host::CURRENT_TIME.with(|t| *t.borrow())
+}
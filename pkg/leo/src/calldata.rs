@@ -9,3 +9,43 @@ pub fn write_address(bytes: &mut [u8], slot: usize, address: Address) {
 pub fn write_u256(bytes: &mut [u8], slot: usize, uint: U256) {
     bytes[4 + 32 * slot..4 + 32 * slot + 32].copy_from_slice(&uint.to_be_bytes::<32>())
 }
+/// Writes an int32, sign extending it to the word.
+pub fn write_i32(bytes: &mut [u8], slot: usize, int: i32) {
+    let word = &mut bytes[4 + 32 * slot..4 + 32 * slot + 32];
+    word.fill(if int < 0 { 0xff } else { 0 });
+    word[28..].copy_from_slice(&int.to_be_bytes());
+}
+
+/// Reads a word of ABI encoded data. Return data has no selector, so
+/// calldata should be passed without its selector.
+pub fn read_word(bytes: &[u8], slot: usize) -> Option<[u8; 32]> {
+    bytes.get(32 * slot..32 * slot + 32)?.try_into().ok()
+}
+pub fn read_u256(bytes: &[u8], slot: usize) -> Option<U256> {
+    read_word(bytes, slot).map(U256::from_be_bytes)
+}
+pub fn read_address(bytes: &[u8], slot: usize) -> Option<Address> {
+    let word = read_word(bytes, slot)?;
+    match word[..12].iter().all(|&b| b == 0) {
+        true => Some(Address::from_slice(&word[12..])),
+        false => None,
+    }
+}
+/// Reads a uint128, which must have its upper bytes cleared.
+pub fn read_u128(bytes: &[u8], slot: usize) -> Option<u128> {
+    read_u256(bytes, slot)?.try_into().ok()
+}
+/// Reads a uint32, which must have its upper bytes cleared.
+pub fn read_u32(bytes: &[u8], slot: usize) -> Option<u32> {
+    read_u256(bytes, slot)?.try_into().ok()
+}
+/// Reads an int32, which must be sign extended to the word.
+pub fn read_i32(bytes: &[u8], slot: usize) -> Option<i32> {
+    let word = read_word(bytes, slot)?;
+    let int = i32::from_be_bytes(word[28..].try_into().ok()?);
+    let extension = if int < 0 { 0xff } else { 0 };
+    match word[..28].iter().all(|&b| b == extension) {
+        true => Some(int),
+        false => None,
+    }
+}
@@ -1,9 +1,89 @@
+//! Utilities for moving ERC20 tokens in and out of Leo, including tokens
+//! that don't return a boolean from their transfer functions.
+
 use stylus_sdk::alloy_primitives::{Address, U256};
 
-pub fn take(pool: Address, amount: U256) -> Result<(), Vec<u8>> {
-    Ok(())
+#[allow(unused_imports)]
+use stylus_sdk::{call::RawCall, contract, msg, types::AddressVM};
+
+#[allow(unused_imports)]
+use crate::{calldata::*, error::Error};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::host;
+
+//transfer(address,uint256)
+pub const TRANSFER_SELECTOR: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+
+//transferFrom(address,address,uint256)
+pub const TRANSFER_FROM_SELECTOR: [u8; 4] = [0x23, 0xb8, 0x72, 0xdd];
+
+/// Encodes a call to `transfer(address to, uint256 amount)`.
+fn pack_transfer(to: Address, amount: U256) -> [u8; 4 + 32 * 2] {
+    let mut data = [0_u8; 4 + 32 * 2];
+    write_selector(&mut data, &TRANSFER_SELECTOR);
+    write_address(&mut data, 0, to);
+    write_u256(&mut data, 1, amount);
+    data
+}
+
+/// Encodes a call to `transferFrom(address from, address to, uint256 amount)`.
+fn pack_transfer_from(from: Address, to: Address, amount: U256) -> [u8; 4 + 32 * 3] {
+    let mut data = [0_u8; 4 + 32 * 3];
+    write_selector(&mut data, &TRANSFER_FROM_SELECTOR);
+    write_address(&mut data, 0, from);
+    write_address(&mut data, 1, to);
+    write_u256(&mut data, 2, amount);
+    data
+}
+
+/// Check what a token returned from a call that didn't revert, like
+/// OpenZeppelin's SafeERC20. Tokens that don't return anything succeeded, as
+/// long as there's a token there, since calls to addresses without code
+/// always succeed. Tokens that return something have to return at least a
+/// word, which succeeded if it isn't zero. Anything after it is ignored.
+fn check_return_data(rd: &[u8], has_code: impl FnOnce() -> bool) -> Result<(), Error> {
+    if rd.is_empty() {
+        return match has_code() {
+            true => Ok(()),
+            false => Err(Error::Erc20NoCode),
+        };
+    }
+    match rd.get(0..32).map(U256::from_be_slice) {
+        None => Err(Error::Erc20BadReturnData),
+        Some(U256::ZERO) => Err(Error::Erc20ReturnedFalse),
+        Some(_) => Ok(()),
+    }
+}
+
+/// Call a token that may or may not return a boolean, failing if the call
+/// reverted or the token didn't return true.
+#[cfg(target_arch = "wasm32")]
+fn call_optional_return(token: Address, data: &[u8]) -> Result<(), Error> {
+    let rd = RawCall::new()
+        .call(token, data)
+        .map_err(|_| Error::Erc20Revert)?;
+    check_return_data(&rd, || token.has_code())
+}
+
+/// Pass the call to the mock tokens in [host], which check the transfer
+/// against the balances they track.
+#[cfg(not(target_arch = "wasm32"))]
+fn call_optional_return(token: Address, data: &[u8]) -> Result<(), Error> {
+    let rd = host::call_erc20(token, data).map_err(|_| Error::Erc20Revert)?;
+    check_return_data(&rd, || host::erc20_has_code(token))
+}
+
+/// Take tokens from the sender using `transferFrom`. Requires the sender
+/// to have approved Leo.
+pub fn take(token: Address, amount: U256) -> Result<(), Error> {
+    call_optional_return(
+        token,
+        &pack_transfer_from(msg::sender(), contract::address(), amount),
+    )
 }
 
-pub fn give(token: Address, amount: U256) -> Result<(), Vec<u8>> {
-    Ok(())
+/// Send tokens held by Leo to the recipient.
+pub fn give(token: Address, recipient: Address, amount: U256) -> Result<(), Error> {
+    call_optional_return(token, &pack_transfer(recipient, amount))
 }
@@ -73,6 +73,46 @@ pub enum Error {
     // 11 (0x0b)
     #[error("Position has no liquidity!")]
     PositionHasNoLiquidity,
+
+    /// A call to Seawater reverted.
+    // 12 (0x0c)
+    #[error("Seawater call reverted")]
+    SeawaterCallFailed,
+
+    /// Seawater returned data we couldn't decode.
+    // 13 (0x0d)
+    #[error("Seawater returned bad data")]
+    SeawaterBadReturnData,
+
+    /// The position was vested for a different pool.
+    // 14 (0x0e)
+    #[error("Position is for a different pool")]
+    PositionWrongPool,
+
+    /// A call to an ERC20 token reverted.
+    // 15 (0x0f)
+    #[error("ERC20 call reverted")]
+    Erc20Revert,
+
+    /// An ERC20 token returned false from a transfer.
+    // 16 (0x10)
+    #[error("ERC20 call returned false")]
+    Erc20ReturnedFalse,
+
+    /// The campaign hasn't finished yet.
+    // 17 (0x11)
+    #[error("Campaign is still ongoing")]
+    CampaignOngoing,
+
+    /// An ERC20 call returned nothing, and there's no token at the address.
+    // 18 (0x12)
+    #[error("ERC20 token has no code")]
+    Erc20NoCode,
+
+    /// An ERC20 token returned something shorter than a word.
+    // 19 (0x13)
+    #[error("ERC20 call returned bad data")]
+    Erc20BadReturnData,
 }
 
 impl From<Error> for Vec<u8> {
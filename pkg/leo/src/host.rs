@@ -4,13 +4,19 @@ use std::cell::RefCell;
 use std::ptr;
 use std::time;
 
+use crate::calldata::*;
+use crate::erc20;
+use crate::seawater;
 use crate::StorageNew;
 
-use stylus_sdk::alloy_primitives::{Address, U256};
+use stylus_sdk::alloy_primitives::{address, Address, U256};
 
 const WORD_BYTES: usize = 32;
 pub type Word = [u8; WORD_BYTES];
 
+/// The address of Leo in tests, which holds the campaign tokens.
+pub const CONTRACT_ADDR: Address = address!("feb6034fc7df27df18a3a6bad5fb94c0d3dcb6d5");
+
 thread_local! {
     pub static CURRENT_SENDER: RefCell<[u8; 20]> =
         const { RefCell::new([0; 20]) };
@@ -20,6 +26,18 @@ thread_local! {
     pub static POSITIONS: RefCell<HashMap<U256, (Address, i32, i32, U256)>> = RefCell::new(HashMap::new());
 
     pub static CURRENT_TIME: RefCell<u64> = RefCell::new(0);
+
+    // position id => fees collectable from Seawater
+    pub static POSITION_FEES: RefCell<HashMap<U256, (u128, u128)>> = RefCell::new(HashMap::new());
+
+    // (pool, lower tick, upper tick) => seconds the price spent outside the range
+    pub static SECONDS_OUTSIDE: RefCell<HashMap<(Address, i32, i32), u32>> = RefCell::new(HashMap::new());
+
+    // (token, owner) => balance received from transfers
+    pub static ERC20_BALANCES: RefCell<HashMap<(Address, Address), U256>> = RefCell::new(HashMap::new());
+
+    // token => what it returns from transfers instead of true, and if it has code
+    pub static ERC20_RETURNS: RefCell<HashMap<Address, (Vec<u8>, bool)>> = RefCell::new(HashMap::new());
 }
 
 unsafe fn read_word(key: *const u8) -> Word {
@@ -98,6 +116,11 @@ pub unsafe extern "C" fn storage_load_bytes32(key: *const u8, out: *mut u8) {
 #[no_mangle]
 pub unsafe extern "C" fn msg_sender(_sender: *mut u8) {}
 
+#[no_mangle]
+pub unsafe extern "C" fn contract_address(address: *mut u8) {
+    ptr::copy(CONTRACT_ADDR.as_ptr(), address, 20);
+}
+
 pub fn position_tick_lower(id: U256) -> Option<i32> {
     POSITIONS.with(|p| p.borrow().get(&id).map(|(_, t, _, _)| *t))
 }
@@ -110,6 +133,150 @@ pub fn position_liquidity(id: U256) -> Option<U256> {
     POSITIONS.with(|p| p.borrow().get(&id).map(|(_, _, _, l)| *l))
 }
 
+pub fn set_position_fees(id: U256, amount_0: u128, amount_1: u128) {
+    POSITION_FEES.with(|f| f.borrow_mut().insert(id, (amount_0, amount_1)));
+}
+
+/// Move the block timestamp forwards by a number of seconds.
+pub fn advance_time(secs: u64) {
+    CURRENT_TIME.with(|t| *t.borrow_mut() += secs);
+}
+
+/// Move the block timestamp forwards by a number of seconds, with the pool's
+/// price outside a range for all of them.
+pub fn advance_time_out_of_range(pool: Address, lower: i32, upper: i32, secs: u64) {
+    advance_time(secs);
+    SECONDS_OUTSIDE
+        .with(|s| *s.borrow_mut().entry((pool, lower, upper)).or_default() += secs as u32);
+}
+
+/// Answer a call to the seconds per liquidity inside function, counting the
+/// range as in range unless told otherwise with [advance_time_out_of_range].
+/// Leo only uses the seconds, so the seconds per liquidity is always zero.
+fn seconds_per_liquidity_inside(pool: Address, args: &[u8]) -> Result<Vec<u8>, Vec<u8>> {
+    let lower = read_i32(args, 1).ok_or(vec![])?;
+    let upper = read_i32(args, 2).ok_or(vec![])?;
+
+    // The ticks are only initialised if a position is using them.
+    let in_use = POSITIONS.with(|p| {
+        p.borrow()
+            .values()
+            .any(|&(p, l, u, _)| (p, l, u) == (pool, lower, upper))
+    });
+    if !in_use {
+        return Err(vec![]);
+    }
+
+    let seconds_outside = SECONDS_OUTSIDE.with(|s| {
+        s.borrow()
+            .get(&(pool, lower, upper))
+            .copied()
+            .unwrap_or_default()
+    });
+    let seconds_inside = (block_timestamp() as u32).wrapping_sub(seconds_outside);
+    Ok([[0; WORD_BYTES], encode_u128(seconds_inside as u128)].concat())
+}
+
+fn encode_i32(int: i32) -> Word {
+    let mut word = match int < 0 {
+        true => [0xff; WORD_BYTES],
+        false => [0; WORD_BYTES],
+    };
+    word[28..].copy_from_slice(&int.to_be_bytes());
+    word
+}
+
+fn encode_u128(int: u128) -> Word {
+    U256::from(int).to_be_bytes::<WORD_BYTES>()
+}
+
+/// Pretend to be Seawater, answering calls to the position functions using
+/// the positions set up with [with_storage]. Reverts with no data if the
+/// call isn't understood, or the position isn't in the pool.
+pub fn call_seawater(data: &[u8]) -> Result<Vec<u8>, Vec<u8>> {
+    let (selector, args) = (data.get(..4).ok_or(vec![])?, &data[4..]);
+    let pool = read_address(args, 0).ok_or(vec![])?;
+    if selector == seawater::SECONDS_PER_LIQUIDITY_INSIDE_SELECTOR {
+        return seconds_per_liquidity_inside(pool, args);
+    }
+    let id = read_u256(args, 1).ok_or(vec![])?;
+
+    let (position_pool, lower, upper, liquidity) = POSITIONS
+        .with(|p| p.borrow().get(&id).copied())
+        .ok_or(vec![])?;
+    if position_pool != pool {
+        return Err(vec![]);
+    }
+
+    let words = match selector.try_into().unwrap() {
+        seawater::TICK_LOWER_SELECTOR => vec![encode_i32(lower)],
+        seawater::TICK_UPPER_SELECTOR => vec![encode_i32(upper)],
+        seawater::POSITION_LIQUIDITY_SELECTOR => vec![liquidity.to_be_bytes::<WORD_BYTES>()],
+        seawater::COLLECT_YIELD_SINGLE_TO_SELECTOR => {
+            read_address(args, 2).ok_or(vec![])?;
+            let (amount_0, amount_1) = POSITION_FEES
+                .with(|f| f.borrow_mut().remove(&id))
+                .unwrap_or_default();
+            vec![encode_u128(amount_0), encode_u128(amount_1)]
+        }
+        _ => return Err(vec![]),
+    };
+
+    Ok(words.concat())
+}
+
+/// Get the balance of a token that an address received in the test.
+pub fn erc20_balance(token: Address, owner: Address) -> U256 {
+    ERC20_BALANCES.with(|b| b.borrow().get(&(token, owner)).copied().unwrap_or_default())
+}
+
+/// Make a token return something other than true from its transfers, and
+/// say if there's code at its address.
+pub fn set_erc20_return(token: Address, data: Vec<u8>, has_code: bool) {
+    ERC20_RETURNS.with(|r| r.borrow_mut().insert(token, (data, has_code)));
+}
+
+/// Tokens have code unless told otherwise with [set_erc20_return].
+pub fn erc20_has_code(token: Address) -> bool {
+    ERC20_RETURNS.with(|r| r.borrow().get(&token).map_or(true, |(_, code)| *code))
+}
+
+/// Pretend to be an ERC20 token, answering calls to transfer and
+/// transferFrom. Addresses other than Leo have as many tokens as they need,
+/// so only Leo's balance is checked, which it has to have been sent first.
+/// Reverts with no data if the call isn't understood, or Leo can't afford it.
+pub fn call_erc20(token: Address, data: &[u8]) -> Result<Vec<u8>, Vec<u8>> {
+    let (selector, args) = (data.get(..4).ok_or(vec![])?, &data[4..]);
+    let (from, to, amount) = match selector.try_into().unwrap() {
+        erc20::TRANSFER_SELECTOR => (
+            CONTRACT_ADDR,
+            read_address(args, 0).ok_or(vec![])?,
+            read_u256(args, 1).ok_or(vec![])?,
+        ),
+        erc20::TRANSFER_FROM_SELECTOR => (
+            read_address(args, 0).ok_or(vec![])?,
+            read_address(args, 1).ok_or(vec![])?,
+            read_u256(args, 2).ok_or(vec![])?,
+        ),
+        _ => return Err(vec![]),
+    };
+
+    ERC20_BALANCES.with(|b| -> Result<(), Vec<u8>> {
+        let mut balances = b.borrow_mut();
+        if from == CONTRACT_ADDR {
+            let balance = balances.entry((token, from)).or_default();
+            *balance = balance.checked_sub(amount).ok_or(vec![])?;
+        }
+        *balances.entry((token, to)).or_default() += amount;
+        Ok(())
+    })?;
+
+    // Return true like a compliant token, unless told otherwise.
+    Ok(ERC20_RETURNS
+        .with(|r| r.borrow().get(&token).map(|(data, _)| data.clone()))
+        .unwrap_or_else(|| encode_u128(1).to_vec()))
+}
+
 // Helper function for getting the actual timestamp, not the cached value.
 pub fn current_timestamp() -> u64 {
     time::SystemTime::now()
@@ -128,6 +295,10 @@ pub fn with_storage<T, P: StorageNew, F: FnOnce(&mut P) -> T>(
         let mut ts = t.borrow_mut();
         *ts = current_timestamp();
     });
+    POSITION_FEES.with(|fees| fees.borrow_mut().clear());
+    SECONDS_OUTSIDE.with(|seconds| seconds.borrow_mut().clear());
+    ERC20_BALANCES.with(|balances| balances.borrow_mut().clear());
+    ERC20_RETURNS.with(|returns| returns.borrow_mut().clear());
     POSITIONS.with(|positions| {
         let mut h = positions.borrow_mut();
         h.clear();
@@ -3,11 +3,12 @@
 
 use stylus_sdk::{
     alloy_primitives::{aliases::*, *},
-    block, evm, msg,
+    evm, msg,
     prelude::*,
     storage::*,
 };
 
+pub mod block;
 pub mod calldata;
 pub mod erc20;
 pub mod error;
@@ -77,6 +78,10 @@ pub struct StorageCampaignBal {
 
     // Amount that was already distributed.
     distributed: StorageU256,
+
+    // Amount credited to positions so far, including what they haven't
+    // collected yet. Never exceeds the maximum.
+    allocated: StorageU256,
 }
 
 #[solidity_storage]
@@ -96,6 +101,19 @@ pub struct StorageCampaign {
     // The timestamp of when this campaign ended. May be modified
     // if updates are made to the existing campaign.
     ending: StorageU64,
+
+    // Liquidity of the positions earning rewards from this version.
+    liquidity: StorageU256,
+
+    // Rewards earned by each unit of liquidity in this version so far, as a Q128.
+    rewards_per_liquidity: StorageU256,
+
+    // The timestamp the rewards per liquidity was last brought up to date.
+    last_updated: StorageU64,
+
+    // Whether the positions earning from the previous version were carried
+    // over to this one, which happens if the range grew to include the old one.
+    carried_over: StorageBool,
 }
 
 #[solidity_storage]
@@ -112,8 +130,33 @@ pub struct StoragePosition {
 
     liquidity: StorageU256,
 
-    // Indexes of the position of the current status per campaign that's updated
-    offsets: StorageMap<CampaignId, StorageU256>,
+    // Campaigns the position has earned rewards from, so divesting can
+    // leave them all.
+    campaigns: StorageVec<StorageFixedBytes<8>>,
+
+    // campaign id => the position's stake in the campaign
+    stakes: StorageMap<CampaignId, StorageStake>,
+}
+
+#[solidity_storage]
+pub struct StorageStake {
+    // Whether the campaign was added to the position's campaigns.
+    tracked: StorageBool,
+
+    // Whether the position's liquidity is counted in the campaign version.
+    joined: StorageBool,
+
+    // The campaign version the position is earning rewards from.
+    version: StorageU256,
+
+    // The version's rewards per liquidity when the position last settled.
+    rewards_per_liquidity: StorageU256,
+
+    // The timestamp the position last settled.
+    settled: StorageU64,
+
+    // Seawater's seconds inside the position's range when it last settled.
+    seconds_inside: StorageU32,
 }
 
 #[external]
@@ -128,15 +171,22 @@ impl Leo {
 
     // Take a user's LP NFT using the NFT Manager, also recording the
     // pool they LP'd, including the timestamp when they deposited it
-    // here. This also serves as the time it was last updated.
-    pub fn vest_position(&mut self, pool: Address, id: U256) -> Result<(), Vec<u8>> {
+    // here. This also serves as the time it was last updated. The
+    // position starts earning from the campaigns given that it's
+    // eligible for.
+    pub fn vest_position(
+        &mut self,
+        pool: Address,
+        id: U256,
+        campaigns: Vec<CampaignId>,
+    ) -> Result<(), Vec<u8>> {
         // Just to be safe, check if we already have this position tracked.
         assert_or!(
             self.positions.get(id).timestamp.get().is_zero(),
             Error::PositionAlreadyExists
         );
 
-        let position_liq = seawater::position_liquidity(pool, id);
+        let position_liq = seawater::position_liquidity(pool, id)?;
         assert_or!(!position_liq.is_zero(), Error::PositionHasNoLiquidity);
 
         nft_manager::take_position(id);
@@ -146,8 +196,8 @@ impl Leo {
         position.owner.set(msg::sender());
         position.timestamp.set(U64::from(block::timestamp()));
         position.token.set(pool);
-        position.tick_lower.set(seawater::tick_lower(pool, id));
-        position.tick_upper.set(seawater::tick_upper(pool, id));
+        position.tick_lower.set(seawater::tick_lower(pool, id)?);
+        position.tick_upper.set(seawater::tick_upper(pool, id)?);
 
         // Also increase the global count for LP available for this pool.
         position.liquidity.set(position_liq);
@@ -155,6 +205,11 @@ impl Leo {
         self.liquidity
             .setter(pool)
             .set(existing_liq + U256::from(position_liq));
+
+        for campaign_id in campaigns {
+            self.join_campaign(pool, id, campaign_id)?;
+        }
+
         Ok(())
     }
 
@@ -219,7 +274,7 @@ impl Leo {
             campaign_bal.maximum.set(new_maximum);
 
             // Take the token's amounts for the campaign.
-            erc20::take(pool, extra_max)?;
+            erc20::take(token, extra_max)?;
 
             evm::log(events::CampaignBalanceUpdated {
                 identifier: identifier.as_slice().try_into().unwrap(),
@@ -271,24 +326,36 @@ impl Leo {
         assert_or!(starting >= block::timestamp(), Error::BadCampaignConfig);
         assert_or!(ending > block::timestamp(), Error::BadCampaignConfig);
 
+        let campaign_versions_len = self.campaigns.getter(pool).ongoing.getter(identifier).len();
+        assert_or!(campaign_versions_len > 0, Error::NoCampaign);
+
+        // Credit the rewards of the previous campaign up to now, before it ends.
+        self.accrue_campaign(pool, identifier, campaign_versions_len - 1);
+
         // Push to the campaign versions the new content, setting the previous
         // campaign's ending timestamp to the current timestamp.
         let ongoing_campaigns = &mut self.campaigns.setter(pool).ongoing;
         let mut campaign_versions = ongoing_campaigns.setter(identifier);
-        let campaign_versions_len = campaign_versions.len();
-        assert_or!(!campaign_versions.is_empty(), Error::NoCampaign);
-        campaign_versions
-            .setter(campaign_versions_len - 1)
-            .unwrap()
+        let mut previous = campaign_versions.setter(campaign_versions_len - 1).unwrap();
+        let previous_ending = previous.ending.get();
+        previous
             .ending
-            .set(U64::from(block::timestamp()));
+            .set(U64::min(previous_ending, U64::from(block::timestamp())));
+
+        // Positions earning from the previous campaign are still eligible if
+        // the range grew, so they carry on earning without having to rejoin.
+        let lower = I32::from_le_bytes(tick_lower.to_le_bytes());
+        let upper = I32::from_le_bytes(tick_upper.to_le_bytes());
+        let carried_over = lower <= previous.tick_lower.get() && upper >= previous.tick_upper.get();
+        let previous_liquidity = previous.liquidity.get();
+
         let mut campaign = campaign_versions.grow();
-        campaign
-            .tick_lower
-            .set(I32::from_le_bytes(tick_lower.to_le_bytes()));
-        campaign
-            .tick_upper
-            .set(I32::from_le_bytes(tick_upper.to_le_bytes()));
+        if carried_over {
+            campaign.carried_over.set(true);
+            campaign.liquidity.set(previous_liquidity);
+        }
+        campaign.tick_lower.set(lower);
+        campaign.tick_upper.set(upper);
         campaign.per_second.set(per_second);
         campaign
             .starting
@@ -304,7 +371,7 @@ impl Leo {
             campaign_bal.maximum.set(new_maximum);
 
             // Take the token's amounts for the campaign.
-            erc20::take(pool, extra_max)?;
+            erc20::take(campaign_bal.token.get(), extra_max)?;
 
             evm::log(events::CampaignBalanceUpdated {
                 identifier: identifier.as_slice().try_into().unwrap(),
@@ -329,20 +396,74 @@ impl Leo {
             self.campaign_balances.getter(identifier).owner.get(),
             msg::sender()
         );
+        let campaign_versions_len = self.campaigns.getter(pool).ongoing.getter(identifier).len();
+        assert_or!(campaign_versions_len > 0, Error::NoCampaign);
+
+        // Credit the rewards of the campaign up to now, before it ends.
+        self.accrue_campaign(pool, identifier, campaign_versions_len - 1);
+
         let ongoing_campaigns = &mut self.campaigns.setter(pool).ongoing;
         let mut campaign_versions = ongoing_campaigns.setter(identifier);
-        let campaign_versions_len = campaign_versions.len();
-        assert_or!(!campaign_versions.is_empty(), Error::NoCampaign);
-        campaign_versions
-            .setter(campaign_versions_len - 1)
-            .unwrap()
+        let mut campaign = campaign_versions.setter(campaign_versions_len - 1).unwrap();
+        let ending = campaign.ending.get();
+        campaign
             .ending
-            .set(U64::from(block::timestamp()));
+            .set(U64::min(ending, U64::from(block::timestamp())));
         campaign_versions.grow(); // Grow with an empty value so it's 0 for all!
         events::emit_campaign_updated(identifier, pool, U256::ZERO, 0, 0, 0, 0);
         Ok(())
     }
 
+    /// Send the tokens that the campaign never credited to positions back
+    /// to the campaign owner's recipient, once the campaign has finished,
+    /// returning the amount sent. Rewards that positions haven't collected
+    /// yet stay behind for them, and any rewards they forfeit later for
+    /// time out of range can be withdrawn again.
+    pub fn withdraw_campaign_remainder(
+        &mut self,
+        pool: Address,
+        identifier: CampaignId,
+        recipient: Address,
+    ) -> Result<U256, Vec<u8>> {
+        assert_or!(
+            self.campaign_balances.getter(identifier).owner.get() == msg::sender(),
+            Error::NotCampaignOwner
+        );
+        let campaign_versions_len = self.campaigns.getter(pool).ongoing.getter(identifier).len();
+        assert_or!(campaign_versions_len > 0, Error::NoCampaign);
+
+        let ending = {
+            let pool_campaigns = self.campaigns.getter(pool);
+            let campaign_versions = pool_campaigns.ongoing.getter(identifier);
+            let campaign = campaign_versions.getter(campaign_versions_len - 1).unwrap();
+            campaign.ending.get()
+        };
+        assert_or!(
+            ending <= U64::from(block::timestamp()),
+            Error::CampaignOngoing
+        );
+
+        // Credit the rewards of the campaign up to its end, so positions
+        // keep what they earned.
+        self.accrue_campaign(pool, identifier, campaign_versions_len - 1);
+
+        let mut campaign_bal = self.campaign_balances.setter(identifier);
+        let allocated = campaign_bal.allocated.get();
+        let remainder = campaign_bal.maximum.get() - allocated;
+        if remainder.is_zero() {
+            return Ok(U256::ZERO);
+        }
+        campaign_bal.maximum.set(allocated);
+        erc20::give(campaign_bal.token.get(), recipient, remainder)?;
+
+        evm::log(events::CampaignBalanceUpdated {
+            identifier: identifier.as_slice().try_into().unwrap(),
+            newMaximum: allocated,
+        });
+
+        Ok(remainder)
+    }
+
     /// Return campaign details, of the form the lower tick, the upper tick,
     /// the amount sent per second in the campaign, the token that's being
     /// distributed, and the amount distributed so far, as well as the maximum
@@ -382,176 +503,81 @@ impl Leo {
             self.positions.get(id).owner.get() == msg::sender(),
             Error::NotPositionOwner
         );
-        let (amount_0, amount_1) = seawater::collect_yield_single_to(id, pool, msg::sender());
+        let (amount_0, amount_1) = seawater::collect_yield_single_to(id, pool, msg::sender())?;
         Ok((amount_0, amount_1))
     }
 
-    // Return the LP rewards paid by Leo for vesting this NFT position.
-    // Update the current position of the user per campaign that's
-    // ongoing, and send them rewards using the time that was spent in
-    // each campaign setting before the update occured. An update to a
-    // campaign is tracked by updating its end date to earlier so the lp
-    // rewards code attempts to roll over. In doing so, update the
-    // timestamp to reset the rewards they've earned so far, and set them
-    // to the latest version of each campaign update.
+    // Send the LP rewards paid by Leo for vesting this NFT position to the
+    // recipient, returning what was sent per campaign. Positions that haven't
+    // earned from a campaign yet start earning from it if they're
+    // eligible. If the campaign was updated since the position last
+    // collected, the position follows it if the new range includes the
+    // old one, and otherwise it has to be eligible for the new range to
+    // keep earning. Rewards are only paid for the time the pool's price
+    // was in the position's range.
     pub fn collect_lp_rewards(
         &mut self,
         pool: Address,
         position_id: U256,
         campaign_ids: Vec<CampaignId>,
+        recipient: Address,
     ) -> Result<Vec<(Address, U256)>, Vec<u8>> {
         assert_or!(self.enabled.get(), Error::NotEnabled);
         assert_or!(
             self.positions.getter(position_id).owner.get() == msg::sender(),
             Error::NotPositionOwner
         );
-
-        // Iterate through every copy of the campaign details until we pass the ending.
-        let mut position = self.positions.setter(position_id);
-        let position_tick_lower = position.tick_lower.get();
-        let position_tick_upper = position.tick_upper.get();
-        let position_liquidity = position.liquidity.get();
-
-        let position_token = position.token.get();
+        assert_or!(
+            self.positions.getter(position_id).token.get() == pool,
+            Error::PositionWrongPool
+        );
 
         // Track amounts owed to this array to return.
         let mut owed = Vec::new();
 
         for campaign_id in campaign_ids {
-            let offsets = position.offsets.getter(campaign_id);
-            let mut offset = offsets.get();
-            let campaigns = &self.campaigns;
-            let campaigns_ongoing = &campaigns.getter(pool).ongoing;
-            let campaign_versions = campaigns_ongoing.getter(campaign_id);
-
-            let campaign_bal = self.campaign_balances.getter(campaign_id);
-            let campaign_token = campaign_bal.token.get();
-            let campaign_maximum = campaign_bal.maximum.get();
-
-            // Weird issues could come up if the campaign maximum is empty.
-            assert_or!(campaign_maximum > U256::ZERO, Error::CampaignMaxEmpty);
-
-            // The amount distributed in this campaign, mutable in a way that
-            // lets us set it later.
-            let mut distributed = self.campaign_balances.setter(campaign_id).distributed.get();
-
-            let mut cur_timestamp = U64::from(block::timestamp());
-
-            loop {
-                eprintln!("offset: {offset}, cur timestamp: {cur_timestamp}");
-
-                let campaign_updates = campaign_versions.getter(offset);
-
-                eprintln!("campaign offset is some? {}", campaign_updates.is_some());
-
-                if campaign_updates.is_none() {
-                    break;
-                }
-
-                let campaign = campaign_updates.unwrap();
-
-                let campaign_starting = campaign.starting.get();
-                let campaign_ending = campaign.ending.get();
-
-                eprintln!("campaign starting offset {offset}, starting: {campaign_starting}, ending {campaign_ending}, current timestamp: {}", block::timestamp());
-
-                if campaign_ending.is_zero() {
-                    // We should terminate, the campaign was cancelled.
-                    break;
-                }
-
-                // If we've exceeded or are equal to the ending date of
-                // the campaign, we assume it's finished.
-                if cur_timestamp >= campaign_ending {
-                    offset += U256::from(1);
-                    continue;
-                }
-
-                // Set the timestamp to either the ending timestamp for the current campaign,
-                // or the block timestamp.
-                let clamped_timestamp = U64::min(campaign_ending, cur_timestamp);
-
-                // If this campaign hasn't started, we need to terminate so the user can wait.
-                if clamped_timestamp < campaign_starting {
-                    break;
-                }
-
-                // Go to the next campaign iteration, hoping that an update might take place
-                // that makes the user eligible.
-                let should_skip = position_tick_lower < campaign.tick_lower.get()
-                    || position_tick_upper > campaign.tick_upper.get();
-                if should_skip {
-                    eprintln!("sholud skip?");
-                    offset += U256::from(1);
-                    continue;
-                }
-
-                // Since we're continuing, we figure out what the user is owed, and we set the
-                // timestamp to the ending of this campaign.
-                let clamped_secs_since = clamped_timestamp - campaign_starting;
-
-                if clamped_secs_since <= U64::ZERO {
-                    break;
-                }
-
-                eprintln!(
-                    "pool lp: {}, position liquidity: {position_liquidity}, campaign per sec: {}, secs since {clamped_secs_since}, campaign starting: {campaign_starting}, current timestamp: {cur_timestamp}",
-                    self.liquidity.getter(pool).get(),
-                    campaign.per_second.get(),
-                );
-
-                let base_rewards = maths::calc_base_rewards(
-                    self.liquidity.getter(pool).get(), // Pool LP
-                    position_liquidity,                // User LP
-                    campaign.per_second.get(),         // Campaign rewards per sec
-                );
-
-                let rewards = base_rewards * U256::from(clamped_secs_since);
-                owed.push((campaign_token, rewards));
-
-                // Use the minimum of the existing current timestamp or the ending timestamp.
-                cur_timestamp = clamped_timestamp;
-                distributed += rewards;
-
-                // Extra protection incase we blow past the amount that should be allocated somehow.
-                assert_or!(
-                    distributed < self.campaign_balances.getter(campaign_id).maximum.get(),
-                    Error::CampaignDistributedCompletely
-                );
-
-                // Since we made it to the end of this campaign for now, break out.
-                break;
+            let rewards = self.settle_campaign(pool, position_id, campaign_id)?;
+            if !rewards.is_zero() {
+                let token = self.pay_campaign(campaign_id, recipient, rewards)?;
+                owed.push((token, rewards));
             }
-
-            // Update the position's tracked last claim timestamp.
-            position.timestamp.set(U64::from(block::timestamp()));
-
-            // Update what we've sent out so far!
-            self.campaign_balances
-                .setter(campaign_id)
-                .distributed
-                .set(distributed);
         }
 
+        // Update the position's tracked last claim timestamp.
+        self.positions
+            .setter(position_id)
+            .timestamp
+            .set(U64::from(block::timestamp()));
+
         Ok(owed)
     }
 
     // Divest LP positions from this contract, sending them back to the
-    // original owner.
+    // original owner, and sending the rewards owed from every campaign
+    // they were earning from to the recipient.
     pub fn divest_position(
         &mut self,
         pool: Address,
         position_id: U256,
-        campaigns: Vec<CampaignId>,
+        recipient: Address,
     ) -> Result<(), Vec<u8>> {
         assert_or!(self.enabled.get(), Error::NotEnabled);
-        // Check if the user owns this position. Do this even though the
-        // claim LP function would do this, just incase they pass zero campaigns.
         assert_or!(
             self.positions.getter(position_id).owner.get() == msg::sender(),
             Error::NotPositionOwner
         );
-        self.collect_lp_rewards(pool, position_id, campaigns)?;
+        assert_or!(
+            self.positions.getter(position_id).token.get() == pool,
+            Error::PositionWrongPool
+        );
+        let campaigns_len = self.positions.getter(position_id).campaigns.len();
+        for i in 0..campaigns_len {
+            let campaign_id = self.positions.getter(position_id).campaigns.get(i).unwrap();
+            let rewards = self.leave_campaign(pool, position_id, campaign_id)?;
+            if !rewards.is_zero() {
+                self.pay_campaign(campaign_id, recipient, rewards)?;
+            }
+        }
         // This should be enough to zero out the position.
         self.positions.setter(position_id).owner.set(Address::ZERO);
         let existing_liq = self.liquidity.getter(pool).get();
@@ -602,6 +628,291 @@ impl Leo {
     }
 }
 
+impl Leo {
+    /// Bring the rewards per liquidity of a campaign version up to now,
+    /// returning it. The rewards emitted are split between the liquidity
+    /// earning from the version, and are capped so the campaign never
+    /// credits more than its maximum. Rewards aren't credited while no
+    /// liquidity is earning, or if the rewards per liquidity would overflow,
+    /// so they stay with the campaign.
+    fn accrue_campaign(&mut self, pool: Address, id: CampaignId, version: usize) -> U256 {
+        let mut campaign_bal = self.campaign_balances.setter(id);
+        let mut pool_campaigns = self.campaigns.setter(pool);
+        let mut campaign_versions = pool_campaigns.ongoing.setter(id);
+        let mut campaign = campaign_versions.setter(version).unwrap();
+
+        let rewards_per_liquidity = campaign.rewards_per_liquidity.get();
+        let from = U64::max(campaign.last_updated.get(), campaign.starting.get());
+        let until = U64::min(campaign.ending.get(), U64::from(block::timestamp()));
+        if until <= from {
+            return rewards_per_liquidity;
+        }
+        campaign.last_updated.set(until);
+
+        let liquidity = campaign.liquidity.get();
+        if liquidity.is_zero() {
+            return rewards_per_liquidity;
+        }
+
+        let allocated = campaign_bal.allocated.get();
+        let rewards = U256::min(
+            campaign
+                .per_second
+                .get()
+                .saturating_mul(U256::from(until - from)),
+            campaign_bal.maximum.get().saturating_sub(allocated),
+        );
+
+        let rewards_per_liquidity = match maths::calc_rewards_per_liquidity(rewards, liquidity)
+            .and_then(|growth| rewards_per_liquidity.checked_add(growth))
+        {
+            Some(rewards_per_liquidity) => rewards_per_liquidity,
+            None => return rewards_per_liquidity,
+        };
+        campaign_bal.allocated.set(allocated + rewards);
+        campaign.rewards_per_liquidity.set(rewards_per_liquidity);
+        rewards_per_liquidity
+    }
+
+    /// Start earning from the latest version of a campaign if the position is
+    /// in its range, adding the position's liquidity to the version.
+    fn join_campaign(
+        &mut self,
+        pool: Address,
+        position_id: U256,
+        id: CampaignId,
+    ) -> Result<(), Vec<u8>> {
+        let campaign_versions_len = self.campaigns.getter(pool).ongoing.getter(id).len();
+        assert_or!(campaign_versions_len > 0, Error::NoCampaign);
+        let version = campaign_versions_len - 1;
+
+        let position = self.positions.getter(position_id);
+        // Campaigns repeated in a list only count the position once.
+        if position.stakes.getter(id).joined.get() {
+            return Ok(());
+        }
+
+        let position_liquidity = position.liquidity.get();
+        let eligible = {
+            let pool_campaigns = self.campaigns.getter(pool);
+            let campaign_versions = pool_campaigns.ongoing.getter(id);
+            let campaign = campaign_versions.getter(version).unwrap();
+            // Cancelled campaigns are an empty version without an ending.
+            !campaign.ending.get().is_zero()
+                && position.tick_lower.get() >= campaign.tick_lower.get()
+                && position.tick_upper.get() <= campaign.tick_upper.get()
+        };
+        if !eligible {
+            return Ok(());
+        }
+
+        let rewards_per_liquidity = self.accrue_campaign(pool, id, version);
+        let seconds_inside = self.position_seconds_inside(pool, position_id)?;
+
+        let mut pool_campaigns = self.campaigns.setter(pool);
+        let mut campaign_versions = pool_campaigns.ongoing.setter(id);
+        let mut campaign = campaign_versions.setter(version).unwrap();
+        let campaign_liquidity = campaign.liquidity.get();
+        campaign
+            .liquidity
+            .set(campaign_liquidity + position_liquidity);
+
+        let mut position = self.positions.setter(position_id);
+        if !position.stakes.getter(id).tracked.get() {
+            position.campaigns.push(id);
+        }
+        let mut stake = position.stakes.setter(id);
+        stake.tracked.set(true);
+        stake.joined.set(true);
+        stake.version.set(U256::from(version));
+        stake.rewards_per_liquidity.set(rewards_per_liquidity);
+        stake.settled.set(U64::from(block::timestamp()));
+        stake.seconds_inside.set(U32::from(seconds_inside));
+        Ok(())
+    }
+
+    /// Return the rewards a position earned from a campaign since it last
+    /// settled, moving it along to the latest version of the campaign. If
+    /// the position wasn't carried over to a later version, or wasn't
+    /// earning from the campaign at all, it tries to join the latest version.
+    /// Positions are only paid for the time the pool's price was in their
+    /// range while each version was running, and the rest of what they earned
+    /// goes back to the campaign.
+    ///
+    /// Seawater only tells us the time in range since the position last
+    /// settled, so for each version we pay for the time in range that must
+    /// have been while it ran. Positions in range the whole time are paid in
+    /// full, and others are paid more exactly the more often they settle.
+    fn settle_campaign(
+        &mut self,
+        pool: Address,
+        position_id: U256,
+        id: CampaignId,
+    ) -> Result<U256, Vec<u8>> {
+        let campaign_versions_len = self.campaigns.getter(pool).ongoing.getter(id).len();
+        assert_or!(campaign_versions_len > 0, Error::NoCampaign);
+
+        let position = self.positions.getter(position_id);
+        let position_liquidity = position.liquidity.get();
+        let stake = position.stakes.getter(id);
+        if !stake.joined.get() {
+            self.join_campaign(pool, position_id, id)?;
+            return Ok(U256::ZERO);
+        }
+        let mut version = stake.version.get().to::<usize>();
+        let mut last_rewards_per_liquidity = stake.rewards_per_liquidity.get();
+        let settled = stake.settled.get();
+        let last_seconds_inside = stake.seconds_inside.get();
+
+        let now = U64::from(block::timestamp());
+        let seconds_inside = self.position_seconds_inside(pool, position_id)?;
+        let elapsed = now - settled;
+        // Seawater's seconds inside wrap, so we only trust them over the
+        // time that passed.
+        let in_range = U64::min(
+            U64::from(seconds_inside.wrapping_sub(last_seconds_inside.to::<u32>())),
+            elapsed,
+        );
+
+        let mut earned = U256::ZERO;
+        let mut owed = U256::ZERO;
+        let carried_over = loop {
+            let rewards_per_liquidity = self.accrue_campaign(pool, id, version);
+            let version_earned = maths::calc_rewards(
+                position_liquidity,
+                rewards_per_liquidity - last_rewards_per_liquidity,
+            );
+            last_rewards_per_liquidity = rewards_per_liquidity;
+
+            // The version only ran for part of the time since the position
+            // settled, and the price could've been in range for all of the
+            // rest of it.
+            let running = {
+                let pool_campaigns = self.campaigns.getter(pool);
+                let campaign_versions = pool_campaigns.ongoing.getter(id);
+                let campaign = campaign_versions.getter(version).unwrap();
+                let from = U64::max(settled, campaign.starting.get());
+                let until = U64::min(now, campaign.ending.get());
+                until.saturating_sub(from)
+            };
+            if !running.is_zero() {
+                let in_range_running = (in_range + running).saturating_sub(elapsed);
+                owed += maths::_mul_div(
+                    version_earned,
+                    U256::from(in_range_running),
+                    U256::from(running),
+                );
+            }
+            earned += version_earned;
+
+            if version == campaign_versions_len - 1 {
+                break true;
+            }
+
+            // The position was counted in the next version since it was made
+            // if it was carried over, so it's owed all of its rewards.
+            version += 1;
+            last_rewards_per_liquidity = U256::ZERO;
+            let pool_campaigns = self.campaigns.getter(pool);
+            let campaign_versions = pool_campaigns.ongoing.getter(id);
+            if !campaign_versions
+                .getter(version)
+                .unwrap()
+                .carried_over
+                .get()
+            {
+                break false;
+            }
+        };
+
+        let mut campaign_bal = self.campaign_balances.setter(id);
+        let allocated = campaign_bal.allocated.get();
+        campaign_bal.allocated.set(allocated - (earned - owed));
+
+        let mut position = self.positions.setter(position_id);
+        let mut stake = position.stakes.setter(id);
+        if carried_over {
+            stake.version.set(U256::from(version));
+            stake.rewards_per_liquidity.set(last_rewards_per_liquidity);
+            stake.settled.set(now);
+            stake.seconds_inside.set(U32::from(seconds_inside));
+        } else {
+            stake.joined.set(false);
+            self.join_campaign(pool, position_id, id)?;
+        }
+
+        Ok(owed)
+    }
+
+    /// Stop earning from a campaign, returning the rewards owed.
+    fn leave_campaign(
+        &mut self,
+        pool: Address,
+        position_id: U256,
+        id: CampaignId,
+    ) -> Result<U256, Vec<u8>> {
+        let owed = self.settle_campaign(pool, position_id, id)?;
+
+        let position = self.positions.getter(position_id);
+        let position_liquidity = position.liquidity.get();
+        let stake = position.stakes.getter(id);
+        if !stake.joined.get() {
+            return Ok(owed);
+        }
+        let version = stake.version.get().to::<usize>();
+
+        let mut pool_campaigns = self.campaigns.setter(pool);
+        let mut campaign_versions = pool_campaigns.ongoing.setter(id);
+        let mut campaign = campaign_versions.setter(version).unwrap();
+        let campaign_liquidity = campaign.liquidity.get();
+        campaign
+            .liquidity
+            .set(campaign_liquidity - position_liquidity);
+
+        self.positions
+            .setter(position_id)
+            .stakes
+            .setter(id)
+            .joined
+            .set(false);
+
+        Ok(owed)
+    }
+
+    /// Get Seawater's seconds inside a position's range.
+    fn position_seconds_inside(&self, pool: Address, position_id: U256) -> Result<u32, Vec<u8>> {
+        let position = self.positions.getter(position_id);
+        let (_, seconds_inside) = seawater::seconds_per_liquidity_inside(
+            pool,
+            position.tick_lower.get(),
+            position.tick_upper.get(),
+        )?;
+        Ok(seconds_inside)
+    }
+
+    /// Send rewards from a campaign to the recipient, returning the token sent.
+    fn pay_campaign(
+        &mut self,
+        id: CampaignId,
+        recipient: Address,
+        amount: U256,
+    ) -> Result<Address, Vec<u8>> {
+        let mut campaign_bal = self.campaign_balances.setter(id);
+        let distributed = campaign_bal.distributed.get() + amount;
+
+        // Extra protection incase we blow past the amount that should be allocated somehow.
+        assert_or!(
+            distributed <= campaign_bal.allocated.get(),
+            Error::CampaignDistributedCompletely
+        );
+
+        campaign_bal.distributed.set(distributed);
+        let token = campaign_bal.token.get();
+        erc20::give(token, recipient, amount)?;
+        Ok(token)
+    }
+}
+
 pub trait StorageNew {
     fn new(i: U256, v: u8) -> Self;
 }
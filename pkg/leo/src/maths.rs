@@ -19,6 +19,36 @@ pub fn _mul_div(a: U256, b: U256, mut denom_and_rem: U256) -> U256 {
     U256::from_limbs_slice(&limbs[0..4])
 }
 
-pub fn calc_base_rewards(pool_lp: U256, our_lp: U256, rewards_per_sec: U256) -> U256 {
-    _mul_div(pool_lp, rewards_per_sec, our_lp)
+/// Returns `a * b / c`, or `None` if the result is too large to fit.
+pub fn checked_mul_div(a: U256, b: U256, mut denom_and_rem: U256) -> Option<U256> {
+    assert!(!denom_and_rem.is_zero());
+
+    let mut mul_and_quo = a.widening_mul::<256, 4, 512, 8>(b);
+
+    unsafe {
+        ruint::algorithms::div(mul_and_quo.as_limbs_mut(), denom_and_rem.as_limbs_mut());
+    }
+
+    let limbs = mul_and_quo.into_limbs();
+    if limbs[4..] != [0_u64; 4] {
+        return None;
+    }
+
+    Some(U256::from_limbs_slice(&limbs[0..4]))
+}
+
+/// Fixed point scale of the rewards per liquidity accumulators.
+pub const Q128: U256 = U256::from_limbs([0, 0, 1, 0]);
+
+/// Returns the rewards earned by each unit of liquidity if the rewards are
+/// split between all the liquidity, as a Q128, or `None` if it's too large
+/// to fit, which is possible with very little liquidity.
+pub fn calc_rewards_per_liquidity(rewards: U256, liquidity: U256) -> Option<U256> {
+    checked_mul_div(rewards, Q128, liquidity)
+}
+
+/// Returns the rewards earned by liquidity given the growth in the rewards
+/// per liquidity while it was staked, rounding down.
+pub fn calc_rewards(liquidity: U256, rewards_per_liquidity: U256) -> U256 {
+    _mul_div(liquidity, rewards_per_liquidity, Q128)
 }
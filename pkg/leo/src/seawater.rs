@@ -4,85 +4,102 @@ use stylus_sdk::alloy_primitives::{Address, I32, U256};
 use stylus_sdk::call::RawCall;
 
 #[allow(unused_imports)]
-use crate::{calldata::*, immutables::SEAWATER_ADDR};
+use crate::{calldata::*, error::Error, immutables::SEAWATER_ADDR};
 
 #[cfg(not(target_arch = "wasm32"))]
 use crate::host;
 
-//collectSingleTo720C50FF(address,uint256,address)
-#[allow(dead_code)]
-const COLLECT_YIELD_SINGLE_TO_SELECTOR: [u8; 4] = [0xd2, 0xb3, 0x16, 0x5c];
+//collectSingleTo6D76575F(address,uint256,address)
+pub const COLLECT_YIELD_SINGLE_TO_SELECTOR: [u8; 4] = [0x00, 0x00, 0x02, 0x87];
 
 //positionTickLower2F77CCE1(address,uint256)
-#[allow(dead_code)]
-const TICK_LOWER_SELECTOR: [u8; 4] = [0x00, 0x00, 0x02, 0xec];
+pub const TICK_LOWER_SELECTOR: [u8; 4] = [0x00, 0x00, 0x02, 0xec];
 
 //positionTickUpper67FD55BA(address,uint256)
-#[allow(dead_code)]
-const TICK_UPPER_SELECTOR: [u8; 4] = [0x00, 0x00, 0x02, 0x4a];
+pub const TICK_UPPER_SELECTOR: [u8; 4] = [0x00, 0x00, 0x02, 0x4a];
 
 //positionLiquidity8D11C045(address,uint256)
-#[allow(dead_code)]
-const POSITION_LIQUIDITY_SELECTOR: [u8; 4] = [0x00, 0x00, 0x02, 0x5b];
+pub const POSITION_LIQUIDITY_SELECTOR: [u8; 4] = [0x00, 0x00, 0x02, 0x5b];
 
+//secondsPerLiquidityInside11B8A254(address,int32,int32)
+pub const SECONDS_PER_LIQUIDITY_INSIDE_SELECTOR: [u8; 4] = [0x00, 0x00, 0x03, 0x6f];
+
+/// Call Seawater, returning its return data.
 #[cfg(target_arch = "wasm32")]
-/// Collect yield, using the [collect_7_F21947_C] function in Longtail.
-pub fn collect_yield_single_to(id: U256, pool: Address, recipient: Address) -> (u128, u128) {
-    let mut data = [0_u8; 4 + 32 * 3];
-    write_selector(&mut data, &COLLECT_YIELD_SINGLE_TO_SELECTOR);
-    write_u256(&mut data, 0, id);
-    write_address(&mut data, 1, pool);
-    write_address(&mut data, 2, recipient);
-    let rd = RawCall::new().call(SEAWATER_ADDR, &data).unwrap();
-    panic!("todo")
+fn call(data: &[u8]) -> Result<Vec<u8>, Error> {
+    RawCall::new()
+        .call(SEAWATER_ADDR, data)
+        .map_err(|_| Error::SeawaterCallFailed)
 }
 
+/// Call the mock Seawater in [host], returning its return data.
 #[cfg(not(target_arch = "wasm32"))]
-pub fn collect_yield_single_to(id: U256, pool: Address, recipient: Address) -> (u128, u128) {
-    (0, 0)
+fn call(data: &[u8]) -> Result<Vec<u8>, Error> {
+    host::call_seawater(data).map_err(|_| Error::SeawaterCallFailed)
 }
 
-#[cfg(target_arch = "wasm32")]
-pub fn tick_lower(pool: Address, id: U256) -> I32 {
+/// Call one of the position getters, which take the pool and the position id.
+fn call_position_getter(selector: &[u8; 4], pool: Address, id: U256) -> Result<Vec<u8>, Error> {
     let mut data = [0_u8; 4 + 32 * 2];
-    write_selector(&mut data, &TICK_LOWER_SELECTOR);
+    write_selector(&mut data, selector);
     write_address(&mut data, 0, pool);
     write_u256(&mut data, 1, id);
-    let t = RawCall::new().call(SEAWATER_ADDR, &data).unwrap();
-    panic!("TODO")
-}
-
-#[cfg(not(target_arch = "wasm32"))]
-pub fn tick_lower(_pool: Address, id: U256) -> I32 {
-    I32::try_from(host::position_tick_lower(id).unwrap()).unwrap()
+    call(&data)
 }
 
-#[cfg(target_arch = "wasm32")]
-pub fn tick_upper(pool: Address, id: U256) -> I32 {
-    let mut data = [0_u8; 4 + 32 * 2];
-    write_selector(&mut data, &TICK_UPPER_SELECTOR);
+/// Collect yield, using the [collect_single_to_6_D_76575_F] function in
+/// Seawater, sending it to the recipient.
+pub fn collect_yield_single_to(
+    id: U256,
+    pool: Address,
+    recipient: Address,
+) -> Result<(u128, u128), Error> {
+    let mut data = [0_u8; 4 + 32 * 3];
+    write_selector(&mut data, &COLLECT_YIELD_SINGLE_TO_SELECTOR);
     write_address(&mut data, 0, pool);
     write_u256(&mut data, 1, id);
-    RawCall::new().call(SEAWATER_ADDR, &data).unwrap();
-    I32::ZERO
+    write_address(&mut data, 2, recipient);
+    let rd = call(&data)?;
+    match (read_u128(&rd, 0), read_u128(&rd, 1)) {
+        (Some(amount_0), Some(amount_1)) => Ok((amount_0, amount_1)),
+        _ => Err(Error::SeawaterBadReturnData),
+    }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-pub fn tick_upper(pool: Address, id: U256) -> I32 {
-    I32::try_from(host::position_tick_upper(id).unwrap()).unwrap()
+pub fn tick_lower(pool: Address, id: U256) -> Result<I32, Error> {
+    let rd = call_position_getter(&TICK_LOWER_SELECTOR, pool, id)?;
+    let tick = read_i32(&rd, 0).ok_or(Error::SeawaterBadReturnData)?;
+    Ok(I32::from_le_bytes(tick.to_le_bytes()))
 }
 
-#[cfg(target_arch = "wasm32")]
-pub fn position_liquidity(pool: Address, id: U256) -> U256 {
-    let mut data = [0_u8; 4 + 32 * 2];
-    write_selector(&mut data, &POSITION_LIQUIDITY_SELECTOR);
-    write_address(&mut data, 0, pool);
-    write_u256(&mut data, 1, id);
-    let cd = RawCall::new().call(SEAWATER_ADDR, &data).unwrap();
-    panic!("TODO")
+pub fn tick_upper(pool: Address, id: U256) -> Result<I32, Error> {
+    let rd = call_position_getter(&TICK_UPPER_SELECTOR, pool, id)?;
+    let tick = read_i32(&rd, 0).ok_or(Error::SeawaterBadReturnData)?;
+    Ok(I32::from_le_bytes(tick.to_le_bytes()))
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-pub fn position_liquidity(_pool: Address, id: U256) -> U256 {
-    host::position_liquidity(id).unwrap()
+pub fn position_liquidity(pool: Address, id: U256) -> Result<U256, Error> {
+    let rd = call_position_getter(&POSITION_LIQUIDITY_SELECTOR, pool, id)?;
+    // Seawater returns a uint128, but we track liquidity as a word anyway.
+    read_u256(&rd, 0).ok_or(Error::SeawaterBadReturnData)
+}
+
+/// Get the seconds per liquidity and the seconds that the pool's price has
+/// spent inside a range. Only meaningful compared to an earlier value, taken
+/// while a position over the range had liquidity.
+pub fn seconds_per_liquidity_inside(
+    pool: Address,
+    lower: I32,
+    upper: I32,
+) -> Result<(U256, u32), Error> {
+    let mut data = [0_u8; 4 + 32 * 3];
+    write_selector(&mut data, &SECONDS_PER_LIQUIDITY_INSIDE_SELECTOR);
+    write_address(&mut data, 0, pool);
+    write_i32(&mut data, 1, i32::from_le_bytes(lower.to_le_bytes()));
+    write_i32(&mut data, 2, i32::from_le_bytes(upper.to_le_bytes()));
+    let rd = call(&data)?;
+    match (read_u256(&rd, 0), read_u32(&rd, 1)) {
+        (Some(seconds_per_liquidity), Some(seconds)) => Ok((seconds_per_liquidity, seconds)),
+        _ => Err(Error::SeawaterBadReturnData),
+    }
 }
@@ -1,15 +1,13 @@
 #[cfg(all(test, not(target_arch = "wasm32")))]
 mod testing {
-    use libleo;
+    use libleo::{self, block, calldata::*, error::Error};
 
-    use stylus_sdk::{
-        alloy_primitives::{address, Address, FixedBytes, U256},
-        block,
-    };
+    use stylus_sdk::alloy_primitives::{address, Address, FixedBytes, I32, U256};
 
     const POOL: Address = address!("6221a9c005f6e47eb398fd867784cacfdcfff4e7");
     const CAMPAIGN_ID: FixedBytes<8> = FixedBytes::ZERO;
     const POS_ID: U256 = U256::ZERO;
+    const RECIPIENT: Address = address!("737b7865f84bdc86b5c8ca718a5b7a6d905776f6");
 
     #[test]
     fn campaign_creation() {
@@ -68,10 +66,10 @@ mod testing {
                 )
                 .unwrap();
 
-                leo.vest_position(POOL, POS_ID).unwrap();
+                leo.vest_position(POOL, POS_ID, vec![CAMPAIGN_ID]).unwrap();
 
                 assert!(
-                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID])
+                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID], RECIPIENT)
                         .unwrap()
                         .len()
                         == 0
@@ -87,9 +85,9 @@ mod testing {
             |leo| {
                 leo.ctor(Address::ZERO).unwrap();
 
-                leo.vest_position(POOL, POS_ID).unwrap();
+                leo.vest_position(POOL, POS_ID, vec![]).unwrap();
 
-                assert!(leo.vest_position(POOL, POS_ID).is_err());
+                assert!(leo.vest_position(POOL, POS_ID, vec![]).is_err());
 
                 leo.create_campaign(
                     CAMPAIGN_ID,                // Identifier
@@ -105,7 +103,7 @@ mod testing {
                 .unwrap();
 
                 assert!(
-                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID])
+                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID], RECIPIENT)
                         .unwrap()
                         .len()
                         == 0
@@ -124,7 +122,7 @@ mod testing {
 
                 leo.ctor(Address::ZERO).unwrap();
 
-                leo.vest_position(POOL, POS_ID).unwrap();
+                leo.vest_position(POOL, POS_ID, vec![]).unwrap();
 
                 // Someone goes to create a campaign.
 
@@ -143,7 +141,7 @@ mod testing {
 
                 // Someone claims from it...
 
-                leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID])
+                leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID], RECIPIENT)
                     .unwrap();
 
                 // Then the campaign author cancels it!
@@ -153,21 +151,21 @@ mod testing {
                 // Then the same user claims again, but they shouldn't receive anything.
 
                 assert_eq!(
-                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID])
+                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID], RECIPIENT)
                         .unwrap()
                         .len(),
                     0
                 );
 
                 assert_eq!(
-                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID])
+                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID], RECIPIENT)
                         .unwrap()
                         .len(),
                     0
                 );
 
                 assert_eq!(
-                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID])
+                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID], RECIPIENT)
                         .unwrap()
                         .len(),
                     0
@@ -179,14 +177,14 @@ mod testing {
     #[test]
     fn campaign_created_claimed_then_updated_claim_again() {
         libleo::host::with_storage::<_, libleo::Leo, _>(
-            &[(POOL, POS_ID, -10, 100, U256::from(1000))],
+            &[(POOL, POS_ID, -10, 100, U256::from(1024))],
             |leo| {
                 let expected_starting = block::timestamp() - 1000;
                 let expected_ending = block::timestamp() + 1000;
 
                 leo.ctor(Address::ZERO).unwrap();
 
-                leo.vest_position(POOL, POS_ID).unwrap();
+                leo.vest_position(POOL, POS_ID, vec![]).unwrap();
 
                 // Someone goes to create a campaign.
 
@@ -203,19 +201,28 @@ mod testing {
                 )
                 .unwrap();
 
-                // Someone claims from it...
+                // Someone claims from it, which starts them earning, but
+                // they weren't around for the time that already went by.
 
-                let earned_rewards = leo
-                    .collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID])
-                    .unwrap()[0]
-                    .1;
+                assert_eq!(
+                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID], RECIPIENT)
+                        .unwrap()
+                        .len(),
+                    0
+                );
+
+                libleo::host::advance_time(100);
 
-                eprintln!("we're done claiming the first time");
+                assert_eq!(
+                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID], RECIPIENT)
+                        .unwrap(),
+                    vec![(POOL, U256::from(200))]
+                );
 
-                // Then the campaign author updates it in the future...
+                // Then the campaign author updates it, with a range that
+                // doesn't include the old one, but the position is still
+                // eligible for it.
 
-                // It's weird if someone tries to adjust the starting time to be earlier,
-                // but it could happen, so we test it.
                 leo.update_campaign(
                     CAMPAIGN_ID,
                     POOL,
@@ -228,41 +235,547 @@ mod testing {
                 )
                 .unwrap();
 
+                // The position has to claim to start earning from the new
+                // range, since it wasn't carried over.
+
+                assert_eq!(
+                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID], RECIPIENT)
+                        .unwrap()
+                        .len(),
+                    0
+                );
+
+                libleo::host::advance_time(200);
+
                 // Then the same user claims again.
 
-                let extra_rewards = leo
-                    .collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID])
-                    .unwrap()[0]
-                    .1;
+                assert_eq!(
+                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID], RECIPIENT)
+                        .unwrap(),
+                    vec![(POOL, U256::from(1000))]
+                );
+
+                let (_, _, _, _, distributed, _, _, _) =
+                    leo.campaign_details(POOL, CAMPAIGN_ID).unwrap();
+                assert_eq!(distributed, U256::from(1200));
+            },
+        )
+    }
+
+    #[test]
+    fn rewards_split_by_liquidity() {
+        const POS_ID_OTHER: U256 = U256::from_limbs([1, 0, 0, 0]);
+
+        libleo::host::with_storage::<_, libleo::Leo, _>(
+            &[
+                (POOL, POS_ID, -10, 100, U256::from(1024)),
+                (POOL, POS_ID_OTHER, 0, 50, U256::from(3072)),
+            ],
+            |leo| {
+                leo.ctor(Address::ZERO).unwrap();
+
+                leo.create_campaign(
+                    CAMPAIGN_ID,               // Identifier
+                    POOL,                      // Pool
+                    -20,                       // Tick lower
+                    100,                       // Tick upper
+                    U256::from(4),             // Per second distribution
+                    POOL,                      // Token to send
+                    U256::from(1000),          // Starting pool of liquidity
+                    block::timestamp(),        // Starting timestamp
+                    block::timestamp() + 1000, // Ending timestamp
+                )
+                .unwrap();
 
-                leo.admin_reduce_campaign_starting_last_iteration(POOL, CAMPAIGN_ID, 200)
+                leo.vest_position(POOL, POS_ID, vec![CAMPAIGN_ID]).unwrap();
+                leo.vest_position(POOL, POS_ID_OTHER, vec![CAMPAIGN_ID])
                     .unwrap();
 
+                // The rewards are split by the liquidity of each position.
+
+                libleo::host::advance_time(100);
+
+                assert_eq!(
+                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID], RECIPIENT)
+                        .unwrap(),
+                    vec![(POOL, U256::from(100))]
+                );
                 assert_eq!(
-                    extra_rewards.to_string(),
-                    (libleo::maths::calc_base_rewards(
-                        U256::from(1000),
-                        U256::from(1000),
-                        U256::from(5)
-                    ) * U256::from(1000))
-                    .to_string()
+                    leo.collect_lp_rewards(POOL, POS_ID_OTHER, vec![CAMPAIGN_ID], RECIPIENT)
+                        .unwrap(),
+                    vec![(POOL, U256::from(300))]
                 );
+
+                // Once the other position leaves, the rest goes to the first.
+
+                leo.divest_position(POOL, POS_ID_OTHER, RECIPIENT).unwrap();
+
+                libleo::host::advance_time(100);
+
+                assert_eq!(
+                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID], RECIPIENT)
+                        .unwrap(),
+                    vec![(POOL, U256::from(400))]
+                );
+
+                // The range grows, so the position carries on earning, but
+                // the campaign runs out of its maximum.
+
+                leo.update_campaign(
+                    CAMPAIGN_ID,
+                    POOL,
+                    -50,
+                    150,
+                    U256::from(8),
+                    U256::ZERO,
+                    block::timestamp(),
+                    block::timestamp() + 1000,
+                )
+                .unwrap();
+
+                libleo::host::advance_time(100);
+
+                assert_eq!(
+                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID], RECIPIENT)
+                        .unwrap(),
+                    vec![(POOL, U256::from(200))]
+                );
+
+                libleo::host::advance_time(100);
+
+                assert_eq!(
+                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID], RECIPIENT)
+                        .unwrap()
+                        .len(),
+                    0
+                );
+
+                let (_, _, _, _, distributed, maximum, _, _) =
+                    leo.campaign_details(POOL, CAMPAIGN_ID).unwrap();
+                assert_eq!(distributed, maximum);
             },
         )
     }
+
+    #[test]
+    fn duplicate_campaign_ids_join_once() {
+        const POS_ID_OTHER: U256 = U256::from_limbs([1, 0, 0, 0]);
+
+        libleo::host::with_storage::<_, libleo::Leo, _>(
+            &[
+                (POOL, POS_ID, -10, 100, U256::from(1024)),
+                (POOL, POS_ID_OTHER, 0, 50, U256::from(3072)),
+            ],
+            |leo| {
+                leo.ctor(Address::ZERO).unwrap();
+
+                leo.create_campaign(
+                    CAMPAIGN_ID,               // Identifier
+                    POOL,                      // Pool
+                    -20,                       // Tick lower
+                    100,                       // Tick upper
+                    U256::from(4),             // Per second distribution
+                    POOL,                      // Token to send
+                    U256::from(1000),          // Starting pool of liquidity
+                    block::timestamp(),        // Starting timestamp
+                    block::timestamp() + 1000, // Ending timestamp
+                )
+                .unwrap();
+
+                // Repeating the campaign doesn't count the position's
+                // liquidity twice, so the split is the same as without.
+
+                leo.vest_position(POOL, POS_ID, vec![CAMPAIGN_ID, CAMPAIGN_ID])
+                    .unwrap();
+                leo.vest_position(POOL, POS_ID_OTHER, vec![CAMPAIGN_ID])
+                    .unwrap();
+
+                libleo::host::advance_time(100);
+
+                assert_eq!(
+                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID], RECIPIENT)
+                        .unwrap(),
+                    vec![(POOL, U256::from(100))]
+                );
+                assert_eq!(
+                    leo.collect_lp_rewards(POOL, POS_ID_OTHER, vec![CAMPAIGN_ID], RECIPIENT)
+                        .unwrap(),
+                    vec![(POOL, U256::from(300))]
+                );
+            },
+        )
+    }
+
+    #[test]
+    fn rewards_only_for_time_in_range() {
+        libleo::host::with_storage::<_, libleo::Leo, _>(
+            &[(POOL, POS_ID, -10, 100, U256::from(1024))],
+            |leo| {
+                leo.ctor(Address::ZERO).unwrap();
+
+                leo.create_campaign(
+                    CAMPAIGN_ID,               // Identifier
+                    POOL,                      // Pool
+                    -20,                       // Tick lower
+                    100,                       // Tick upper
+                    U256::from(4),             // Per second distribution
+                    POOL,                      // Token to send
+                    U256::from(1000),          // Starting pool of liquidity
+                    block::timestamp(),        // Starting timestamp
+                    block::timestamp() + 1000, // Ending timestamp
+                )
+                .unwrap();
+
+                leo.vest_position(POOL, POS_ID, vec![CAMPAIGN_ID]).unwrap();
+
+                // The price spends half the time outside the position's
+                // range, so it's paid half of what it earned.
+
+                libleo::host::advance_time(100);
+                libleo::host::advance_time_out_of_range(POOL, -10, 100, 100);
+
+                assert_eq!(
+                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID], RECIPIENT)
+                        .unwrap(),
+                    vec![(POOL, U256::from(400))]
+                );
+
+                // The rest goes back to the campaign, so it can still be
+                // paid out later.
+
+                libleo::host::advance_time(150);
+
+                assert_eq!(
+                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID], RECIPIENT)
+                        .unwrap(),
+                    vec![(POOL, U256::from(600))]
+                );
+
+                let (_, _, _, _, distributed, maximum, _, _) =
+                    leo.campaign_details(POOL, CAMPAIGN_ID).unwrap();
+                assert_eq!(distributed, maximum);
+            },
+        )
+    }
+
+    #[test]
+    fn rewards_not_paid_for_time_in_range_after_campaign() {
+        libleo::host::with_storage::<_, libleo::Leo, _>(
+            &[(POOL, POS_ID, -10, 100, U256::from(1024))],
+            |leo| {
+                leo.ctor(Address::ZERO).unwrap();
+
+                leo.create_campaign(
+                    CAMPAIGN_ID,               // Identifier
+                    POOL,                      // Pool
+                    -20,                       // Tick lower
+                    100,                       // Tick upper
+                    U256::from(1),             // Per second distribution
+                    POOL,                      // Token to send
+                    U256::from(1000),          // Starting pool of liquidity
+                    block::timestamp(),        // Starting timestamp
+                    block::timestamp() + 1000, // Ending timestamp
+                )
+                .unwrap();
+
+                leo.vest_position(POOL, POS_ID, vec![CAMPAIGN_ID]).unwrap();
+
+                // The price is outside the position's range for the whole
+                // campaign, and only comes back once it's over, so the
+                // position isn't paid anything.
+
+                libleo::host::advance_time_out_of_range(POOL, -10, 100, 1000);
+                libleo::host::advance_time(1000);
+
+                assert_eq!(
+                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID], RECIPIENT)
+                        .unwrap()
+                        .len(),
+                    0
+                );
+
+                let (_, _, _, _, distributed, _, _, _) =
+                    leo.campaign_details(POOL, CAMPAIGN_ID).unwrap();
+                assert_eq!(distributed, U256::ZERO);
+
+                // Everything that was earned goes back to the owner.
+
+                assert_eq!(
+                    leo.withdraw_campaign_remainder(POOL, CAMPAIGN_ID, RECIPIENT)
+                        .unwrap(),
+                    U256::from(1000)
+                );
+            },
+        )
+    }
+
+    #[test]
+    fn rewards_per_liquidity_overflow_not_credited() {
+        const PER_SECOND: U256 = U256::from_limbs([0, 0, 2, 0]);
+        const MAXIMUM: U256 = U256::from_limbs([0, 0, 0, 1]);
+
+        libleo::host::with_storage::<_, libleo::Leo, _>(
+            &[(POOL, POS_ID, -10, 100, U256::from(1))],
+            |leo| {
+                leo.ctor(Address::ZERO).unwrap();
+
+                leo.create_campaign(
+                    CAMPAIGN_ID,               // Identifier
+                    POOL,                      // Pool
+                    -20,                       // Tick lower
+                    100,                       // Tick upper
+                    PER_SECOND,                // Per second distribution
+                    POOL,                      // Token to send
+                    MAXIMUM,                   // Starting pool of liquidity
+                    block::timestamp(),        // Starting timestamp
+                    block::timestamp() + 1000, // Ending timestamp
+                )
+                .unwrap();
+
+                leo.vest_position(POOL, POS_ID, vec![CAMPAIGN_ID]).unwrap();
+
+                // Splitting the rewards between a single unit of liquidity
+                // would overflow the rewards per liquidity, so they aren't
+                // credited, and the position can still be collected from and
+                // divested.
+
+                libleo::host::advance_time(100);
+
+                assert_eq!(
+                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID], RECIPIENT)
+                        .unwrap()
+                        .len(),
+                    0
+                );
+                leo.divest_position(POOL, POS_ID, RECIPIENT).unwrap();
+
+                let (_, _, _, _, distributed, _, _, _) =
+                    leo.campaign_details(POOL, CAMPAIGN_ID).unwrap();
+                assert_eq!(distributed, U256::ZERO);
+
+                // The rewards go back to the owner instead.
+
+                libleo::host::advance_time(1000);
+
+                assert_eq!(
+                    leo.withdraw_campaign_remainder(POOL, CAMPAIGN_ID, RECIPIENT)
+                        .unwrap(),
+                    MAXIMUM
+                );
+            },
+        )
+    }
+
+    #[test]
+    fn rewards_paid_then_remainder_withdrawn() {
+        const TOKEN: Address = address!("837b7865f84bdc86b5c8ca718a5b7a6d905776f6");
+        const OWNER_RECIPIENT: Address = address!("937b7865f84bdc86b5c8ca718a5b7a6d905776f6");
+
+        libleo::host::with_storage::<_, libleo::Leo, _>(
+            &[(POOL, POS_ID, -10, 100, U256::from(1024))],
+            |leo| {
+                leo.ctor(Address::ZERO).unwrap();
+
+                leo.create_campaign(
+                    CAMPAIGN_ID,              // Identifier
+                    POOL,                     // Pool
+                    -20,                      // Tick lower
+                    100,                      // Tick upper
+                    U256::from(1),            // Per second distribution
+                    TOKEN,                    // Token to send
+                    U256::from(1000),         // Starting pool of liquidity
+                    block::timestamp(),       // Starting timestamp
+                    block::timestamp() + 100, // Ending timestamp
+                )
+                .unwrap();
+
+                // The campaign's token was taken, not the pool's.
+                let leo_balance =
+                    || libleo::host::erc20_balance(TOKEN, libleo::host::CONTRACT_ADDR);
+                assert_eq!(leo_balance(), U256::from(1000));
+
+                leo.vest_position(POOL, POS_ID, vec![CAMPAIGN_ID]).unwrap();
+
+                libleo::host::advance_time(50);
+
+                assert_eq!(
+                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID], RECIPIENT)
+                        .unwrap(),
+                    vec![(TOKEN, U256::from(50))]
+                );
+                assert_eq!(
+                    libleo::host::erc20_balance(TOKEN, RECIPIENT),
+                    U256::from(50)
+                );
+
+                // The owner can't take anything back until the campaign ends.
+                assert_eq!(
+                    leo.withdraw_campaign_remainder(POOL, CAMPAIGN_ID, OWNER_RECIPIENT)
+                        .unwrap_err(),
+                    Vec::<u8>::from(Error::CampaignOngoing)
+                );
+
+                libleo::host::advance_time(100);
+
+                // The position's uncollected rewards stay behind for it.
+                assert_eq!(
+                    leo.withdraw_campaign_remainder(POOL, CAMPAIGN_ID, OWNER_RECIPIENT)
+                        .unwrap(),
+                    U256::from(900)
+                );
+                assert_eq!(
+                    libleo::host::erc20_balance(TOKEN, OWNER_RECIPIENT),
+                    U256::from(900)
+                );
+                assert_eq!(leo_balance(), U256::from(50));
+
+                assert_eq!(
+                    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID], RECIPIENT)
+                        .unwrap(),
+                    vec![(TOKEN, U256::from(50))]
+                );
+                assert_eq!(leo_balance(), U256::ZERO);
+
+                assert_eq!(
+                    leo.withdraw_campaign_remainder(POOL, CAMPAIGN_ID, OWNER_RECIPIENT)
+                        .unwrap(),
+                    U256::ZERO
+                );
+            },
+        )
+    }
+
+    #[test]
+    fn erc20_return_data_checked() {
+        const TOKEN: Address = address!("837b7865f84bdc86b5c8ca718a5b7a6d905776f6");
+
+        libleo::host::with_storage::<_, libleo::Leo, _>(&[], |_: &mut libleo::Leo| {
+            libleo::erc20::take(TOKEN, U256::from(10)).unwrap();
+
+            let mut word = [0_u8; 32];
+            word[31] = 1;
+            let word = word.to_vec();
+
+            // Tokens can return true, anything that isn't zero, or nothing at
+            // all. Anything after the first word is ignored.
+
+            libleo::erc20::give(TOKEN, RECIPIENT, U256::from(1)).unwrap();
+            for data in [vec![], vec![0xff; 32], [word.clone(), vec![0; 32]].concat()] {
+                libleo::host::set_erc20_return(TOKEN, data, true);
+                libleo::erc20::give(TOKEN, RECIPIENT, U256::from(1)).unwrap();
+            }
+            assert_eq!(libleo::host::erc20_balance(TOKEN, RECIPIENT), U256::from(4));
+
+            // Anything else fails, including calls to addresses without code.
+
+            for (data, has_code, err) in [
+                (vec![], false, Error::Erc20NoCode),
+                (vec![0; 32], true, Error::Erc20ReturnedFalse),
+                (
+                    [vec![0; 32], word.clone()].concat(),
+                    true,
+                    Error::Erc20ReturnedFalse,
+                ),
+                (vec![1], true, Error::Erc20BadReturnData),
+                (word[1..].to_vec(), true, Error::Erc20BadReturnData),
+            ] {
+                libleo::host::set_erc20_return(TOKEN, data, has_code);
+                assert_eq!(
+                    libleo::erc20::give(TOKEN, RECIPIENT, U256::from(1))
+                        .unwrap_err()
+                        .to_string(),
+                    err.to_string()
+                );
+            }
+        })
+    }
+
+    #[test]
+    fn seawater_calls_decode_results() {
+        libleo::host::with_storage::<_, libleo::Leo, _>(
+            &[(POOL, POS_ID, -10, 100, U256::from(100))],
+            |_| {
+                assert_eq!(
+                    libleo::seawater::position_liquidity(POOL, POS_ID).unwrap(),
+                    U256::from(100)
+                );
+                assert_eq!(
+                    libleo::seawater::tick_lower(POOL, POS_ID).unwrap(),
+                    I32::try_from(-10).unwrap()
+                );
+                assert_eq!(
+                    libleo::seawater::tick_upper(POOL, POS_ID).unwrap(),
+                    I32::try_from(100).unwrap()
+                );
+
+                libleo::host::set_position_fees(POS_ID, 5, 7);
+                assert_eq!(
+                    libleo::seawater::collect_yield_single_to(POS_ID, POOL, Address::ZERO).unwrap(),
+                    (5, 7)
+                );
+                assert_eq!(
+                    libleo::seawater::collect_yield_single_to(POS_ID, POOL, Address::ZERO).unwrap(),
+                    (0, 0)
+                );
+
+                // Reverts from Seawater are passed on as errors.
+                assert!(matches!(
+                    libleo::seawater::tick_lower(Address::ZERO, POS_ID),
+                    Err(Error::SeawaterCallFailed)
+                ));
+                assert!(matches!(
+                    libleo::seawater::position_liquidity(POOL, U256::from(1)),
+                    Err(Error::SeawaterCallFailed)
+                ));
+            },
+        )
+    }
+
+    #[test]
+    fn vest_unknown_position() {
+        libleo::host::with_storage::<_, libleo::Leo, _>(
+            &[(POOL, POS_ID, -10, 100, U256::from(100))],
+            |leo| {
+                leo.ctor(Address::ZERO).unwrap();
+                assert_eq!(
+                    leo.vest_position(POOL, U256::from(1), vec![]).unwrap_err(),
+                    Vec::<u8>::from(Error::SeawaterCallFailed)
+                );
+            },
+        )
+    }
+
+    #[test]
+    fn return_data_decoding() {
+        let mut word = [0xff_u8; 32];
+        word[28..].copy_from_slice(&(-10_i32).to_be_bytes());
+        assert_eq!(read_i32(&word, 0), Some(-10));
+
+        // Ints that aren't sign extended are rejected.
+        word[0] = 0;
+        assert_eq!(read_i32(&word, 0), None);
+        assert_eq!(read_u128(&word, 0), None);
+
+        let mut word = [0_u8; 32];
+        word[31] = 10;
+        assert_eq!(read_i32(&word, 0), Some(10));
+        assert_eq!(read_u128(&word, 0), Some(10));
+
+        // Short return data is rejected.
+        assert_eq!(read_u128(&word, 1), None);
+        assert_eq!(read_u128(&word[..31], 0), None);
+    }
 }
 
 #[cfg(all(test, not(target_arch = "wasm32")))]
 mod proptesting {
-    use libleo;
+    use libleo::{self, block};
     use proptest::prelude::*;
 
-    use stylus_sdk::{
-        alloy_primitives::{Address, FixedBytes, U256},
-        block,
-    };
+    use stylus_sdk::alloy_primitives::{address, Address, FixedBytes, U256};
 
     const POOL: Address = Address::ZERO;
+    const RECIPIENT: Address = address!("737b7865f84bdc86b5c8ca718a5b7a6d905776f6");
     const CAMPAIGN_ID: FixedBytes<8> = FixedBytes::ZERO;
 
     const POS_ID: U256 = U256::ZERO;
@@ -277,26 +790,20 @@ mod proptesting {
             mut tick_lower in MIN_TICK..MAX_TICK,
             mut tick_upper in MIN_TICK..MAX_TICK,
             per_second in 1..u64::MAX,
-            starting_pool in any::<[u64; 4]>(),
-            expected_starting in 0..libleo::host::current_timestamp(),
-            expected_ending in any::<u64>(),
-            secs_in in 1..u64::MAX,
-            position_lp in any::<[u64; 4]>(),
-            other_position_lp in any::<[u64; 4]>()
+            starting_pool in any::<u128>(),
+            duration in 1..1_000_000_000_u64,
+            secs_in in 1..1_000_000_000_u64,
+            position_lp in 1..u128::MAX,
+            other_position_lp in 1..u128::MAX
         ) {
-            let starting_pool = U256::from_limbs(starting_pool);
-            let position_lp = U256::from_limbs(position_lp) + U256::from(1);
-            let other_position_lp = U256::from_limbs(other_position_lp) + U256::from(1);
-
-            if starting_pool.is_zero() || position_lp.is_zero() {
-                return Ok(())
-            }
-
             if tick_upper < tick_lower {
                 (tick_lower, tick_upper) = (tick_upper, tick_lower);
             }
 
             let per_second = U256::from(per_second);
+            let starting_pool = U256::from(starting_pool);
+            let position_lp = U256::from(position_lp);
+            let other_position_lp = U256::from(other_position_lp);
 
             libleo::host::with_storage::<_, libleo::Leo, _>(
                 &[
@@ -304,7 +811,8 @@ mod proptesting {
                   (POOL, POS_ID_OTHER, 0, 0, other_position_lp)
                 ],
                 |leo| {
-                    let expected_ending = expected_starting + expected_ending;
+                    let expected_starting = block::timestamp();
+                    let expected_ending = expected_starting + duration;
 
                     leo.ctor(Address::ZERO).unwrap();
 
@@ -322,38 +830,51 @@ mod proptesting {
 
                     assert_eq!(leo.pool_lp(POOL).unwrap(), U256::ZERO);
 
-                    leo.vest_position(POOL, POS_ID_OTHER).unwrap();
+                    leo.vest_position(POOL, POS_ID_OTHER, vec![CAMPAIGN_ID]).unwrap();
 
                     assert_eq!(leo.pool_lp(POOL).unwrap(), other_position_lp);
 
-                    leo.vest_position(POOL, POS_ID).unwrap();
+                    leo.vest_position(POOL, POS_ID, vec![CAMPAIGN_ID]).unwrap();
 
                     assert_eq!(leo.pool_lp(POOL).unwrap(), other_position_lp + position_lp);
 
-                    leo.admin_reduce_pos_time(POS_ID, 100).unwrap();
-
-                    let reward = leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID]).unwrap()[0].1;
-
-                    // We take either when the campaign ended, or the current timestamp
-                    let clamped_campaign_ending =
-                        u64::min(expected_ending, block::timestamp());
-
-                    let seconds_since = clamped_campaign_ending - expected_starting;
-
-                    let expected_reward =
-                      libleo::maths::calc_base_rewards(other_position_lp + position_lp, position_lp, per_second) *
-                      U256::from(seconds_since);
-
-                    assert_eq!(expected_reward.to_string(), reward.to_string());
-
-                    //assert_eq!(
-                    //    leo.collect_lp_rewards(POOL, POS_ID, vec![CAMPAIGN_ID]).unwrap().len(),
-                    //    0
-                    //);
+                    libleo::host::advance_time(secs_in);
+
+                    let rewards = |leo: &mut libleo::Leo, id| {
+                        leo.collect_lp_rewards(POOL, id, vec![CAMPAIGN_ID], RECIPIENT)
+                            .unwrap()
+                            .first()
+                            .map_or(U256::ZERO, |(_, reward)| *reward)
+                    };
+                    let reward = rewards(leo, POS_ID);
+                    let other_reward = rewards(leo, POS_ID_OTHER);
+
+                    // The other position only earns if the campaign includes its tick.
+                    let other_eligible = tick_lower <= 0 && tick_upper >= 0;
+                    let staked_lp = match other_eligible {
+                        true => position_lp + other_position_lp,
+                        false => position_lp,
+                    };
+
+                    let expected_rewards = U256::min(
+                        per_second * U256::from(u64::min(secs_in, duration)),
+                        starting_pool,
+                    );
+
+                    // Each position earns its share of the rewards, rounding down
+                    // the rewards per liquidity and then the rewards themselves.
+                    assert!(reward <= expected_rewards * position_lp / staked_lp);
+                    assert!(reward + U256::from(2) >= expected_rewards * position_lp / staked_lp);
+                    if !other_eligible {
+                        assert_eq!(other_reward, U256::ZERO);
+                    }
 
                     let (_, _, _, _, distributed, _, _, _) =
                         leo.campaign_details(POOL, CAMPAIGN_ID).unwrap();
-                    assert_eq!(distributed, expected_reward);
+                    assert_eq!(distributed, reward + other_reward);
+                    assert_eq!(libleo::host::erc20_balance(POOL, RECIPIENT), distributed);
+                    assert!(distributed <= expected_rewards);
+                    assert!(distributed + U256::from(4) >= expected_rewards);
                 },
             )
         }
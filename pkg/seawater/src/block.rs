@@ -0,0 +1,15 @@
+//! Information about the current block. Mocked out on the host if tests are enabled, so tests can
+//! control the passage of time.
+
+/// Returns the timestamp of the current block, in seconds.
+#[cfg(not(all(not(target_arch = "wasm32"), feature = "testing")))]
+pub fn timestamp() -> u64 {
+    stylus_sdk::block::timestamp()
+}
+
+/// Returns the timestamp of the current block, in seconds. Set with
+/// [crate::host_test_shims::set_timestamp] and [crate::host_test_shims::advance_time].
+#[cfg(all(not(target_arch = "wasm32"), feature = "testing"))]
+pub fn timestamp() -> u64 {
+    crate::host_test_shims::get_timestamp()
+}
@@ -1,6 +1,8 @@
 //! The [enum@Error] enum.
 
+use crate::types::{Address, U256};
 use alloc::vec::Vec;
+use stylus_sdk::alloy_primitives::B256;
 use thiserror::Error;
 
 /// Asserts that a boolean value is true at runtime, returning an Err if not.
@@ -59,7 +61,7 @@ macro_rules! assert_neq_or {
 }
 
 /// The list of possible errors the contract can return.
-#[derive(Error, Debug)]
+#[derive(Error, Debug, PartialEq, Eq)]
 #[repr(u8)]
 pub enum Error {
     // 0 (0x00)
@@ -144,11 +146,11 @@ pub enum Error {
 
     #[error("Price limit too high")]
     // 19 (0x13)
-    PriceLimitTooHigh,
+    PriceLimitTooHigh { limit: U256, price: U256 },
 
     // 20 (0x14)
     #[error("Price limit too low")]
-    PriceLimitTooLow,
+    PriceLimitTooLow { limit: U256, price: U256 },
 
     // 21 (0x15)
     #[error("Checked abs called on an unexpected positive number")]
@@ -233,6 +235,114 @@ pub enum Error {
     // 41 (0x29)
     #[error("The emergency council can only disable pools")]
     SeawaterEmergencyOnlyDisable,
+
+    // 42 (0x2a)
+    #[error("Fee protocol must be 0, or between 4 and 10")]
+    InvalidFeeProtocol,
+
+    // 43 (0x2b)
+    #[error("Swap path must have at least two tokens, with fUSDC on every hop")]
+    InvalidSwapPath,
+
+    // 44 (0x2c)
+    #[error("Maximum in exceeded")]
+    MaxInExceeded,
+
+    // 45 (0x2d)
+    #[error("Position index is out of bounds for the owner")]
+    PositionIndexOutOfBounds,
+
+    // 46 (0x2e)
+    #[error("Only the pending admin can use this")]
+    PendingAdminOnly,
+
+    // 47 (0x2f)
+    #[error("Timelocked call is already queued")]
+    TimelockAlreadyQueued,
+
+    // 48 (0x30)
+    #[error("Timelocked call is not queued")]
+    TimelockNotQueued,
+
+    // 49 (0x31)
+    #[error("Timelocked call is not ready to be executed")]
+    TimelockNotReady,
+
+    // 50 (0x32)
+    #[error("Timelocked call has expired")]
+    TimelockExpired,
+
+    // 51 (0x33)
+    #[error("Account {account} is missing role {role}")]
+    MissingRole { role: B256, account: Address },
+
+    // 52 (0x34)
+    #[error("Operation is paused")]
+    OperationPaused,
+
+    // 53 (0x35)
+    #[error("Pause flags are invalid")]
+    InvalidPauseFlags,
+
+    // 54 (0x36)
+    #[error("Fee tier is not enabled")]
+    FeeTierNotEnabled,
+
+    // 55 (0x37)
+    #[error("Fee tier must have a fee below 100%, and a non zero tick spacing")]
+    InvalidFeeTier,
+
+    // 56 (0x38)
+    #[error("Fee tier is already enabled")]
+    FeeTierAlreadyEnabled,
+
+    // 57 (0x39)
+    #[error("Pair pools need two different tokens, neither of which is the fluid token")]
+    InvalidTokenPair,
+
+    // 58 (0x3a)
+    #[error("Flash loan wasn't repaid with its fee")]
+    FlashLoanNotRepaid,
+
+    // 59 (0x3b)
+    #[error("Pool's price oracle isn't initialised")]
+    OracleNotInitialised,
+
+    // 60 (0x3c)
+    #[error("Observation is older than the oldest observation kept")]
+    ObservationTooOld,
+
+    // 61 (0x3d)
+    #[error("Range order's range isn't entirely on one side of the price")]
+    RangeOrderInRange,
+
+    // 62 (0x3e)
+    #[error("Range order has already been filled")]
+    RangeOrderFilled,
+
+    // 63 (0x3f)
+    #[error("Range order hasn't been filled yet")]
+    RangeOrderNotFilled,
+
+    // 64 (0x40)
+    #[error("No range order for the caller in this epoch")]
+    RangeOrderEmpty,
+
+    // 65 (0x41)
+    #[error("Tick isn't initialised")]
+    TickNotInitialised,
+
+    // 66 (0x42)
+    #[error("Pools need a token that isn't the zero address or the fluid token")]
+    InvalidPoolToken,
+
+    // 67 (0x43)
+    #[error("Flash loan is larger than the pool's reserves")]
+    FlashLoanTooLarge,
+
+    // 68 (0x44)
+    #[error("Seawater is locked during a flash loan")]
+    FlashLoanLocked,
 }
 
 impl From<Error> for Vec<u8> {
@@ -242,21 +352,491 @@ impl From<Error> for Vec<u8> {
         val.to_string().into()
     }
 
-    // runtime returns the message code to save binary size
-    // TODO - once errors are mostly finalised we should find a way to return actual solidity
-    // errors
-    #[cfg(target_arch = "wasm32")]
+    // runtime returns a solidity custom error, see ISeawaterErrors.sol
+    #[cfg(all(target_arch = "wasm32", not(feature = "compact-errors")))]
+    fn from(val: Error) -> Self {
+        val.abi_encode()
+    }
+
+    // or the message code, to save binary size
+    #[cfg(all(target_arch = "wasm32", feature = "compact-errors"))]
     fn from(val: Error) -> Self {
+        val.compact_encode()
+    }
+}
+
+/// The Solidity definition of an [enum@Error] variant, used to encode it as a custom error.
+pub struct AbiError {
+    pub name: &'static str,
+    /// The type and name of each of the error's parameters.
+    pub params: &'static [(&'static str, &'static str)],
+}
+
+impl AbiError {
+    const fn new(name: &'static str, params: &'static [(&'static str, &'static str)]) -> Self {
+        Self { name, params }
+    }
+
+    /// Computes the error's selector, from its canonical signature.
+    pub const fn selector(&self) -> [u8; 4] {
+        let mut hasher = keccak_const::Keccak256::new()
+            .update(self.name.as_bytes())
+            .update(b"(");
+
+        let mut i = 0;
+        while i < self.params.len() {
+            if i > 0 {
+                hasher = hasher.update(b",");
+            }
+            hasher = hasher.update(self.params[i].0.as_bytes());
+            i += 1;
+        }
+
+        let hash = hasher.update(b")").finalize();
+        [hash[0], hash[1], hash[2], hash[3]]
+    }
+}
+
+/// The Solidity definitions of every error, indexed by the error's code. This must be kept in the
+/// same order as [enum@Error].
+pub const ABI_ERRORS: &[AbiError] = &[
+    AbiError::new("DenominatorIsZero", &[]),
+    AbiError::new("ResultIsU256MAX", &[]),
+    AbiError::new("SqrtPriceIsZero", &[]),
+    AbiError::new("SqrtPriceIsLteQuotient", &[]),
+    AbiError::new("ZeroValue", &[]),
+    AbiError::new("LiquidityIsZero", &[]),
+    AbiError::new("ProductDivAmount", &[]),
+    AbiError::new("DenominatorIsLteProdOne", &[]),
+    AbiError::new("LiquiditySub", &[]),
+    AbiError::new("LiquidityAdd", &[]),
+    AbiError::new("T", &[]),
+    AbiError::new("R", &[]),
+    AbiError::new("SafeCastToU160Overflow", &[]),
+    AbiError::new("LiquidityTooHigh", &[]),
+    AbiError::new("FeeGrowthSubPos", &[]),
+    AbiError::new("Erc20Revert", &[("bytes", "data")]),
+    AbiError::new("Erc20RevertNoData", &[]),
+    AbiError::new("PoolAlreadyInitialised", &[]),
+    AbiError::new("ContractAlreadyInitialised", &[]),
+    AbiError::new(
+        "PriceLimitTooHigh",
+        &[("uint256", "limit"), ("uint256", "price")],
+    ),
+    AbiError::new(
+        "PriceLimitTooLow",
+        &[("uint256", "limit"), ("uint256", "price")],
+    ),
+    AbiError::new("CheckedAbsIsNegative", &[]),
+    AbiError::new("CheckedAbsIsPositive", &[]),
+    AbiError::new("AbsTooLow", &[]),
+    AbiError::new("FeeTooHigh", &[]),
+    AbiError::new("SwapResultTooHigh", &[]),
+    AbiError::new("InterimSwapNotEq", &[]),
+    AbiError::new("InterimSwapPositive", &[]),
+    AbiError::new("MinOutNotReached", &[]),
+    AbiError::new("PositionOwnerOnly", &[]),
+    AbiError::new("NftManagerOnly", &[]),
+    AbiError::new("SeawaterAdminOnly", &[]),
+    AbiError::new("PoolDisabled", &[]),
+    AbiError::new("InvalidTickSpacing", &[]),
+    AbiError::new("SwapResultTooLow", &[]),
+    AbiError::new("LiquidityAmountTooWide", &[]),
+    AbiError::new("InvalidTick", &[]),
+    AbiError::new("PoolEnabled", &[]),
+    AbiError::new("EmptyPosition", &[]),
+    AbiError::new("LiqResultTooLow", &[]),
+    AbiError::new("FeeGrowthSubTick", &[]),
+    AbiError::new("SeawaterEmergencyOnlyDisable", &[]),
+    AbiError::new("InvalidFeeProtocol", &[]),
+    AbiError::new("InvalidSwapPath", &[]),
+    AbiError::new("MaxInExceeded", &[]),
+    AbiError::new("PositionIndexOutOfBounds", &[]),
+    AbiError::new("PendingAdminOnly", &[]),
+    AbiError::new("TimelockAlreadyQueued", &[]),
+    AbiError::new("TimelockNotQueued", &[]),
+    AbiError::new("TimelockNotReady", &[]),
+    AbiError::new("TimelockExpired", &[]),
+    AbiError::new(
+        "MissingRole",
+        &[("bytes32", "role"), ("address", "account")],
+    ),
+    AbiError::new("OperationPaused", &[]),
+    AbiError::new("InvalidPauseFlags", &[]),
+    AbiError::new("FeeTierNotEnabled", &[]),
+    AbiError::new("InvalidFeeTier", &[]),
+    AbiError::new("FeeTierAlreadyEnabled", &[]),
+    AbiError::new("InvalidTokenPair", &[]),
+    AbiError::new("FlashLoanNotRepaid", &[]),
+    AbiError::new("OracleNotInitialised", &[]),
+    AbiError::new("ObservationTooOld", &[]),
+    AbiError::new("RangeOrderInRange", &[]),
+    AbiError::new("RangeOrderFilled", &[]),
+    AbiError::new("RangeOrderNotFilled", &[]),
+    AbiError::new("RangeOrderEmpty", &[]),
+    AbiError::new("TickNotInitialised", &[]),
+    AbiError::new("InvalidPoolToken", &[]),
+    AbiError::new("FlashLoanTooLarge", &[]),
+    AbiError::new("FlashLoanLocked", &[]),
+];
+
+/// The selectors of every error, indexed by the error's code.
+pub const SELECTORS: [[u8; 4]; ABI_ERRORS.len()] = {
+    let mut selectors = [[0; 4]; ABI_ERRORS.len()];
+    let mut i = 0;
+    while i < ABI_ERRORS.len() {
+        selectors[i] = ABI_ERRORS[i].selector();
+        i += 1;
+    }
+    selectors
+};
+
+impl Error {
+    /// The error's code, which is its discriminant, and its index in [ABI_ERRORS].
+    pub fn code(&self) -> u8 {
         // cast the enum to its descriminant
         // https://doc.rust-lang.org/std/mem/fn.discriminant.html
-        let id = unsafe { *<*const _>::from(&val).cast::<u8>() };
+        // SAFETY: the enum is repr(u8), so its layout starts with a u8 discriminant
+        unsafe { *<*const _>::from(self).cast::<u8>() }
+    }
+
+    /// Encodes the error as a Solidity custom error, with its selector and ABI encoded
+    /// parameters.
+    pub fn abi_encode(self) -> Vec<u8> {
+        let mut e = SELECTORS[self.code() as usize].to_vec();
+
+        match self {
+            Error::PriceLimitTooHigh { limit, price }
+            | Error::PriceLimitTooLow { limit, price } => {
+                e.extend_from_slice(&limit.to_be_bytes::<32>());
+                e.extend_from_slice(&price.to_be_bytes::<32>());
+            }
+            Error::MissingRole { role, account } => {
+                e.extend_from_slice(role.as_slice());
+                e.extend_from_slice(account.into_word().as_slice());
+            }
+            Error::Erc20Revert(data) => {
+                // dynamic bytes are encoded as an offset to the length, then the padded data
+                let len = e.len() + 64 + data.len().div_ceil(32) * 32;
+                e.extend_from_slice(&U256::from(32).to_be_bytes::<32>());
+                e.extend_from_slice(&U256::from(data.len()).to_be_bytes::<32>());
+                e.extend_from_slice(&data);
+                e.resize(len, 0);
+            }
+            _ => {}
+        }
+
+        e
+    }
 
-        let mut e = vec![id];
+    /// Encodes the error as its code, followed by the revert data if it was an ERC20 revert.
+    pub fn compact_encode(self) -> Vec<u8> {
+        let mut e = vec![self.code()];
 
-        if let Error::Erc20Revert(mut err) = val {
+        if let Error::Erc20Revert(mut err) = self {
             e.append(&mut err);
         }
 
         e
     }
 }
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Error {
+    /// Creates an error from its code, with any parameters zeroed.
+    pub fn from_code(code: u8) -> Option<Self> {
+        Some(match code {
+            0 => Error::DenominatorIsZero,
+            1 => Error::ResultIsU256MAX,
+            2 => Error::SqrtPriceIsZero,
+            3 => Error::SqrtPriceIsLteQuotient,
+            4 => Error::ZeroValue,
+            5 => Error::LiquidityIsZero,
+            6 => Error::ProductDivAmount,
+            7 => Error::DenominatorIsLteProdOne,
+            8 => Error::LiquiditySub,
+            9 => Error::LiquidityAdd,
+            10 => Error::T,
+            11 => Error::R,
+            12 => Error::SafeCastToU160Overflow,
+            13 => Error::LiquidityTooHigh,
+            14 => Error::FeeGrowthSubPos,
+            15 => Error::Erc20Revert(Vec::new()),
+            16 => Error::Erc20RevertNoData,
+            17 => Error::PoolAlreadyInitialised,
+            18 => Error::ContractAlreadyInitialised,
+            19 => Error::PriceLimitTooHigh {
+                limit: U256::ZERO,
+                price: U256::ZERO,
+            },
+            20 => Error::PriceLimitTooLow {
+                limit: U256::ZERO,
+                price: U256::ZERO,
+            },
+            21 => Error::CheckedAbsIsNegative,
+            22 => Error::CheckedAbsIsPositive,
+            23 => Error::AbsTooLow,
+            24 => Error::FeeTooHigh,
+            25 => Error::SwapResultTooHigh,
+            26 => Error::InterimSwapNotEq,
+            27 => Error::InterimSwapPositive,
+            28 => Error::MinOutNotReached,
+            29 => Error::PositionOwnerOnly,
+            30 => Error::NftManagerOnly,
+            31 => Error::SeawaterAdminOnly,
+            32 => Error::PoolDisabled,
+            33 => Error::InvalidTickSpacing,
+            34 => Error::SwapResultTooLow,
+            35 => Error::LiquidityAmountTooWide,
+            36 => Error::InvalidTick,
+            37 => Error::PoolEnabled,
+            38 => Error::EmptyPosition,
+            39 => Error::LiqResultTooLow,
+            40 => Error::FeeGrowthSubTick,
+            41 => Error::SeawaterEmergencyOnlyDisable,
+            42 => Error::InvalidFeeProtocol,
+            43 => Error::InvalidSwapPath,
+            44 => Error::MaxInExceeded,
+            45 => Error::PositionIndexOutOfBounds,
+            46 => Error::PendingAdminOnly,
+            47 => Error::TimelockAlreadyQueued,
+            48 => Error::TimelockNotQueued,
+            49 => Error::TimelockNotReady,
+            50 => Error::TimelockExpired,
+            51 => Error::MissingRole {
+                role: B256::ZERO,
+                account: Address::ZERO,
+            },
+            52 => Error::OperationPaused,
+            53 => Error::InvalidPauseFlags,
+            54 => Error::FeeTierNotEnabled,
+            55 => Error::InvalidFeeTier,
+            56 => Error::FeeTierAlreadyEnabled,
+            57 => Error::InvalidTokenPair,
+            58 => Error::FlashLoanNotRepaid,
+            59 => Error::OracleNotInitialised,
+            60 => Error::ObservationTooOld,
+            61 => Error::RangeOrderInRange,
+            62 => Error::RangeOrderFilled,
+            63 => Error::RangeOrderNotFilled,
+            64 => Error::RangeOrderEmpty,
+            65 => Error::TickNotInitialised,
+            66 => Error::InvalidPoolToken,
+            67 => Error::FlashLoanTooLarge,
+            68 => Error::FlashLoanLocked,
+            _ => return None,
+        })
+    }
+
+    /// Decodes an error encoded by [Self::abi_encode], returning None if the data isn't a
+    /// seawater error.
+    pub fn abi_decode(data: &[u8]) -> Option<Self> {
+        let (selector, data) = data.split_first_chunk::<4>()?;
+        let code = SELECTORS.iter().position(|s| s == selector)?;
+
+        let word = |i: usize| -> Option<U256> {
+            let word = data.get(i * 32..(i + 1) * 32)?;
+            Some(U256::from_be_slice(word))
+        };
+
+        Some(match Self::from_code(code as u8)? {
+            Error::PriceLimitTooHigh { .. } => Error::PriceLimitTooHigh {
+                limit: word(0)?,
+                price: word(1)?,
+            },
+            Error::PriceLimitTooLow { .. } => Error::PriceLimitTooLow {
+                limit: word(0)?,
+                price: word(1)?,
+            },
+            Error::MissingRole { .. } => Error::MissingRole {
+                role: word(0)?.into(),
+                account: Address::from_word(word(1)?.into()),
+            },
+            Error::Erc20Revert(_) => {
+                let offset = usize::try_from(word(0)?).ok()?;
+                let (len, data) = data.get(offset..)?.split_first_chunk::<32>()?;
+                let len = usize::try_from(U256::from_be_bytes(*len)).ok()?;
+                Error::Erc20Revert(data.get(..len)?.to_vec())
+            }
+            err => err,
+        })
+    }
+
+    /// Decodes an error encoded by [Self::compact_encode], returning None if the data isn't a
+    /// seawater error. Parameters other than the ERC20 revert data are zeroed, since they aren't
+    /// encoded.
+    pub fn compact_decode(data: &[u8]) -> Option<Self> {
+        let (code, data) = data.split_first()?;
+
+        match Self::from_code(*code)? {
+            Error::Erc20Revert(_) => Some(Error::Erc20Revert(data.to_vec())),
+            err if data.is_empty() => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Generates the Solidity interface declaring every error, `sol/ISeawaterErrors.sol`.
+    pub fn solidity_interface() -> String {
+        let errors = ABI_ERRORS
+            .iter()
+            .enumerate()
+            .map(|(code, error)| {
+                let params = error
+                    .params
+                    .iter()
+                    .map(|(ty, name)| format!("{ty} {name}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                #[allow(clippy::unwrap_used)]
+                let message = Self::from_code(code as u8).unwrap().to_string();
+
+                format!(
+                    "    /// @notice {message}\n    /// @dev code {code} (0x{code:02x})\n    error {}({params});\n",
+                    error.name
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        format!(
+            "// SPDX-Identifier: MIT\n\
+             pragma solidity 0.8.16;\n\
+             \n\
+             // Generated from the Error enum in seawater/src/error.rs, don't edit this by hand!\n\
+             // Regenerate with UPDATE_ERRORS_SOL=1 cargo test -p seawater --features testing errors_sol\n\
+             \n\
+             interface ISeawaterErrors {{\n\
+             {errors}\
+             }}\n"
+        )
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use crate::eth_serde;
+
+    #[test]
+    fn abi_errors_match_variants() {
+        assert_eq!(ABI_ERRORS.len(), 69);
+        for (code, error) in ABI_ERRORS.iter().enumerate() {
+            let err = Error::from_code(code as u8).unwrap();
+            assert_eq!(err.code() as usize, code);
+
+            // the debug name is the variant name, followed by any parameters
+            let debug = format!("{err:?}");
+            let name = debug.split([' ', '(']).next().unwrap();
+            assert_eq!(name, error.name);
+        }
+        assert!(Error::from_code(ABI_ERRORS.len() as u8).is_none());
+    }
+
+    #[test]
+    fn selectors_are_canonical() {
+        assert_eq!(
+            SELECTORS[Error::PoolDisabled.code() as usize],
+            eth_serde::selector(b"PoolDisabled()")
+        );
+        assert_eq!(
+            SELECTORS[20],
+            eth_serde::selector(b"PriceLimitTooLow(uint256,uint256)")
+        );
+        assert_eq!(SELECTORS[15], eth_serde::selector(b"Erc20Revert(bytes)"));
+
+        // every selector must be unique to be decodable
+        let mut selectors = SELECTORS.to_vec();
+        selectors.sort();
+        selectors.dedup();
+        assert_eq!(selectors.len(), SELECTORS.len());
+    }
+
+    #[test]
+    fn abi_encoding_round_trips() {
+        let err = Error::PriceLimitTooLow {
+            limit: U256::from(1),
+            price: U256::from(2),
+        };
+        let encoded = err.abi_encode();
+        assert_eq!(encoded.len(), 4 + 64);
+        assert_eq!(encoded[4 + 31], 1);
+        assert_eq!(encoded[4 + 63], 2);
+        assert_eq!(
+            Error::abi_decode(&encoded),
+            Some(Error::PriceLimitTooLow {
+                limit: U256::from(1),
+                price: U256::from(2),
+            })
+        );
+
+        let err = Error::MissingRole {
+            role: B256::repeat_byte(0xaa),
+            account: Address::repeat_byte(0xbb),
+        };
+        let encoded = err.abi_encode();
+        assert_eq!(encoded.len(), 4 + 64);
+        assert_eq!(encoded[4 + 12], 0xaa);
+        assert_eq!(encoded[4 + 32 + 11], 0);
+        assert_eq!(encoded[4 + 32 + 12], 0xbb);
+        assert_eq!(
+            Error::abi_decode(&encoded),
+            Some(Error::MissingRole {
+                role: B256::repeat_byte(0xaa),
+                account: Address::repeat_byte(0xbb),
+            })
+        );
+
+        let revert = vec![0xab; 33];
+        let encoded = Error::Erc20Revert(revert.clone()).abi_encode();
+        assert_eq!(encoded.len(), 4 + 32 + 32 + 64);
+        assert_eq!(encoded[4 + 31], 0x20);
+        assert_eq!(encoded[4 + 63], 33);
+        assert_eq!(
+            Error::abi_decode(&encoded),
+            Some(Error::Erc20Revert(revert))
+        );
+
+        assert_eq!(Error::PoolDisabled.abi_encode().len(), 4);
+        assert_eq!(
+            Error::abi_decode(&Error::PoolDisabled.abi_encode()),
+            Some(Error::PoolDisabled)
+        );
+
+        assert_eq!(Error::abi_decode(&[0xde, 0xad, 0xbe, 0xef]), None);
+        assert_eq!(Error::abi_decode(&encoded[..40]), None);
+    }
+
+    #[test]
+    fn compact_encoding_round_trips() {
+        assert_eq!(Error::PoolDisabled.compact_encode(), vec![0x20]);
+        assert_eq!(Error::compact_decode(&[0x20]), Some(Error::PoolDisabled));
+
+        let encoded = Error::Erc20Revert(vec![1, 2, 3]).compact_encode();
+        assert_eq!(encoded, vec![0x0f, 1, 2, 3]);
+        assert_eq!(
+            Error::compact_decode(&encoded),
+            Some(Error::Erc20Revert(vec![1, 2, 3]))
+        );
+
+        assert_eq!(Error::compact_decode(&[0x20, 1]), None);
+        assert_eq!(Error::compact_decode(&[0xff]), None);
+    }
+
+    #[test]
+    fn errors_sol_is_up_to_date() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../sol/ISeawaterErrors.sol");
+        let generated = Error::solidity_interface();
+
+        if std::env::var_os("UPDATE_ERRORS_SOL").is_some() {
+            std::fs::write(path, &generated).unwrap();
+        }
+
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            generated,
+            "ISeawaterErrors.sol is out of date, regenerate it with UPDATE_ERRORS_SOL=1"
+        );
+    }
+}
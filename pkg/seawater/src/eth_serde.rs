@@ -64,6 +64,7 @@ gen_parse!(
 gen_parse!(parse_bool, val, bool, 1, val[0] != 0);
 
 gen_parse_int!(parse_i32, i32);
+gen_parse_int!(parse_u32, u32);
 gen_parse_int!(parse_i128, i128);
 
 /// Extracts a 32 bit selector from a data stream, returning the selector and the remaining data.
@@ -128,6 +129,94 @@ pub fn parse_bytes(data: &[u8]) -> (&[u8], &[u8]) {
     (bytes, data)
 }
 
+/// The selector of the `QuoteResult` custom error that structured quotes revert with.
+pub const QUOTE_RESULT_SELECTOR: [u8; 4] =
+    selector(b"QuoteResult(int256,int256,uint256,int32,uint32)");
+
+/// The result of a structured quote, encoded as the Solidity custom error
+/// `QuoteResult(int256 amount0, int256 amount1, uint256 sqrtPriceX96, int32 tick, uint32 initialisedTicksCrossed)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuoteResult {
+    /// The amount of token 0 the swap would take (positive) or give (negative).
+    pub amount_0: crate::types::I256,
+    /// The amount of token 1 the swap would take (positive) or give (negative).
+    pub amount_1: crate::types::I256,
+    /// The square root price of the pool after the swap.
+    pub sqrt_price_x96: crate::types::U256,
+    /// The tick of the pool after the swap.
+    pub tick: i32,
+    /// The number of initialised ticks crossed during the swap.
+    pub initialised_ticks_crossed: u32,
+}
+
+impl QuoteResult {
+    /// The length of the encoded error - the selector, followed by five words.
+    pub const ENCODED_LEN: usize = 4 + 32 * 5;
+
+    /// Encodes the quote result as revert data for the `QuoteResult` custom error.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut encoded = Vec::with_capacity(Self::ENCODED_LEN);
+
+        encoded.extend_from_slice(&QUOTE_RESULT_SELECTOR);
+        encoded.extend_from_slice(&self.amount_0.to_be_bytes::<32>());
+        encoded.extend_from_slice(&self.amount_1.to_be_bytes::<32>());
+        encoded.extend_from_slice(&self.sqrt_price_x96.to_be_bytes::<32>());
+        // signed integers are sign extended to a full word
+        encoded
+            .extend_from_slice(&crate::types::I256::unchecked_from(self.tick).to_be_bytes::<32>());
+        encoded.extend_from_slice(
+            &crate::types::U256::from(self.initialised_ticks_crossed).to_be_bytes::<32>(),
+        );
+
+        encoded
+    }
+
+    /// Decodes revert data from a structured quote, returning `None` if the data isn't a
+    /// `QuoteResult` error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use libseawater::eth_serde::QuoteResult;
+    /// use libseawater::types::{I256, U256};
+    ///
+    /// let quote = QuoteResult {
+    ///     amount_0: I256::unchecked_from(1000),
+    ///     amount_1: I256::unchecked_from(-996),
+    ///     sqrt_price_x96: U256::from(1) << 96,
+    ///     tick: -3,
+    ///     initialised_ticks_crossed: 1,
+    /// };
+    ///
+    /// assert_eq!(QuoteResult::decode(&quote.encode()), Some(quote));
+    /// assert_eq!(QuoteResult::decode(&[0, 1, 2, 3]), None);
+    /// ```
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() != Self::ENCODED_LEN {
+            return None;
+        }
+
+        let (sel, data) = parse_selector(data);
+        if sel != u32::from_be_bytes(QUOTE_RESULT_SELECTOR) {
+            return None;
+        }
+
+        let (amount_0, data) = parse_i256(data);
+        let (amount_1, data) = parse_i256(data);
+        let (sqrt_price_x96, data) = parse_u256(data);
+        let (tick, data) = parse_i32(data);
+        let (initialised_ticks_crossed, _) = parse_u32(data);
+
+        Some(QuoteResult {
+            amount_0,
+            amount_1,
+            sqrt_price_x96,
+            tick,
+            initialised_ticks_crossed,
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
     use ruint_macro::uint;
@@ -0,0 +1,70 @@
+//! Flash loan callbacks, using uniswap v3's `IUniswapV3FlashCallback` interface so existing
+//! borrowers can be used. Mocked out on the host if tests are enabled, calling a borrower set
+//! with [crate::host_test_shims::set_flash_borrower] instead.
+
+use crate::types::{Address, U256};
+
+/// The selector for `uniswapV3FlashCallback(uint256,uint256,bytes)`.
+pub const FLASH_CALLBACK_SELECTOR: [u8; 4] = [0xe9, 0xcb, 0xaf, 0xb0];
+
+/// Encodes a call to `uniswapV3FlashCallback(uint256 fee0, uint256 fee1, bytes data)`.
+pub fn encode_flash_callback(fee_0: U256, fee_1: U256, data: &[u8]) -> Vec<u8> {
+    let mut calldata = vec![0_u8; 4 + 32 * 4 + data.len().next_multiple_of(32)];
+    calldata[0..4].copy_from_slice(&FLASH_CALLBACK_SELECTOR);
+    calldata[4..36].copy_from_slice(&fee_0.to_be_bytes::<32>());
+    calldata[36..68].copy_from_slice(&fee_1.to_be_bytes::<32>());
+    calldata[68..100].copy_from_slice(&U256::from(0x60).to_be_bytes::<32>()); // data (byte offset)
+    calldata[100..132].copy_from_slice(&U256::from(data.len()).to_be_bytes::<32>()); // data (length)
+    calldata[132..132 + data.len()].copy_from_slice(data); // data
+
+    calldata
+}
+
+/// Calls back a flash loan borrower with the fees it owes, returning its revert data if it
+/// reverts.
+#[cfg(not(all(not(target_arch = "wasm32"), feature = "testing")))]
+pub fn flash_callback(
+    borrower: Address,
+    fee_0: U256,
+    fee_1: U256,
+    data: &[u8],
+) -> Result<(), Vec<u8>> {
+    stylus_sdk::call::RawCall::new()
+        .call(borrower, &encode_flash_callback(fee_0, fee_1, data))
+        .map(|_| ())
+}
+
+/// Calls back the mock flash loan borrower set with
+/// [crate::host_test_shims::set_flash_borrower], if there is one.
+#[cfg(all(not(target_arch = "wasm32"), feature = "testing"))]
+pub fn flash_callback(
+    _borrower: Address,
+    fee_0: U256,
+    fee_1: U256,
+    data: &[u8],
+) -> Result<(), Vec<u8>> {
+    crate::host_test_shims::call_flash_borrower(fee_0, fee_1, data)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use stylus_sdk::alloy_primitives::hex;
+
+    #[test]
+    fn test_encode_flash_callback() {
+        let encoded = encode_flash_callback(U256::from(3), U256::from(5), &[0xab, 0xcd]);
+
+        assert_eq!(
+            encoded,
+            hex!(
+                "e9cbafb0"
+                "0000000000000000000000000000000000000000000000000000000000000003"
+                "0000000000000000000000000000000000000000000000000000000000000005"
+                "0000000000000000000000000000000000000000000000000000000000000060"
+                "0000000000000000000000000000000000000000000000000000000000000002"
+                "abcd000000000000000000000000000000000000000000000000000000000000"
+            )
+        );
+    }
+}
@@ -38,7 +38,7 @@ pub fn transfer_to_sender(_token: Address, _amount: U256) -> Result<(), Error> {
 }
 
 /// Does the same thing as [transfer_to_sender] for testing reasons.
-pub fn transfer_to_addr(_addr: Address, _token: Address, _amount: U256) -> Result<(), Error> {
+pub fn transfer_to_addr(_token: Address, _addr: Address, _amount: U256) -> Result<(), Error> {
     transfer_to_sender(_token, _amount)
 }
 
@@ -78,6 +78,11 @@ pub fn take(
     )
 }
 
+/// Pretends to get the AMM's balance of a token, using the balances set with the host shims.
+pub fn balance_of_self(_token: Address) -> Result<U256, Error> {
+    Ok(host_test_shims::get_amm_bal(_token))
+}
+
 /// Pretends to construct a revert string from a message, only happening if the underlying
 /// environment is not WASM. Only useful for testing.
 pub fn revert_from_msg(_msg: &str) -> Vec<u8> {
@@ -42,6 +42,8 @@ pub mod storage {
         pub static CURRENT_SENDER: RefCell<[u8; 20]> =
             const { RefCell::new([0; 20]) };
 
+        pub static CURRENT_TIMESTAMP: RefCell<u64> = const { RefCell::new(0) };
+
         pub static STORAGE: RefCell<WordHashMap> = RefCell::new(HashMap::new());
 
         pub static CALLER_BALS: RefCell<HashMap<Address, U256>> =
@@ -49,6 +51,9 @@ pub mod storage {
 
         pub static AMM_BALS: RefCell<HashMap<Address, U256>> =
             RefCell::new(HashMap::new());
+
+        pub static FLASH_BORROWER: RefCell<Option<super::FlashBorrower>> =
+            const { RefCell::new(None) };
     }
 
     pub unsafe fn read_word(key: *const u8) -> Word {
@@ -145,6 +150,19 @@ pub fn set_sender(new_sender: [u8; 20]) {
     storage::CURRENT_SENDER.with(|sender| *sender.borrow_mut() = new_sender);
 }
 
+pub fn get_timestamp() -> u64 {
+    storage::CURRENT_TIMESTAMP.with(|timestamp| *timestamp.borrow())
+}
+
+pub fn set_timestamp(new_timestamp: u64) {
+    storage::CURRENT_TIMESTAMP.with(|timestamp| *timestamp.borrow_mut() = new_timestamp);
+}
+
+/// Moves the current block timestamp forwards by a number of seconds.
+pub fn advance_time(seconds: u64) {
+    storage::CURRENT_TIMESTAMP.with(|timestamp| *timestamp.borrow_mut() += seconds);
+}
+
 pub fn set_caller_bals(items: HashMap<Address, U256>) {
     storage::CALLER_BALS.with(|sender| *sender.borrow_mut() = items);
 }
@@ -160,8 +178,10 @@ pub fn insert_word(key: storage::Word, value: storage::Word) {
 pub fn reset_storage() {
     storage::STORAGE.with(|storage| storage.borrow_mut().clear());
     storage::CURRENT_SENDER.with(|sender| *sender.borrow_mut() = [0; 20]);
+    storage::CURRENT_TIMESTAMP.with(|timestamp| *timestamp.borrow_mut() = 0);
     storage::CALLER_BALS.with(|bals| bals.borrow_mut().clear());
     storage::AMM_BALS.with(|bals| bals.borrow_mut().clear());
+    storage::FLASH_BORROWER.with(|borrower| *borrower.borrow_mut() = None);
 }
 
 pub fn take_caller_bal(token: Address, amt: U256) -> Result<(), U256> {
@@ -197,3 +217,30 @@ pub fn take_amm_bal(token: Address, amt: U256) -> Result<(), U256> {
         _ => Ok(()),
     })
 }
+
+/// Get the AMM's balance of a token, or zero if it isn't set.
+pub fn get_amm_bal(token: Address) -> U256 {
+    storage::AMM_BALS.with(|bals| bals.borrow().get(&token).copied().unwrap_or_default())
+}
+
+/// Give the AMM tokens, as if they were transferred to it.
+pub fn give_amm_bal(token: Address, amt: U256) {
+    storage::AMM_BALS.with(|bals| *bals.borrow_mut().entry(token).or_default() += amt);
+}
+
+/// A mock flash loan borrower, called with the fees owed and the data passed to the flash loan.
+/// Repays the loan using [give_amm_bal], and returns revert data if it should revert.
+pub type FlashBorrower = Box<dyn Fn(U256, U256, &[u8]) -> Result<(), Vec<u8>>>;
+
+/// Sets the mock flash loan borrower called back by flash loans.
+pub fn set_flash_borrower(borrower: impl Fn(U256, U256, &[u8]) -> Result<(), Vec<u8>> + 'static) {
+    storage::FLASH_BORROWER.with(|cur| *cur.borrow_mut() = Some(Box::new(borrower)));
+}
+
+/// Call the mock flash loan borrower, doing nothing if it isn't set.
+pub fn call_flash_borrower(fee_0: U256, fee_1: U256, data: &[u8]) -> Result<(), Vec<u8>> {
+    storage::FLASH_BORROWER.with(|borrower| match &*borrower.borrow() {
+        Some(borrower) => borrower(fee_0, fee_1, data),
+        None => Ok(()),
+    })
+}
@@ -6,24 +6,37 @@
 #![cfg_attr(not(target_arch = "wasm32"), feature(const_trait_impl))]
 #![deny(clippy::unwrap_used)]
 
+pub mod block;
 pub mod eth_serde;
 pub mod immutables;
 #[macro_use]
 pub mod error;
 pub mod events;
+pub mod flash;
 
 pub mod maths;
+pub mod oracle;
 pub mod pool;
 pub mod position;
+pub mod roles;
+pub mod swap;
 pub mod tick;
+pub mod timelock;
 pub mod types;
 
+// Off-chain pool snapshots, for simulating swaps on the host.
+#[cfg(not(target_arch = "wasm32"))]
+pub mod snapshot;
+
 #[cfg(all(not(target_arch = "wasm32"), feature = "testing"))]
 pub mod host_test_shims;
 
 #[cfg(all(not(target_arch = "wasm32"), feature = "testing"))]
 pub mod host_test_utils;
 
+#[cfg(all(not(target_arch = "wasm32"), feature = "testing"))]
+pub mod storage_dump;
+
 #[cfg(feature = "testing")]
 pub mod test_shims;
 
@@ -52,9 +65,11 @@ use error::Error;
 use immutables::FUSDC_ADDR;
 use maths::tick_math;
 
-use types::{U256Extension, WrappedNative};
+use types::{U256Extension, WrappedNative, U64, U8};
+
+use core::cmp::Ordering;
 
-use stylus_sdk::{msg, prelude::*, storage::*};
+use stylus_sdk::{alloy_primitives::B256, msg, prelude::*, storage::*};
 
 #[cfg(feature = "log-events")]
 use stylus_sdk::evm;
@@ -119,6 +134,31 @@ pub struct Pools {
 
     // authorised enablers to create new pools, and enable them
     authorised_enablers: StorageMap<Address, StorageBool>,
+
+    // owner => index => ID, for enumerating the positions of an owner
+    pub owned_position_ids: StorageMap<Address, StorageMap<U256, StorageU256>>,
+    // ID => index in the owner's enumeration plus one, or zero if it isn't enumerated
+    pub owned_position_index: StorageMap<U256, StorageU256>,
+    // owner => number of positions in the enumeration, which leaves out positions granted before
+    // positions were enumerated until they're backfilled
+    pub enumerated_positions: StorageMap<Address, StorageU256>,
+
+    // admin proposed by the current admin, that has yet to accept
+    pending_admin: StorageAddress,
+    // timelocked call ID => the timestamp it can be executed from
+    timelocked_calls: StorageMap<B256, StorageU64>,
+
+    // role => account => whether the account was granted the role
+    roles: StorageMap<B256, StorageMap<Address, StorageBool>>,
+
+    // pauses swaps and liquidity adds in every pool, see [pool::GLOBAL_PAUSE_FLAGS]
+    globally_paused: StorageBool,
+
+    // fee => tick spacing of the fee tier, or 0 if the tier isn't enabled
+    fee_tiers: StorageMap<u32, StorageU8>,
+
+    // set while a flash loan calls back its borrower, locking swaps, positions, and flash loans
+    pub flash_locked: StorageBool,
 }
 
 impl Pools {
@@ -128,10 +168,10 @@ impl Pools {
     /// argument decoding.
     ///
     /// # Arguments
-    /// * `pool` - The pool to swap for. Pools are accessed as the address of their first token,
-    /// where every pool has the fluid token as token 1.
-    /// * `zero_for_one` - The swap direction. This is `true` if swapping to the fluid token, or
-    /// `false` if swapping from the fluid token.
+    /// * `pool` - The ID of the pool to swap for, see [pool::pool_id]. Token 1 is the fluid token
+    /// unless the pool was created with [Self::create_pair_pool_111_D0_A09].
+    /// * `zero_for_one` - The swap direction. This is `true` if swapping to token 1 (usually the
+    /// fluid token), or `false` if swapping from it.
     /// * `amount` - The amount of token to swap. Follows the uniswap convention, where a positive
     /// amount will perform an exact in swap and a negative amount will perform an exact out swap.
     /// * `price_limit_x96` - The price limit, specified as an X96 encoded square root price.
@@ -149,6 +189,8 @@ impl Pools {
         price_limit_x96: U256,
         permit2: Option<Permit2Args>,
     ) -> Result<(I256, I256), Revert> {
+        pools.require_unpaused(pool, pool::PAUSE_SWAPS)?;
+
         let (amount_0, amount_1, _ending_tick) =
             pools
                 .pools
@@ -159,9 +201,10 @@ impl Pools {
         // denies all reentrancy unless explicity allowed (which we don't)
 
         // if zero_for_one, send them token1 and take token0
+        let (token_0, token_1) = (pools.pool_token(pool), pools.pool_token_1(pool));
         let (take_token, take_amount, give_token, give_amount) = match zero_for_one {
-            true => (pool, amount_0, FUSDC_ADDR, amount_1),
-            false => (FUSDC_ADDR, amount_1, pool, amount_0),
+            true => (token_0, amount_0, token_1, amount_1),
+            false => (token_1, amount_1, token_0, amount_0),
         };
 
         erc20::take(take_token, take_amount.abs_pos()?, permit2)?;
@@ -194,7 +237,8 @@ impl Pools {
         Ok((amount_0, amount_1))
     }
 
-    /// Performs a two step swap internally, without performing any ERC20 transfers.
+    /// Performs a two step swap internally, without performing any ERC20 transfers. `from` and
+    /// `to` are pool IDs, see [pool::pool_id], or the fluid token.
     fn swap_2_internal(
         pools: &mut Pools,
         from: Address,
@@ -202,6 +246,11 @@ impl Pools {
         amount: U256,
         min_out: U256,
     ) -> Result<(U256, U256, U256, I256, i32, i32), Revert> {
+        pools.require_fluid_pool(from)?;
+        pools.require_fluid_pool(to)?;
+        pools.require_unpaused(from, pool::PAUSE_SWAPS)?;
+        pools.require_unpaused(to, pool::PAUSE_SWAPS)?;
+
         let original_amount = amount;
 
         let amount = I256::try_from(amount).map_err(|_| Error::SwapResultTooHigh)?;
@@ -287,6 +336,7 @@ impl Pools {
         ));
 
         // transfer tokens
+        let (from, to) = (pools.pool_token(from), pools.pool_token(to));
         erc20::take(from, original_amount, permit2)?;
         erc20::transfer_to_sender(to, amount_out)?;
 
@@ -306,6 +356,258 @@ impl Pools {
         // send amount_out to the user
         Ok((amount_in, amount_out))
     }
+
+    /// Performs a two step exact out swap internally, without performing any ERC20 transfers.
+    ///
+    /// The second hop is swapped first, to find the amount of the fluid token needed to receive
+    /// `amount_out`, then the first hop is swapped for exactly that amount of the fluid token.
+    ///
+    /// # Errors
+    /// Requires the full `amount_out` to be received, and the amount in to be at most `max_in`.
+    fn swap_2_exact_out_internal(
+        pools: &mut Pools,
+        from: Address,
+        to: Address,
+        amount_out: U256,
+        max_in: U256,
+    ) -> Result<(U256, U256, I256, i32, i32), Revert> {
+        pools.require_fluid_pool(from)?;
+        pools.require_fluid_pool(to)?;
+        pools.require_unpaused(from, pool::PAUSE_SWAPS)?;
+        pools.require_unpaused(to, pool::PAUSE_SWAPS)?;
+
+        let amount = I256::try_from(amount_out).map_err(|_| Error::SwapResultTooHigh)?;
+
+        // swap usdc -> out, for exactly amount_out
+        let (amount_out_swapped, interim_usdc_in, final_tick_out) = pools.pools.setter(to).swap(
+            false,
+            -amount,
+            // swap with no price limit, since we use max_in instead
+            tick_math::MAX_SQRT_RATIO - U256::one(),
+        )?;
+
+        // the pool might have run out of liquidity before giving the full amount
+        assert_eq_or!(amount_out_swapped, -amount, Error::MinOutNotReached);
+
+        // swap in -> usdc, for exactly the usdc needed by the second hop
+        let (amount_in, interim_usdc_out, final_tick_in) = pools.pools.setter(from).swap(
+            true,
+            -interim_usdc_in,
+            tick_math::MIN_SQRT_RATIO + U256::one(),
+        )?;
+
+        #[cfg(feature = "testing-dbg")]
+        dbg!((
+            "inside swap_2_exact_out_internal",
+            interim_usdc_out,
+            interim_usdc_in,
+            amount_in.to_string(),
+            max_in.to_string()
+        ));
+
+        assert_eq_or!(-interim_usdc_out, interim_usdc_in, Error::InterimSwapNotEq);
+
+        let amount_in = amount_in.abs_pos()?;
+
+        assert_or!(amount_in <= max_in, Error::MaxInExceeded);
+        Ok((
+            amount_in,
+            amount_out,
+            interim_usdc_in,
+            final_tick_in,
+            final_tick_out,
+        ))
+    }
+
+    /// Performs a two step exact out swap, taking a permit2 blob for transfers.
+    ///
+    /// This function is called by [Self::swap_2_exact_out] and `swap_2_exact_out_permit2`,
+    /// which do argument decoding.
+    /// See [Self::swap_2_exact_out_internal] for more details on how this operates.
+    pub fn swap_2_exact_out_internal_erc20(
+        pools: &mut Pools,
+        from: Address,
+        to: Address,
+        amount_out: U256,
+        max_in: U256,
+        permit2: Option<Permit2Args>,
+    ) -> Result<(U256, U256), Revert> {
+        let (amount_in, amount_out, _interim_usdc, _final_tick_in, _final_tick_out) =
+            Self::swap_2_exact_out_internal(pools, from, to, amount_out, max_in)?;
+
+        // transfer tokens
+        let (from, to) = (pools.pool_token(from), pools.pool_token(to));
+        erc20::take(from, amount_in, permit2)?;
+        erc20::transfer_to_sender(to, amount_out)?;
+
+        #[cfg(feature = "log-events")]
+        evm::log(events::Swap2 {
+            user: msg::sender(),
+            from,
+            to,
+            amountIn: amount_in,
+            amountOut: amount_out,
+            fluidVolume: _interim_usdc.abs().into_raw(),
+            finalTick0: _final_tick_in,
+            finalTick1: _final_tick_out,
+        });
+
+        Ok((amount_in, amount_out))
+    }
+
+    /// Finds the pool and swap direction for a single hop of a swap path. Every hop must either
+    /// be into or out of the fluid token.
+    fn path_hop(from: Address, to: Address) -> Result<(Address, bool), Error> {
+        match (from == FUSDC_ADDR, to == FUSDC_ADDR) {
+            // token -> fusdc
+            (false, true) => Ok((from, true)),
+            // fusdc -> token
+            (true, false) => Ok((to, false)),
+            _ => Err(Error::InvalidSwapPath),
+        }
+    }
+
+    /// Performs a multi step swap along a path of tokens internally, without performing any ERC20
+    /// transfers.
+    ///
+    /// # Arguments
+    /// * `path` - The pools to swap through, or the fluid token, starting with the input and
+    ///   ending with the output. Every hop must either be into or out of the fluid token.
+    /// * `amount` - The amount of the input token to use if `exact_in` is set, or the amount of
+    ///   the output token to receive otherwise.
+    /// * `exact_in` - Whether the swap is exact in or exact out. Exact out swaps are performed
+    ///   from the last hop to the first.
+    ///
+    /// # Errors
+    /// Requires every hop to fully use its input (if exact in) or produce its output (if exact
+    /// out).
+    ///
+    /// Returns the amount of the input token taken, and the amount of the output token given.
+    pub fn swap_path_internal(
+        pools: &mut Pools,
+        path: &[Address],
+        amount: U256,
+        exact_in: bool,
+    ) -> Result<(U256, U256), Revert> {
+        assert_or!(path.len() >= 2, Error::InvalidSwapPath);
+
+        let hops = path
+            .windows(2)
+            .map(|hop| Self::path_hop(hop[0], hop[1]))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let mut amount_next = amount;
+
+        let mut step = |(pool, zero_for_one): (Address, bool)| -> Result<(), Revert> {
+            pools.require_fluid_pool(pool)?;
+            pools.require_unpaused(pool, pool::PAUSE_SWAPS)?;
+
+            let amount_hop = I256::try_from(amount_next).map_err(|_| Error::SwapResultTooHigh)?;
+            let amount_hop = match exact_in {
+                true => amount_hop,
+                false => -amount_hop,
+            };
+
+            // swap with no price limit, since we use min_out/max_in instead
+            let price_limit = match zero_for_one {
+                true => tick_math::MIN_SQRT_RATIO + U256::one(),
+                false => tick_math::MAX_SQRT_RATIO - U256::one(),
+            };
+
+            let (amount_0, amount_1, _final_tick) =
+                pools
+                    .pools
+                    .setter(pool)
+                    .swap(zero_for_one, amount_hop, price_limit)?;
+
+            let (hop_in, hop_out) = match zero_for_one {
+                true => (amount_0, amount_1),
+                false => (amount_1, amount_0),
+            };
+
+            #[cfg(feature = "testing-dbg")]
+            dbg!((
+                "inside swap_path_internal",
+                pool,
+                zero_for_one,
+                hop_in.to_string(),
+                hop_out.to_string()
+            ));
+
+            amount_next = match exact_in {
+                true => {
+                    assert_eq_or!(hop_in, amount_hop, Error::InterimSwapNotEq);
+                    hop_out
+                        .checked_neg()
+                        .ok_or(Error::InterimSwapPositive)?
+                        .abs_pos()?
+                }
+                false => {
+                    assert_eq_or!(hop_out, amount_hop, Error::InterimSwapNotEq);
+                    hop_in.abs_pos()?
+                }
+            };
+
+            Ok(())
+        };
+
+        match exact_in {
+            true => hops.into_iter().try_for_each(&mut step)?,
+            // exact out swaps work backwards from the amount out
+            false => hops.into_iter().rev().try_for_each(&mut step)?,
+        };
+
+        match exact_in {
+            true => Ok((amount, amount_next)),
+            false => Ok((amount_next, amount)),
+        }
+    }
+
+    /// Performs a multi step swap along a path of tokens, transferring the input token from the
+    /// caller and the output token to them.
+    ///
+    /// This function is called by [Self::swap_path_exact_in] and [Self::swap_path_exact_out],
+    /// which do argument decoding. See [Self::swap_path_internal] for more details on how this
+    /// operates.
+    ///
+    /// # Errors
+    /// Requires the amount out to be at least `min_out`, and the amount in to be at most `max_in`.
+    pub fn swap_path_internal_erc20(
+        pools: &mut Pools,
+        path: Vec<Address>,
+        amount: U256,
+        exact_in: bool,
+        min_out: U256,
+        max_in: U256,
+        permit2: Option<Permit2Args>,
+    ) -> Result<(U256, U256), Revert> {
+        let (amount_in, amount_out) = Self::swap_path_internal(pools, &path, amount, exact_in)?;
+
+        assert_or!(amount_out >= min_out, Error::MinOutNotReached);
+        assert_or!(amount_in <= max_in, Error::MaxInExceeded);
+
+        // the path is at least two long, so these will always be set
+        let (from, to) = match (path.first(), path.last()) {
+            (Some(&from), Some(&to)) => (from, to),
+            _ => Err(Error::InvalidSwapPath)?,
+        };
+        let (from, to) = (pools.pool_token(from), pools.pool_token(to));
+
+        erc20::take(from, amount_in, permit2)?;
+        erc20::transfer_to_sender(to, amount_out)?;
+
+        #[cfg(feature = "log-events")]
+        evm::log(events::SwapPath {
+            user: msg::sender(),
+            from,
+            to,
+            path,
+            amountIn: amount_in,
+            amountOut: amount_out,
+        });
+
+        Ok((amount_in, amount_out))
+    }
 }
 
 /// Swap functions. Only enabled when the `swaps` feature is set.
@@ -333,6 +635,132 @@ impl Pools {
     ) -> Result<(U256, U256), Revert> {
         Pools::swap_2_internal_erc20(self, from, to, amount, min_out, None)
     }
+
+    /// Performs a two stage exact out swap, using approvals to transfer tokens. See
+    /// [Self::swap_2_exact_out_internal].
+    #[allow(non_snake_case)]
+    pub fn swap_2_exact_out_1758_D1_A7(
+        &mut self,
+        from: Address,
+        to: Address,
+        amount_out: U256,
+        max_in: U256,
+    ) -> Result<(U256, U256), Revert> {
+        Pools::swap_2_exact_out_internal_erc20(self, from, to, amount_out, max_in, None)
+    }
+
+    /// Performs a multi step exact in swap along a path of tokens, using approvals to transfer
+    /// tokens. See [Self::swap_path_internal].
+    #[allow(non_snake_case)]
+    pub fn swap_path_exact_in_1033_D01_B(
+        &mut self,
+        path: Vec<Address>,
+        amount: U256,
+        min_out: U256,
+    ) -> Result<(U256, U256), Revert> {
+        Pools::swap_path_internal_erc20(self, path, amount, true, min_out, U256::MAX, None)
+    }
+
+    /// Performs a multi step exact out swap along a path of tokens, using approvals to transfer
+    /// tokens. See [Self::swap_path_internal].
+    #[allow(non_snake_case)]
+    pub fn swap_path_exact_out_100_F69_C2(
+        &mut self,
+        path: Vec<Address>,
+        amount: U256,
+        max_in: U256,
+    ) -> Result<(U256, U256), Revert> {
+        Pools::swap_path_internal_erc20(self, path, amount, false, U256::ZERO, max_in, None)
+    }
+
+    /// Lends the tokens of a pool, calling back the caller with uniswap's
+    /// `uniswapV3FlashCallback` (see [flash]), which must repay the loan plus the pool's fee.
+    /// The fees are credited to in range liquidity providers, less the protocol's share.
+    ///
+    /// # Arguments
+    /// * `pool` - The pool to borrow from.
+    /// * `recipient` - The recipient of the loan.
+    /// * `amount_0` - The amount of token 0 to borrow.
+    /// * `amount_1` - The amount of token 1 to borrow.
+    /// * `data` - Data to pass to the callback.
+    ///
+    /// # Errors
+    /// Requires the pool to be enabled, with liquidity in range, and swaps not paused. The amounts
+    /// can't be more than the pool's reserves, the tokens it holds for its liquidity providers and
+    /// fees. Requires the callback to succeed, and the pool's balances to increase by at least the
+    /// fees owed.
+    ///
+    /// Seawater is locked during the callback, so swaps, positions, and flash loans can't be used
+    /// until the loan is repaid.
+    #[allow(non_snake_case)]
+    pub fn flash_1022_F44_D(
+        &mut self,
+        pool: Address,
+        recipient: Address,
+        amount_0: U256,
+        amount_1: U256,
+        data: Vec<u8>,
+    ) -> Result<(), Revert> {
+        self.require_unpaused(pool, pool::PAUSE_SWAPS)?;
+
+        let (fee_0, fee_1) = self.pools.getter(pool).flash_fees(amount_0, amount_1)?;
+
+        let (token_0, token_1) = (self.pool_token(pool), self.pool_token_1(pool));
+
+        // nothing else can move tokens in or out while locked, so the change in balances is what
+        // the borrower sent back. the lock is released before returning errors, as storage is
+        // only reverted on chain
+        self.flash_locked.set(true);
+        let paid = (|| -> Result<(U256, U256), Revert> {
+            let balance_0_before = erc20::balance_of_self(token_0)?;
+            let balance_1_before = erc20::balance_of_self(token_1)?;
+
+            if amount_0 > U256::ZERO {
+                erc20::transfer_to_addr(token_0, recipient, amount_0)?;
+            }
+            if amount_1 > U256::ZERO {
+                erc20::transfer_to_addr(token_1, recipient, amount_1)?;
+            }
+
+            flash::flash_callback(msg::sender(), fee_0, fee_1, &data)?;
+
+            let balance_0_after = erc20::balance_of_self(token_0)?;
+            let balance_1_after = erc20::balance_of_self(token_1)?;
+
+            assert_or!(
+                balance_0_after >= balance_0_before.saturating_add(fee_0),
+                Error::FlashLoanNotRepaid
+            );
+            assert_or!(
+                balance_1_after >= balance_1_before.saturating_add(fee_1),
+                Error::FlashLoanNotRepaid
+            );
+
+            Ok((
+                balance_0_after - balance_0_before,
+                balance_1_after - balance_1_before,
+            ))
+        })();
+        self.flash_locked.set(false);
+
+        // borrowers can pay more than they owe, which is given to liquidity providers as well
+        let (paid_0, paid_1) = paid?;
+
+        self.pools.setter(pool).credit_flash_fees(paid_0, paid_1)?;
+
+        #[cfg(feature = "log-events")]
+        evm::log(events::Flash {
+            user: msg::sender(),
+            pool,
+            recipient,
+            amount0: amount_0,
+            amount1: amount_1,
+            paid0: paid_0,
+            paid1: paid_1,
+        });
+
+        Ok(())
+    }
 }
 
 /// Quote functions. Only enabled when the `quotes` feature is set.
@@ -349,6 +777,8 @@ impl Pools {
         amount: I256,
         price_limit_x96: U256,
     ) -> Result<(), Revert> {
+        self.require_unpaused(pool, pool::PAUSE_SWAPS)?;
+
         let swapped = self
             .pools
             .setter(pool)
@@ -358,8 +788,8 @@ impl Pools {
             Ok((amount_0, amount_1, _)) => {
                 // if zero_for_one, send them token1 and take token0
                 let (give_token, give_amount) = match zero_for_one {
-                    true => (FUSDC_ADDR, amount_1),
-                    false => (pool, amount_0),
+                    true => (self.pool_token_1(pool), amount_1),
+                    false => (self.pool_token(pool), amount_0),
                 };
 
                 erc20::transfer_to_sender(give_token, give_amount.abs_neg()?)?;
@@ -375,6 +805,50 @@ impl Pools {
         }
     }
 
+    /// Quote a [Self::swap], reverting with the `QuoteResult` custom error instead of a decimal
+    /// string. See [eth_serde::QuoteResult] to decode the result.
+    /// Returns a `Result` as Stylus expects but will always only fill the `Revert`.
+    #[allow(non_snake_case)]
+    pub fn quote_structured_2065_E121(
+        &mut self,
+        pool: Address,
+        zero_for_one: bool,
+        amount: I256,
+        price_limit_x96: U256,
+    ) -> Result<(), Revert> {
+        self.require_unpaused(pool, pool::PAUSE_SWAPS)?;
+
+        let (token_0, token_1) = (self.pool_token(pool), self.pool_token_1(pool));
+        let mut storage_pool = self.pools.setter(pool);
+        let swapped = storage_pool.swap_counting_ticks(zero_for_one, amount, price_limit_x96);
+
+        match swapped {
+            Ok((amount_0, amount_1, tick, initialised_ticks_crossed)) => {
+                let sqrt_price_x96 = storage_pool.get_sqrt_price();
+
+                // if zero_for_one, send them token1 and take token0
+                let (give_token, give_amount) = match zero_for_one {
+                    true => (token_1, amount_1),
+                    false => (token_0, amount_0),
+                };
+
+                erc20::transfer_to_sender(give_token, give_amount.abs_neg()?)?;
+
+                let quote = eth_serde::QuoteResult {
+                    amount_0,
+                    amount_1,
+                    sqrt_price_x96,
+                    tick,
+                    initialised_ticks_crossed,
+                };
+
+                Err(quote.encode())
+            }
+            // actual error, return it as normal
+            Err(e) => Err(e),
+        }
+    }
+
     /// Quote a [Self::swap_2_exact_ine4f82465]. Will revert with the result of the swap
     /// as a decimal number as the message of an `Error(string)`.
     /// Returns a `Result` as Stylus expects but will always only fill the `Revert`.
@@ -390,7 +864,7 @@ impl Pools {
 
         match swapped {
             Ok((_, _, amount_out, _, _, _)) => {
-                erc20::transfer_to_sender(to, amount_out)?;
+                erc20::transfer_to_sender(self.pool_token(to), amount_out)?;
                 let revert = erc20::revert_from_msg(&amount_out.to_string());
                 Err(revert)
             }
@@ -398,6 +872,64 @@ impl Pools {
             Err(e) => Err(e),
         }
     }
+
+    /// Quote a [Self::swap_2_exact_out_1758_D1_A7]. Will revert with the amount of the input
+    /// token needed as a decimal number as the message of an `Error(string)`.
+    /// Returns a `Result` as Stylus expects but will always only fill the `Revert`.
+    #[allow(non_snake_case)]
+    pub fn quote_2_exact_out_13_F_B992_B(
+        &mut self,
+        from: Address,
+        to: Address,
+        amount_out: U256,
+        max_in: U256,
+    ) -> Result<(), Revert> {
+        let swapped = Pools::swap_2_exact_out_internal(self, from, to, amount_out, max_in);
+
+        match swapped {
+            Ok((amount_in, amount_out, _, _, _)) => {
+                erc20::transfer_to_sender(self.pool_token(to), amount_out)?;
+                let revert = erc20::revert_from_msg(&amount_in.to_string());
+                Err(revert)
+            }
+            // actual error, return it as normal
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Quote a [Self::swap_path_exact_in_1033_D01_B] or [Self::swap_path_exact_out_100_F69_C2].
+    /// Follows the uniswap convention, where a positive amount is an exact in swap and a negative
+    /// amount is an exact out swap. Will revert with the amount out for exact in swaps, or the
+    /// amount in for exact out swaps, as a decimal number as the message of an `Error(string)`.
+    /// Returns a `Result` as Stylus expects but will always only fill the `Revert`.
+    #[allow(non_snake_case)]
+    pub fn quote_path_101993_E8(&mut self, path: Vec<Address>, amount: I256) -> Result<(), Revert> {
+        let exact_in = amount > I256::zero();
+        let amount = amount
+            .checked_abs()
+            .ok_or(Error::SwapResultTooHigh)?
+            .into_raw();
+
+        let swapped = Pools::swap_path_internal(self, &path, amount, exact_in);
+
+        match swapped {
+            Ok((amount_in, amount_out)) => {
+                if let Some(&to) = path.last() {
+                    erc20::transfer_to_sender(self.pool_token(to), amount_out)?;
+                }
+
+                let quote_amount = match exact_in {
+                    true => amount_out,
+                    false => amount_in,
+                };
+
+                let revert = erc20::revert_from_msg(&quote_amount.to_string());
+                Err(revert)
+            }
+            // actual error, return it as normal
+            Err(e) => Err(e),
+        }
+    }
 }
 
 /// Swap functions using Permit2. Only enabled when the `swap_permit2` feature is set.
@@ -455,20 +987,62 @@ impl Pools {
 
         Pools::swap_2_internal_erc20(self, from, to, amount, min_out, Some(permit2_args))
     }
-}
-
-/// Internal functions for position management.
-impl Pools {
-    /// Makes the user the owner of a position. The position must not have an owner.
-    fn grant_position(&mut self, owner: Address, id: U256) {
-        // set owner
-        self.position_owners.setter(id).set(owner);
 
-        // increment count
-        let owned_positions_count = self.owned_positions.get(owner) + U256::one();
-        self.owned_positions
-            .setter(owner)
-            .set(owned_positions_count);
+    /// Performs a two stage exact out swap, using permit2 to transfer tokens. See
+    /// [Self::swap_2_exact_out_internal].
+    #[cfg(feature = "swap_permit2")]
+    #[allow(non_snake_case)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn swap_2_exact_out_permit_2_1161_B87_F(
+        &mut self,
+        from: Address,
+        to: Address,
+        amount_out: U256,
+        max_in: U256,
+        nonce: U256,
+        deadline: U256,
+        sig: Vec<u8>,
+    ) -> Result<(U256, U256), Revert> {
+        let permit2_args = Permit2Args {
+            max_amount: max_in,
+            nonce,
+            deadline,
+            sig: &sig,
+        };
+
+        Pools::swap_2_exact_out_internal_erc20(
+            self,
+            from,
+            to,
+            amount_out,
+            max_in,
+            Some(permit2_args),
+        )
+    }
+}
+
+/// Internal functions for position management.
+impl Pools {
+    /// Makes the user the owner of a position. The position must not have an owner.
+    fn grant_position(&mut self, owner: Address, id: U256) {
+        // set owner
+        self.position_owners.setter(id).set(owner);
+
+        // append to the owner's enumeration
+        let index = self.enumerated_positions.get(owner);
+        self.owned_position_ids.setter(owner).setter(index).set(id);
+        self.owned_position_index
+            .setter(id)
+            .set(index + U256::one());
+        self.enumerated_positions
+            .setter(owner)
+            .set(index + U256::one());
+
+        // increment count
+        let owned_positions_count = self.owned_positions.get(owner) + U256::one();
+        self.owned_positions
+            .setter(owner)
+            .set(owned_positions_count);
     }
 
     /// Removes the user as the owner of a position. The position must have an owner.
@@ -481,6 +1055,28 @@ impl Pools {
         self.owned_positions
             .setter(owner)
             .set(owned_positions_count);
+
+        // positions granted before owners' positions were enumerated aren't in the enumeration
+        // until it's backfilled with [Self::backfill_position_enumeration]
+        let index_plus_one = self.owned_position_index.get(id);
+        if index_plus_one.is_zero() {
+            return;
+        }
+        let index = index_plus_one - U256::one();
+        self.owned_position_index.setter(id).erase();
+
+        // swap the last position of the owner into the removed position's index, and pop it
+        let last_index = self.enumerated_positions.get(owner) - U256::one();
+        self.enumerated_positions.setter(owner).set(last_index);
+        let mut owned_ids = self.owned_position_ids.setter(owner);
+        if index != last_index {
+            let last_id = owned_ids.get(last_index);
+            owned_ids.setter(index).set(last_id);
+            self.owned_position_index
+                .setter(last_id)
+                .set(index_plus_one);
+        }
+        owned_ids.setter(last_index).erase();
     }
 }
 
@@ -580,6 +1176,90 @@ impl Pools {
         Ok(self.owned_positions.get(user))
     }
 
+    /// Returns the ID of the position at an index of the positions owned by an address.
+    ///
+    /// Indexes are only stable while the owner's positions don't change - removing a position
+    /// moves the owner's last position into its index. Positions granted before positions were
+    /// enumerated are left out until they're backfilled, see
+    /// [Self::backfill_position_enumeration].
+    ///
+    /// # Errors
+    /// Requires the index to be less than the number of enumerated positions owned by the
+    /// address.
+    #[allow(non_snake_case)]
+    pub fn position_of_owner_by_index_1068_E3_C7(
+        &self,
+        owner: Address,
+        index: U256,
+    ) -> Result<U256, Revert> {
+        assert_or!(
+            index < self.enumerated_positions.get(owner),
+            Error::PositionIndexOutOfBounds
+        );
+        Ok(self.owned_position_ids.getter(owner).get(index))
+    }
+
+    /// Returns a page of the IDs of the positions owned by an address, in index order.
+    ///
+    /// # Arguments
+    /// * `owner` - The address to list the positions of.
+    /// * `offset` - The index of the first position to return.
+    /// * `limit` - The maximum number of positions to return.
+    ///
+    /// Returns an empty list if the offset is past the owner's last position. Like
+    /// [Self::position_of_owner_by_index_1068_E3_C7], positions that aren't enumerated yet are
+    /// left out.
+    #[allow(non_snake_case)]
+    pub fn positions_of_owner_103_E_C_F2_F(
+        &self,
+        owner: Address,
+        offset: U256,
+        limit: U256,
+    ) -> Result<Vec<U256>, Revert> {
+        let count = self.enumerated_positions.get(owner);
+        let end = offset.saturating_add(limit).min(count);
+
+        let owned_ids = self.owned_position_ids.getter(owner);
+        let mut ids = Vec::new();
+        let mut index = offset;
+        while index < end {
+            ids.push(owned_ids.get(index));
+            index += U256::one();
+        }
+
+        Ok(ids)
+    }
+
+    /// Returns the full state of a position in one call.
+    ///
+    /// Returns the position's owner, lower tick, upper tick, liquidity, the amounts of token 0
+    /// and token 1 its liquidity is worth at the current price, and the fees of token 0 and
+    /// token 1 it's owed. Unlike [Self::fees_owed_22_F28_D_B_D], the fees include those
+    /// earned since the position was last updated, without checkpointing them.
+    ///
+    /// Positions that don't exist return zero for everything.
+    #[allow(non_snake_case)]
+    #[allow(clippy::type_complexity)]
+    pub fn position_info_110_D9723(
+        &self,
+        pool: Address,
+        id: U256,
+    ) -> Result<(Address, i32, i32, u128, U256, U256, u128, u128), Revert> {
+        let (lower, upper, liquidity, amount_0, amount_1, fees_owed_0, fees_owed_1) =
+            self.pools.getter(pool).get_position_info(id)?;
+
+        Ok((
+            self.position_owners.get(id),
+            lower,
+            upper,
+            liquidity,
+            amount_0,
+            amount_1,
+            fees_owed_0,
+            fees_owed_1,
+        ))
+    }
+
     /// Returns the amount of liquidity in a position.
     #[allow(non_snake_case)]
     pub fn position_liquidity_8_D11_C045(&self, pool: Address, id: U256) -> Result<u128, Revert> {
@@ -620,6 +1300,8 @@ impl Pools {
             Error::PositionOwnerOnly
         );
 
+        self.require_unpaused(pool, pool::PAUSE_FEE_COLLECTION)?;
+
         let res = self.pools.setter(pool).collect(id)?;
         let (token_0, token_1) = res;
 
@@ -632,8 +1314,8 @@ impl Pools {
             amount1: token_1,
         });
 
-        erc20::transfer_to_addr(pool, recipient, U256::from(token_0))?;
-        erc20::transfer_to_addr(FUSDC_ADDR, recipient, U256::from(token_1))?;
+        erc20::transfer_to_addr(self.pool_token(pool), recipient, U256::from(token_0))?;
+        erc20::transfer_to_addr(self.pool_token_1(pool), recipient, U256::from(token_1))?;
 
         Ok(res)
     }
@@ -684,7 +1366,8 @@ impl Pools {
     ///
     /// # Errors
     /// Requires token approvals to be set if adding liquidity. Requires the caller to be the
-    /// position owner. Requires the pool to be enabled unless removing liquidity.
+    /// position owner. Requires the pool to be enabled, and adding or removing liquidity to not be
+    /// paused.
     pub fn update_position_internal(
         &mut self,
         pool: Address,
@@ -698,22 +1381,29 @@ impl Pools {
             Error::PositionOwnerOnly
         );
 
+        match delta.cmp(&0) {
+            Ordering::Greater => self.require_unpaused(pool, pool::PAUSE_ADDS)?,
+            Ordering::Less => self.require_unpaused(pool, pool::PAUSE_REMOVALS)?,
+            Ordering::Equal => {}
+        }
+
         let (token_0, token_1) = self.pools.setter(pool).update_position(id, delta)?;
 
         #[cfg(feature = "testing-dbg")]
         dbg!(("update position taking", current_test!(), token_0, token_1));
 
+        let (address_0, address_1) = (self.pool_token(pool), self.pool_token_1(pool));
         if delta < 0 {
-            erc20::transfer_to_sender(pool, token_0.abs_neg()?)?;
-            erc20::transfer_to_sender(FUSDC_ADDR, token_1.abs_neg()?)?;
+            erc20::transfer_to_sender(address_0, token_0.abs_neg()?)?;
+            erc20::transfer_to_sender(address_1, token_1.abs_neg()?)?;
         } else {
             let (permit_0, permit_1) = match permit2 {
                 Some((permit_0, permit_1)) => (Some(permit_0), Some(permit_1)),
                 None => (None, None),
             };
 
-            erc20::take(pool, token_0.abs_pos()?, permit_0)?;
-            erc20::take(FUSDC_ADDR, token_1.abs_pos()?, permit_1)?;
+            erc20::take(address_0, token_0.abs_pos()?, permit_0)?;
+            erc20::take(address_1, token_1.abs_pos()?, permit_1)?;
         }
 
         #[cfg(feature = "log-events")]
@@ -744,6 +1434,12 @@ impl Pools {
             Error::PositionOwnerOnly
         );
 
+        // the pool gives tokens when liquidity is removed
+        match giving {
+            true => self.require_unpaused(pool, pool::PAUSE_REMOVALS)?,
+            false => self.require_unpaused(pool, pool::PAUSE_ADDS)?,
+        }
+
         let (amount_0, amount_1) = self.pools.setter(pool).adjust_position(
             id,
             amount_0_desired,
@@ -877,17 +1573,18 @@ impl Pools {
             giving
         ));
 
+        let (token_0, token_1) = (self.pool_token(pool), self.pool_token_1(pool));
         if giving {
-            erc20::transfer_to_sender(pool, amount_0)?;
-            erc20::transfer_to_sender(FUSDC_ADDR, amount_1)?;
+            erc20::transfer_to_sender(token_0, amount_0)?;
+            erc20::transfer_to_sender(token_1, amount_1)?;
         } else {
             let (permit_0, permit_1) = match permit2 {
                 Some((permit_0, permit_1)) => (Some(permit_0), Some(permit_1)),
                 None => (None, None),
             };
 
-            erc20::take(pool, amount_0, permit_0)?;
-            erc20::take(FUSDC_ADDR, amount_1, permit_1)?;
+            erc20::take(token_0, amount_0, permit_0)?;
+            erc20::take(token_1, amount_1, permit_1)?;
         }
 
         Ok((amount_0, amount_1))
@@ -932,6 +1629,52 @@ impl Pools {
         )
     }
 
+    /// Refreshes and updates liquidity in a position, using permit2 to transfer tokens from the
+    /// user with a restriction on the amount taken. The permit2 max amounts are used as the
+    /// desired amounts. See [Self::adjust_position_internal].
+    #[allow(non_snake_case)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn incr_position_permit_2_5468326_E(
+        &mut self,
+        pool: Address,
+        id: U256,
+        amount_0_min: U256,
+        amount_1_min: U256,
+        nonce_0: U256,
+        deadline_0: U256,
+        amount_0_max: U256,
+        sig_0: Vec<u8>,
+        nonce_1: U256,
+        deadline_1: U256,
+        amount_1_max: U256,
+        sig_1: Vec<u8>,
+    ) -> Result<(U256, U256), Revert> {
+        let permit_0 = Permit2Args {
+            max_amount: amount_0_max,
+            nonce: nonce_0,
+            deadline: deadline_0,
+            sig: &sig_0,
+        };
+
+        let permit_1 = Permit2Args {
+            max_amount: amount_1_max,
+            nonce: nonce_1,
+            deadline: deadline_1,
+            sig: &sig_1,
+        };
+
+        self.adjust_position_internal(
+            pool,
+            id,
+            amount_0_min,
+            amount_1_min,
+            amount_0_max,
+            amount_1_max,
+            false,
+            Some((permit_0, permit_1)),
+        )
+    }
+
     /// Refreshes and updates liquidity in a position, transferring tokens to the user with restrictions.
     /// See [Self::adjust_position_internal].
     #[allow(non_snake_case)]
@@ -955,178 +1698,826 @@ impl Pools {
             None,
         )
     }
-}
 
-/// Admin functions. Only enabled when the `admin` feature is set.
-#[cfg_attr(feature = "admin", external)]
-impl Pools {
-    /// The initialiser function for the seawater contract. Should be called in the proxy's
-    /// constructor.
+    /// Places a range order, selling a token over the single tick spacing range starting at
+    /// `lower`, using approvals to transfer it. The order is filled, and its liquidity removed
+    /// from the pool, once a swap crosses the far side of the range. See
+    /// [pool::StoragePool::place_range_order].
     ///
-    ///  # Errors
-    ///  Requires the contract to not be initialised.
-    pub fn ctor(
+    /// # Arguments
+    /// * `pool` - The pool to place the order in.
+    /// * `lower` - The lower tick of the range.
+    /// * `zero_for_one` - True if selling token 0 for token 1, false if selling token 1.
+    /// * `liquidity` - The liquidity to add to the range.
+    ///
+    /// # Errors
+    /// Requires the range to be entirely above the current tick if selling token 0, or below it
+    /// if selling token 1. Requires the pool to be enabled, and adding liquidity to not be paused.
+    #[allow(non_snake_case)]
+    pub fn place_range_order_103_B25_D5(
         &mut self,
-        seawater_admin: Address,
-        nft_manager: Address,
-        emergency_council: Address,
-    ) -> Result<(), Revert> {
-        assert_eq_or!(
-            self.seawater_admin.get(),
-            Address::ZERO,
-            Error::ContractAlreadyInitialised
-        );
+        pool: Address,
+        lower: i32,
+        zero_for_one: bool,
+        liquidity: u128,
+    ) -> Result<(U256, U256), Revert> {
+        self.require_unpaused(pool, pool::PAUSE_ADDS)?;
 
-        self.seawater_admin.set(seawater_admin);
-        self.nft_manager.set(nft_manager);
-        self.emergency_council.set(emergency_council);
+        let (epoch, amount) = self.pools.setter(pool).place_range_order(
+            msg::sender(),
+            lower,
+            zero_for_one,
+            liquidity,
+        )?;
 
-        Ok(())
+        let token = match zero_for_one {
+            true => self.pool_token(pool),
+            false => self.pool_token_1(pool),
+        };
+        erc20::take(token, amount, None)?;
+
+        #[cfg(feature = "log-events")]
+        evm::log(events::RangeOrderPlaced {
+            user: msg::sender(),
+            pool,
+            epoch,
+            lower,
+            zeroForOne: zero_for_one,
+            liquidity,
+            amount,
+        });
+
+        Ok((epoch, amount))
     }
 
-    /// Creates a new pool. Only usable by the seawater admin.
-    ///
-    /// # Arguments
-    /// * `pool` - The address of the non-fluid token to construct the pool around.
-    /// * `price` - The initial price for the pool, as an X96 encoded square root price.
-    /// * `fee` - The fee for the pool.
-    /// * `tick_spacing` - The tick spacing for the pool.
-    /// * `max_liquidity_per_tick` - The maximum amount of liquidity allowed in a single tick.
+    /// Cancels the caller's unfilled range order in an epoch, sending them the tokens it's worth
+    /// and its share of the fees it earned. See [pool::StoragePool::cancel_range_order].
     ///
     /// # Errors
-    /// Requires the caller to be the seawater admin. Requires the pool to not exist.
+    /// Requires the epoch to be unfilled, and the caller to have an order in it. Requires the
+    /// pool to be enabled, and removing liquidity to not be paused.
     #[allow(non_snake_case)]
-    pub fn create_pool_D650_E2_D0(
+    pub fn cancel_range_order_11_E_E2063(
         &mut self,
         pool: Address,
-        price: U256,
-        fee: u32,
-        tick_spacing: u8,
-        max_liquidity_per_tick: u128,
-    ) -> Result<(), Revert> {
-        assert_eq_or!(
-            msg::sender(),
-            self.seawater_admin.get(),
-            Error::SeawaterAdminOnly
-        );
+        epoch: U256,
+    ) -> Result<(U256, U256), Revert> {
+        self.require_unpaused(pool, pool::PAUSE_REMOVALS)?;
 
-        self.pools
+        let (amount_0, amount_1) = self
+            .pools
             .setter(pool)
-            .init(price, fee, tick_spacing, max_liquidity_per_tick)?;
-
-        // get the decimals for the asset so we can log it's decimals for the indexer
+            .cancel_range_order(msg::sender(), epoch)?;
 
-        let _decimals = erc20::decimals(pool)?;
+        erc20::transfer_to_sender(self.pool_token(pool), amount_0)?;
+        erc20::transfer_to_sender(self.pool_token_1(pool), amount_1)?;
 
         #[cfg(feature = "log-events")]
-        evm::log(events::NewPool {
-            token: pool,
-            fee,
-            decimals: _decimals,
-            tickSpacing: tick_spacing,
+        evm::log(events::RangeOrderCancelled {
+            user: msg::sender(),
+            pool,
+            epoch,
+            amount0: amount_0,
+            amount1: amount_1,
         });
 
-        Ok(())
+        Ok((amount_0, amount_1))
     }
 
-    /// Getter method for the sqrt price
+    /// Claims the caller's filled range order in an epoch, sending them the tokens it was filled
+    /// with and its share of the fees it earned. See [pool::StoragePool::claim_range_order].
+    ///
+    /// # Errors
+    /// Requires the epoch to be filled, and the caller to have an order in it. Requires removing
+    /// liquidity to not be paused.
     #[allow(non_snake_case)]
-    pub fn sqrt_price_x967_B8_F5_F_C5(&self, pool: Address) -> Result<U256, Revert> {
-        Ok(self.pools.getter(pool).get_sqrt_price())
-    }
+    pub fn claim_range_order_11_F9_C034(
+        &mut self,
+        pool: Address,
+        epoch: U256,
+    ) -> Result<(U256, U256), Revert> {
+        self.require_unpaused(pool, pool::PAUSE_REMOVALS)?;
 
-    /// Getter method for the current tick
-    #[allow(non_snake_case)]
-    pub fn cur_tick181_C6_F_D9(&self, pool: Address) -> Result<i32, Revert> {
-        // converted to i32 for automatic abi encoding
-        Ok(self.pools.getter(pool).get_cur_tick().sys())
-    }
+        let (amount_0, amount_1) = self
+            .pools
+            .setter(pool)
+            .claim_range_order(msg::sender(), epoch)?;
 
-    #[allow(non_snake_case)]
-    pub fn fees_owed_22_F28_D_B_D(&self, pool: Address, id: U256) -> Result<(u128, u128), Revert> {
-        Ok(self.pools.getter(pool).get_fees_owed(id))
-    }
+        erc20::transfer_to_sender(self.pool_token(pool), amount_0)?;
+        erc20::transfer_to_sender(self.pool_token_1(pool), amount_1)?;
 
-    /// Getter method for the tick spacing of the pool given.
-    #[allow(non_snake_case)]
-    pub fn tick_spacing_653_F_E28_F(&self, pool: Address) -> Result<u8, Revert> {
-        // converted to i32 for automatic abi encoding
-        Ok(self.pools.getter(pool).get_tick_spacing().sys())
-    }
+        #[cfg(feature = "log-events")]
+        evm::log(events::RangeOrderClaimed {
+            user: msg::sender(),
+            pool,
+            epoch,
+            amount0: amount_0,
+            amount1: amount_1,
+        });
 
-    #[allow(non_snake_case)]
-    pub fn fee_B_B_3_C_F_608(&self, pool: Address) -> Result<u32, Revert> {
-        Ok(self.pools.getter(pool).get_fee())
+        Ok((amount_0, amount_1))
     }
+}
 
-    /// Getter method for getting the fee growth for token 0
-    #[allow(non_snake_case)]
-    pub fn fee_growth_global_0_38_B5665_B(&self, pool: Address) -> Result<U256, Revert> {
-        Ok(self.pools.getter(pool).get_fee_growth_global_0())
-    }
+/// Internal functions for access control.
+impl Pools {
+    /// Checks if an account holds a role, either directly or implicitly. See [roles].
+    fn has_role(&self, role: B256, account: Address) -> bool {
+        if account.is_zero() {
+            return false;
+        }
 
-    /// Getter method for getting the fee growth for token 1
-    #[allow(non_snake_case)]
-    pub fn fee_growth_global_1_A_33_A_5_A_1_B(&self, pool: Address) -> Result<U256, Revert> {
-        Ok(self.pools.getter(pool).get_fee_growth_global_1())
+        account == self.seawater_admin.get()
+            || self.roles.getter(role).get(account)
+            || (role == roles::PAUSER && account == self.emergency_council.get())
+            || (role == roles::POOL_CREATOR && self.authorised_enablers.get(account))
     }
 
-    /// Set the sqrt price for a pool. Only useful if the pool was
-    /// misconfigured (intentionally or otherwise) at the beginning of the
-    /// pool's life. Be careful with this!
-    #[allow(non_snake_case)]
-    pub fn set_sqrt_price_F_F_4_D_B_98_C(
-        &mut self,
-        pool: Address,
-        new_price: U256,
-    ) -> Result<(), Revert> {
+    /// Requires the caller to be the seawater admin, for functions that can't be delegated with a
+    /// role.
+    fn require_admin(&self) -> Result<(), Revert> {
         assert_eq_or!(
             msg::sender(),
             self.seawater_admin.get(),
             Error::SeawaterAdminOnly
         );
-
-        self.pools.setter(pool).set_sqrt_price(new_price);
-
         Ok(())
     }
 
-    /// Update the NFT manager that has trusted access to moving tokens on
-    /// behalf of users.
-    #[allow(non_snake_case)]
-    pub fn update_nft_manager_9_B_D_F_41_F_6(&mut self, manager: Address) -> Result<(), Revert> {
-        assert_eq_or!(
-            msg::sender(),
-            self.seawater_admin.get(),
-            Error::SeawaterAdminOnly
+    /// Requires the caller to hold a role.
+    fn require_role(&self, role: B256) -> Result<(), Revert> {
+        let account = msg::sender();
+        assert_or!(
+            self.has_role(role, account),
+            Error::MissingRole { role, account }
         );
-
-        self.nft_manager.set(manager);
-
         Ok(())
     }
 
-    /// Update the emergency council that can disable the pools.
-    #[allow(non_snake_case)]
-    pub fn update_emergency_council_7_D_0_C_1_C_58(
-        &mut self,
-        manager: Address,
+    /// Grants or revokes a role, logging the change.
+    fn set_role(&mut self, role: B256, account: Address, granted: bool) {
+        self.roles.setter(role).setter(account).set(granted);
+
+        // authorised enablers predate roles, and are migrated when their role changes
+        if role == roles::POOL_CREATOR {
+            self.authorised_enablers.setter(account).erase();
+        }
+
+        #[cfg(feature = "log-events")]
+        match granted {
+            true => evm::log(events::RoleGranted {
+                role,
+                account,
+                sender: msg::sender(),
+            }),
+            false => evm::log(events::RoleRevoked {
+                role,
+                account,
+                sender: msg::sender(),
+            }),
+        }
+    }
+}
+
+/// Internal functions for pausing operations.
+impl Pools {
+    /// Requires an operation to not be paused in a pool, by either the pool's pause flags or the
+    /// global pause, and a flash loan to not be in progress.
+    ///
+    /// # Errors
+    /// Requires every flag in `operation` to be unset, and Seawater to not be locked by a flash
+    /// loan.
+    fn require_unpaused(&self, pool: Address, operation: u8) -> Result<(), Revert> {
+        assert_or!(!self.flash_locked.get(), Error::FlashLoanLocked);
+
+        let mut flags = self.pools.getter(pool).get_pause_flags();
+        if self.globally_paused.get() {
+            flags |= pool::GLOBAL_PAUSE_FLAGS;
+        }
+
+        assert_eq_or!(flags & operation, 0, Error::OperationPaused);
+        Ok(())
+    }
+}
+
+/// Internal functions for finding and creating pools.
+impl Pools {
+    /// Gets token 0 of a pool. Pools created before pools had fee tiers are stored at their
+    /// token's address, so that's used if the pool doesn't have a token set. Since the fluid
+    /// token doesn't have a pool, this returns the fluid token for its own address.
+    fn pool_token(&self, pool: Address) -> Address {
+        match self.pools.getter(pool).get_token() {
+            token if token.is_zero() => pool,
+            token => token,
+        }
+    }
+
+    /// Gets token 1 of a pool, which is the fluid token unless the pool was created with
+    /// [Self::create_pair_pool].
+    fn pool_token_1(&self, pool: Address) -> Address {
+        match self.pools.getter(pool).get_token_1() {
+            token if token.is_zero() => FUSDC_ADDR,
+            token => token,
+        }
+    }
+
+    /// Checks that a pool trades against the fluid token, so it can be used as a hop in a
+    /// multi step swap.
+    fn require_fluid_pool(&self, pool: Address) -> Result<(), Error> {
+        assert_or!(
+            self.pools.getter(pool).get_token_1().is_zero(),
+            Error::InvalidSwapPath
+        );
+        Ok(())
+    }
+
+    /// Finds the pool for a token and fee, or returns the zero address if it doesn't exist.
+    fn find_pool(&self, token: Address, fee: u32) -> Address {
+        let id = pool::pool_id(token, FUSDC_ADDR, fee);
+        if !self.pools.getter(id).get_sqrt_price().is_zero() {
+            return id;
+        }
+
+        // pools stored at their token's address have a single fee tier
+        let legacy = self.pools.getter(token);
+        if !legacy.get_sqrt_price().is_zero() && legacy.get_fee() == fee {
+            return token;
+        }
+
+        Address::ZERO
+    }
+
+    /// Sorts the tokens of a pool that doesn't use the fluid token.
+    fn sort_pair(token_a: Address, token_b: Address) -> Result<(Address, Address), Error> {
+        assert_or!(
+            token_a != token_b
+                && !token_a.is_zero()
+                && !token_b.is_zero()
+                && token_a != FUSDC_ADDR
+                && token_b != FUSDC_ADDR,
+            Error::InvalidTokenPair
+        );
+
+        match token_a < token_b {
+            true => Ok((token_a, token_b)),
+            false => Ok((token_b, token_a)),
+        }
+    }
+
+    /// Creates a new, enabled, pool using an enabled fee tier, returning its ID.
+    fn create_tier_pool_internal(
+        &mut self,
+        token: Address,
+        token_1: Address,
+        price: U256,
+        fee: u32,
+    ) -> Result<Address, Revert> {
+        let tick_spacing = self.fee_tiers.get(fee).sys();
+        assert_neq_or!(tick_spacing, 0, Error::FeeTierNotEnabled);
+
+        let pool = pool::pool_id(token, token_1, fee);
+        self.create_pool_internal(
+            pool,
+            token,
+            token_1,
+            price,
+            fee,
+            tick_spacing,
+            tick_math::tick_spacing_to_max_liquidity_per_tick(tick_spacing),
+        )?;
+
+        self.pools.setter(pool).set_enabled(true);
+
+        #[cfg(feature = "log-events")]
+        evm::log(events::SetPoolEnabled {
+            pool,
+            enabled: true,
+            sender: msg::sender(),
+        });
+
+        Ok(pool)
+    }
+
+    /// Initialises a pool, logging its creation along with the decimals of token 0. `token_1` is
+    /// the fluid token for pools that trade against it.
+    ///
+    /// # Errors
+    /// Requires there to be no pool for the tokens and fee already. Requires token 0 to report
+    /// its decimals.
+    #[allow(clippy::too_many_arguments)]
+    fn create_pool_internal(
+        &mut self,
+        pool: Address,
+        token: Address,
+        token_1: Address,
+        price: U256,
+        fee: u32,
+        tick_spacing: u8,
+        max_liquidity_per_tick: u128,
+    ) -> Result<(), Revert> {
+        // pools with the fluid token might be stored at their token's address
+        if token_1 == FUSDC_ADDR {
+            assert_or!(
+                self.find_pool(token, fee).is_zero(),
+                Error::PoolAlreadyInitialised
+            );
+        }
+
+        let mut storage_pool = self.pools.setter(pool);
+        storage_pool.init(price, fee, tick_spacing, max_liquidity_per_tick)?;
+        storage_pool.set_token(token);
+        if token_1 != FUSDC_ADDR {
+            storage_pool.set_token_1(token_1);
+        }
+
+        // get the decimals for the asset so we can log it's decimals for the indexer
+
+        let _decimals = erc20::decimals(token)?;
+
+        #[cfg(feature = "log-events")]
+        evm::log(events::NewPool {
+            token,
+            fee,
+            decimals: _decimals,
+            tickSpacing: tick_spacing,
+            pool,
+            token1: token_1,
+        });
+
+        Ok(())
+    }
+}
+
+/// Internal functions for timelocked admin calls.
+impl Pools {
+    /// Executes a timelocked call, removing it from the queue. See [timelock].
+    ///
+    /// # Errors
+    /// Requires the call to have been queued, and for the current time to be between the end of
+    /// its delay and the end of its grace period.
+    fn execute_timelocked_call(&mut self, id: B256) -> Result<(), Revert> {
+        let eta = self.timelocked_calls.get(id).sys();
+        assert_neq_or!(eta, 0, Error::TimelockNotQueued);
+
+        let now = block::timestamp();
+        assert_or!(now >= eta, Error::TimelockNotReady);
+        assert_or!(
+            now <= eta + timelock::TIMELOCK_GRACE_PERIOD,
+            Error::TimelockExpired
+        );
+
+        self.timelocked_calls.setter(id).erase();
+
+        #[cfg(feature = "log-events")]
+        evm::log(events::TimelockedCallExecuted { id });
+
+        Ok(())
+    }
+}
+
+/// Admin functions. Only enabled when the `admin` feature is set.
+#[cfg_attr(feature = "admin", external)]
+impl Pools {
+    /// The initialiser function for the seawater contract. Should be called in the proxy's
+    /// constructor.
+    ///
+    ///  # Errors
+    ///  Requires the contract to not be initialised.
+    pub fn ctor(
+        &mut self,
+        seawater_admin: Address,
+        nft_manager: Address,
+        emergency_council: Address,
+    ) -> Result<(), Revert> {
+        assert_eq_or!(
+            self.seawater_admin.get(),
+            Address::ZERO,
+            Error::ContractAlreadyInitialised
+        );
+
+        self.seawater_admin.set(seawater_admin);
+        self.nft_manager.set(nft_manager);
+        self.emergency_council.set(emergency_council);
+
+        Ok(())
+    }
+
+    /// Creates a new pool, stored at the address of its token. Only usable by accounts with the
+    /// [roles::POOL_CREATOR] role. Pools for fee tiers are created with
+    /// [Self::create_pool_111360_A2] instead, so a token can have several pools.
+    ///
+    /// # Arguments
+    /// * `pool` - The address of the non-fluid token to construct the pool around, which is also
+    ///   the ID of the pool.
+    /// * `price` - The initial price for the pool, as an X96 encoded square root price.
+    /// * `fee` - The fee for the pool.
+    /// * `tick_spacing` - The tick spacing for the pool.
+    /// * `max_liquidity_per_tick` - The maximum amount of liquidity allowed in a single tick.
+    ///
+    /// # Errors
+    /// Requires the caller to have the [roles::POOL_CREATOR] role. Requires the pool, and any
+    /// other pool for the token with the same fee, to not exist.
+    #[allow(non_snake_case)]
+    pub fn create_pool_D650_E2_D0(
+        &mut self,
+        pool: Address,
+        price: U256,
+        fee: u32,
+        tick_spacing: u8,
+        max_liquidity_per_tick: u128,
+    ) -> Result<(), Revert> {
+        self.require_role(roles::POOL_CREATOR)?;
+
+        self.create_pool_internal(
+            pool,
+            pool,
+            FUSDC_ADDR,
+            price,
+            fee,
+            tick_spacing,
+            max_liquidity_per_tick,
+        )
+    }
+
+    /// Creates a new, enabled, pool using an enabled fee tier, which sets its tick spacing and
+    /// maximum liquidity per tick. See [Self::enable_fee_tier_120_D0_D_D_C]. Usable by anyone.
+    /// Returns the ID of the pool, see [pool::pool_id].
+    ///
+    /// # Arguments
+    /// * `token` - The address of the non-fluid token to construct the pool around.
+    /// * `price` - The initial price for the pool, as an X96 encoded square root price.
+    /// * `fee` - The fee for the pool, which must be an enabled fee tier.
+    ///
+    /// # Errors
+    /// Requires the token to not be the zero address or the fluid token. Requires the fee tier to
+    /// be enabled. Requires the token to not have a pool with the fee already. Requires the token
+    /// to report its decimals.
+    #[allow(non_snake_case)]
+    pub fn create_pool_111360_A2(
+        &mut self,
+        token: Address,
+        price: U256,
+        fee: u32,
+    ) -> Result<Address, Revert> {
+        assert_or!(
+            !token.is_zero() && token != FUSDC_ADDR,
+            Error::InvalidPoolToken
+        );
+
+        self.create_tier_pool_internal(token, FUSDC_ADDR, price, fee)
+    }
+
+    /// Creates a new, enabled, pool between two tokens that aren't the fluid token, using an
+    /// enabled fee tier. The tokens are sorted, so the lower address is token 0. Usable by
+    /// anyone. Returns the ID of the pool, see [pool::pool_id].
+    ///
+    /// Pools between two tokens can't be used as hops in multi step swaps, which always go
+    /// through the fluid token.
+    ///
+    /// # Arguments
+    /// * `token_a` - One of the tokens of the pool.
+    /// * `token_b` - The other token of the pool.
+    /// * `price` - The initial price for the pool, as an X96 encoded square root price of token 1
+    ///   in terms of token 0.
+    /// * `fee` - The fee for the pool, which must be an enabled fee tier.
+    ///
+    /// # Errors
+    /// Requires the tokens to be different, and to not be the fluid token. Requires the fee tier
+    /// to be enabled. Requires the pool to not exist. Requires token 0 to report its decimals.
+    #[allow(non_snake_case)]
+    pub fn create_pair_pool_111_D0_A09(
+        &mut self,
+        token_a: Address,
+        token_b: Address,
+        price: U256,
+        fee: u32,
+    ) -> Result<Address, Revert> {
+        let (token_0, token_1) = Self::sort_pair(token_a, token_b)?;
+        self.create_tier_pool_internal(token_0, token_1, price, fee)
+    }
+
+    /// Gets the ID of the pool between two tokens with a fee, in either order, or the zero
+    /// address if it doesn't exist. If one of the tokens is the fluid token, this is the same as
+    /// [Self::get_pool_12523831].
+    #[allow(non_snake_case)]
+    pub fn get_pair_pool_11957_B4_F(
+        &self,
+        token_a: Address,
+        token_b: Address,
+        fee: u32,
+    ) -> Result<Address, Revert> {
+        if token_a == FUSDC_ADDR {
+            return Ok(self.find_pool(token_b, fee));
+        }
+        if token_b == FUSDC_ADDR {
+            return Ok(self.find_pool(token_a, fee));
+        }
+
+        let (token_0, token_1) = Self::sort_pair(token_a, token_b)?;
+        let pool = pool::pool_id(token_0, token_1, fee);
+        match self.pools.getter(pool).get_sqrt_price().is_zero() {
+            true => Ok(Address::ZERO),
+            false => Ok(pool),
+        }
+    }
+
+    /// Gets the ID of the pool for a token and fee, or the zero address if it doesn't exist.
+    /// Pools created with [Self::create_pool_D650_E2_D0] are found using their fee, and have
+    /// their token's address as their ID.
+    #[allow(non_snake_case)]
+    pub fn get_pool_12523831(&self, token: Address, fee: u32) -> Result<Address, Revert> {
+        Ok(self.find_pool(token, fee))
+    }
+
+    /// Gets token 0 of a pool.
+    #[allow(non_snake_case)]
+    pub fn pool_token_10162_F39(&self, pool: Address) -> Result<Address, Revert> {
+        Ok(self.pool_token(pool))
+    }
+
+    /// Gets token 0 and token 1 of a pool.
+    #[allow(non_snake_case)]
+    pub fn pool_tokens_10591443(&self, pool: Address) -> Result<(Address, Address), Revert> {
+        Ok((self.pool_token(pool), self.pool_token_1(pool)))
+    }
+
+    /// Enables a fee tier, letting anyone create pools with the fee and tick spacing given. Fee
+    /// tiers can't be changed or disabled once enabled, like uniswap's `enableFeeAmount`.
+    ///
+    /// # Arguments
+    /// * `fee` - The fee for pools in the tier, in hundredths of a bip.
+    /// * `tick_spacing` - The tick spacing for pools in the tier.
+    ///
+    /// # Errors
+    /// Requires the caller to be the seawater admin. Requires the fee to be below 100%, and the
+    /// tick spacing to be non zero. Requires the fee tier to not be enabled.
+    #[allow(non_snake_case)]
+    pub fn enable_fee_tier_120_D0_D_D_C(
+        &mut self,
+        fee: u32,
+        tick_spacing: u8,
+    ) -> Result<(), Revert> {
+        self.require_admin()?;
+
+        assert_or!(fee < 1_000_000 && tick_spacing > 0, Error::InvalidFeeTier);
+        assert_eq_or!(
+            self.fee_tiers.get(fee).sys(),
+            0,
+            Error::FeeTierAlreadyEnabled
+        );
+
+        self.fee_tiers.setter(fee).set(U8::lib(&tick_spacing));
+
+        #[cfg(feature = "log-events")]
+        evm::log(events::FeeTierEnabled {
+            fee,
+            tickSpacing: tick_spacing,
+        });
+
+        Ok(())
+    }
+
+    /// Returns the tick spacing of a fee tier, or 0 if it isn't enabled.
+    #[allow(non_snake_case)]
+    pub fn fee_tier_tick_spacing_10_D66666(&self, fee: u32) -> Result<u8, Revert> {
+        Ok(self.fee_tiers.get(fee).sys())
+    }
+
+    /// Getter method for the sqrt price
+    #[allow(non_snake_case)]
+    pub fn sqrt_price_x967_B8_F5_F_C5(&self, pool: Address) -> Result<U256, Revert> {
+        Ok(self.pools.getter(pool).get_sqrt_price())
+    }
+
+    /// Getter method for the current tick
+    #[allow(non_snake_case)]
+    pub fn cur_tick181_C6_F_D9(&self, pool: Address) -> Result<i32, Revert> {
+        // converted to i32 for automatic abi encoding
+        Ok(self.pools.getter(pool).get_cur_tick().sys())
+    }
+
+    /// Gets the cumulative tick and seconds per liquidity of a pool at each of a number of
+    /// seconds ago, for computing time weighted averages. See [oracle].
+    ///
+    /// # Errors
+    /// Requires the pool's oracle to be initialised, and each time to be at or after its oldest
+    /// observation. See [Self::increase_observation_cardinality_next_100_D2_A_B9].
+    #[allow(non_snake_case)]
+    pub fn observe_106_B4_A_B6(
+        &self,
+        pool: Address,
+        seconds_agos: Vec<u32>,
+    ) -> Result<(Vec<i64>, Vec<U256>), Revert> {
+        let (tick_cumulatives, seconds_per_liquidity_cumulatives) =
+            self.pools.getter(pool).observe(&seconds_agos)?;
+
+        // uint160 has the same encoding as uint256
+        Ok((
+            tick_cumulatives,
+            seconds_per_liquidity_cumulatives
+                .into_iter()
+                .map(U256::from)
+                .collect(),
+        ))
+    }
+
+    /// Gets the seconds per liquidity, as an X128 number, and the seconds, that a pool's price
+    /// has spent inside a range, for tracking how long liquidity over the range was in use. Only
+    /// meaningful compared to an earlier value taken while the range's ticks were initialised.
+    ///
+    /// # Errors
+    /// Requires both ticks to be initialised, as they are while a position over them has
+    /// liquidity.
+    #[allow(non_snake_case)]
+    pub fn seconds_per_liquidity_inside_11_B8_A254(
+        &self,
+        pool: Address,
+        lower: i32,
+        upper: i32,
+    ) -> Result<(U256, u32), Revert> {
+        let (seconds_per_liquidity_inside, seconds_inside) = self
+            .pools
+            .getter(pool)
+            .seconds_per_liquidity_inside(lower, upper)?;
+
+        // uint160 has the same encoding as uint256
+        Ok((U256::from(seconds_per_liquidity_inside), seconds_inside))
+    }
+
+    /// Gets the state of a pool's oracle, as the index of the most recent observation, the
+    /// number of observations kept, and the number that will be kept once the buffer wraps.
+    #[allow(non_snake_case)]
+    pub fn observation_state_105820_A6(&self, pool: Address) -> Result<(u16, u16, u16), Revert> {
+        Ok(self.pools.getter(pool).oracle.state())
+    }
+
+    /// Increases the number of observations a pool's oracle keeps, letting it look further back
+    /// in time. Usable by anyone, who pays to write to the new observations.
+    ///
+    /// # Errors
+    /// Requires the pool's oracle to be initialised.
+    #[allow(non_snake_case)]
+    pub fn increase_observation_cardinality_next_100_D2_A_B9(
+        &mut self,
+        pool: Address,
+        next: u16,
+    ) -> Result<(), Revert> {
+        let (_old, _new) = self.pools.setter(pool).oracle.grow(next)?;
+
+        #[cfg(feature = "log-events")]
+        if _old != _new {
+            evm::log(events::IncreaseObservationCardinalityNext {
+                pool,
+                observationCardinalityNextOld: _old,
+                observationCardinalityNextNew: _new,
+            });
+        }
+
+        Ok(())
+    }
+
+    #[allow(non_snake_case)]
+    pub fn fees_owed_22_F28_D_B_D(&self, pool: Address, id: U256) -> Result<(u128, u128), Revert> {
+        Ok(self.pools.getter(pool).get_fees_owed(id))
+    }
+
+    /// Getter method for the tick spacing of the pool given.
+    #[allow(non_snake_case)]
+    pub fn tick_spacing_653_F_E28_F(&self, pool: Address) -> Result<u8, Revert> {
+        // converted to i32 for automatic abi encoding
+        Ok(self.pools.getter(pool).get_tick_spacing().sys())
+    }
+
+    #[allow(non_snake_case)]
+    pub fn fee_B_B_3_C_F_608(&self, pool: Address) -> Result<u32, Revert> {
+        Ok(self.pools.getter(pool).get_fee())
+    }
+
+    /// Getter method for getting the fee growth for token 0
+    #[allow(non_snake_case)]
+    pub fn fee_growth_global_0_38_B5665_B(&self, pool: Address) -> Result<U256, Revert> {
+        Ok(self.pools.getter(pool).get_fee_growth_global_0())
+    }
+
+    /// Getter method for getting the fee growth for token 1
+    #[allow(non_snake_case)]
+    pub fn fee_growth_global_1_A_33_A_5_A_1_B(&self, pool: Address) -> Result<U256, Revert> {
+        Ok(self.pools.getter(pool).get_fee_growth_global_1())
+    }
+
+    /// Getter method for the initialised ticks of a pool within a range, for depth charts. See
+    /// [pool::StoragePool::get_initialised_ticks].
+    ///
+    /// # Arguments
+    /// * `pool` - The pool to get the ticks of.
+    /// * `from` - The lowest tick to return, inclusive.
+    /// * `to` - The highest tick to return, inclusive.
+    /// * `limit` - The maximum number of ticks to return.
+    ///
+    /// Returns the ticks found, and the tick to use as `from` to get the next page of ticks. Pages
+    /// over sparse ranges can end early with fewer than `limit` ticks, see
+    /// [pool::MAX_TICK_BITMAP_WORDS].
+    #[allow(non_snake_case)]
+    pub fn ticks_in_range_132_A_C_C_E_E(
+        &self,
+        pool: Address,
+        from: i32,
+        to: i32,
+        limit: u32,
+    ) -> Result<(Vec<tick::InitialisedTick>, i32), Revert> {
+        self.pools
+            .getter(pool)
+            .get_initialised_ticks(from, to, limit)
+    }
+
+    /// Set the sqrt price for a pool. Only useful if the pool was
+    /// misconfigured (intentionally or otherwise) at the beginning of the
+    /// pool's life. Be careful with this!
+    ///
+    /// # Errors
+    /// Requires the caller to have the [roles::PRICE_SETTER] role. Requires the call to have been
+    /// queued by the seawater admin with [Self::queue_timelocked_call_10_C89285], and its delay
+    /// to have passed.
+    #[allow(non_snake_case)]
+    pub fn set_sqrt_price_F_F_4_D_B_98_C(
+        &mut self,
+        pool: Address,
+        new_price: U256,
     ) -> Result<(), Revert> {
-        assert_eq_or!(
-            msg::sender(),
-            self.seawater_admin.get(),
-            Error::SeawaterAdminOnly
-        );
+        self.require_role(roles::PRICE_SETTER)?;
+
+        self.execute_timelocked_call(timelock::call_id(
+            timelock::SET_SQRT_PRICE_SELECTOR,
+            &[timelock::address_arg(pool), timelock::uint_arg(new_price)],
+        ))?;
+
+        let mut storage_pool = self.pools.setter(pool);
+
+        let _old_price = storage_pool.get_sqrt_price();
+
+        storage_pool.set_sqrt_price(new_price);
+
+        #[cfg(feature = "log-events")]
+        evm::log(events::SqrtPriceOverridden {
+            pool,
+            oldPrice: _old_price,
+            newPrice: new_price,
+        });
+
+        Ok(())
+    }
+
+    /// Update the NFT manager that has trusted access to moving tokens on
+    /// behalf of users.
+    ///
+    /// # Errors
+    /// Requires the caller to be the seawater admin. Requires the call to have been queued with
+    /// [Self::queue_timelocked_call_10_C89285], and its delay to have passed.
+    #[allow(non_snake_case)]
+    pub fn update_nft_manager_9_B_D_F_41_F_6(&mut self, manager: Address) -> Result<(), Revert> {
+        self.require_admin()?;
+
+        self.execute_timelocked_call(timelock::call_id(
+            timelock::UPDATE_NFT_MANAGER_SELECTOR,
+            &[timelock::address_arg(manager)],
+        ))?;
+
+        #[cfg(feature = "log-events")]
+        evm::log(events::NftManagerUpdated {
+            oldManager: self.nft_manager.get(),
+            newManager: manager,
+        });
 
         self.nft_manager.set(manager);
 
         Ok(())
     }
 
-    /// Collects protocol fees from the AMM. Only usable by the seawater admin.
+    /// Update the emergency council that can disable the pools.
+    #[allow(non_snake_case)]
+    pub fn update_emergency_council_7_D_0_C_1_C_58(
+        &mut self,
+        manager: Address,
+    ) -> Result<(), Revert> {
+        self.require_admin()?;
+
+        #[cfg(feature = "log-events")]
+        evm::log(events::EmergencyCouncilUpdated {
+            oldCouncil: self.emergency_council.get(),
+            newCouncil: manager,
+        });
+
+        self.emergency_council.set(manager);
+
+        Ok(())
+    }
+
+    /// Collects protocol fees from the AMM. Only usable by accounts with the
+    /// [roles::FEE_COLLECTOR] role.
     ///
     /// # Errors
-    /// Requires the user to be the seawater admin. Requires the pool to be enabled.
+    /// Requires the user to have the [roles::FEE_COLLECTOR] role. Requires the pool to be enabled,
+    /// and fee collection to not be paused.
     #[allow(non_snake_case)]
     pub fn collect_protocol_7540_F_A_9_F(
         &mut self,
@@ -1135,19 +2526,16 @@ impl Pools {
         amount_1: u128,
         recipient: Address,
     ) -> Result<(u128, u128), Revert> {
-        assert_eq_or!(
-            msg::sender(),
-            self.seawater_admin.get(),
-            Error::SeawaterAdminOnly
-        );
+        self.require_role(roles::FEE_COLLECTOR)?;
+        self.require_unpaused(pool, pool::PAUSE_FEE_COLLECTION)?;
 
         let (token_0, token_1) = self
             .pools
             .setter(pool)
             .collect_protocol(amount_0, amount_1)?;
 
-        erc20::transfer_to_addr(recipient, pool, U256::from(token_0))?;
-        erc20::transfer_to_addr(recipient, FUSDC_ADDR, U256::from(token_1))?;
+        erc20::transfer_to_addr(self.pool_token(pool), recipient, U256::from(token_0))?;
+        erc20::transfer_to_addr(self.pool_token_1(pool), recipient, U256::from(token_1))?;
 
         #[cfg(feature = "log-events")]
         evm::log(events::CollectProtocolFees {
@@ -1161,46 +2549,307 @@ impl Pools {
         Ok((token_0, token_1))
     }
 
-    /// Changes if a pool is enabled. Only usable by the seawater admin, or the emergency council, or the
+    /// Sets the protocol's share of the swap fees for a pool. Only usable by the seawater admin.
+    ///
+    /// # Arguments
+    /// * `pool` - The pool to set the protocol fee for.
+    /// * `fee_protocol_0` - The protocol fee denominator for token 0, either 0 or between 4 and 10.
+    /// * `fee_protocol_1` - The protocol fee denominator for token 1, either 0 or between 4 and 10.
+    ///
+    /// # Errors
+    /// Requires the user to be the seawater admin. Requires the fee protocols to be valid.
+    #[allow(non_snake_case)]
+    pub fn set_fee_protocol_1004_E_D95(
+        &mut self,
+        pool: Address,
+        fee_protocol_0: u8,
+        fee_protocol_1: u8,
+    ) -> Result<(), Revert> {
+        self.require_admin()?;
+
+        let mut storage_pool = self.pools.setter(pool);
+
+        let (_fee_protocol_0_old, _fee_protocol_1_old) = storage_pool.get_fee_protocol();
+
+        storage_pool.set_fee_protocol(fee_protocol_0, fee_protocol_1)?;
+
+        #[cfg(feature = "log-events")]
+        evm::log(events::SetFeeProtocol {
+            pool,
+            feeProtocol0Old: _fee_protocol_0_old,
+            feeProtocol1Old: _fee_protocol_1_old,
+            feeProtocol0New: fee_protocol_0,
+            feeProtocol1New: fee_protocol_1,
+        });
+
+        Ok(())
+    }
+
+    /// Changes if a pool is enabled. Only usable by accounts with the [roles::POOL_CREATOR] role,
+    /// or the [roles::PAUSER] role (held by the emergency council) to disable pools.
     ///
     /// # Errors
-    /// Requires the user to be the seawater admin.
+    /// Requires the user to have the [roles::POOL_CREATOR] role, or the [roles::PAUSER] role if
+    /// disabling the pool.
     #[allow(non_snake_case)]
     pub fn enable_pool_579_D_A658(&mut self, pool: Address, enabled: bool) -> Result<(), Revert> {
-        assert_or!(
-            self.seawater_admin.get() == msg::sender()
-                || self.emergency_council.get() == msg::sender()
-                || self.authorised_enablers.get(msg::sender()),
-            Error::SeawaterAdminOnly
-        );
+        let sender = msg::sender();
+        if !self.has_role(roles::POOL_CREATOR, sender) {
+            assert_or!(
+                self.has_role(roles::PAUSER, sender),
+                Error::MissingRole {
+                    role: roles::POOL_CREATOR,
+                    account: sender,
+                }
+            );
 
-        if self.emergency_council.get() == msg::sender()
-            && self.seawater_admin.get() != msg::sender()
-            && enabled
-        {
-            // Emergency council can only disable!
-            return Err(Error::SeawaterEmergencyOnlyDisable.into());
+            // pausers can only disable!
+            assert_or!(!enabled, Error::SeawaterEmergencyOnlyDisable);
         }
 
         self.pools.setter(pool).set_enabled(enabled);
+
+        #[cfg(feature = "log-events")]
+        evm::log(events::SetPoolEnabled {
+            pool,
+            enabled,
+            sender: msg::sender(),
+        });
+
+        Ok(())
+    }
+
+    /// Pauses or unpauses swaps and liquidity adds in every pool, see
+    /// [pool::GLOBAL_PAUSE_FLAGS]. LPs can still remove liquidity and collect fees while the
+    /// global pause is active.
+    ///
+    /// # Errors
+    /// Requires the caller to have the [roles::PAUSER] role to pause, or to be the seawater admin
+    /// to unpause.
+    #[allow(non_snake_case)]
+    pub fn set_global_pause_10803050(&mut self, paused: bool) -> Result<(), Revert> {
+        match paused {
+            true => self.require_role(roles::PAUSER)?,
+            false => self.require_admin()?,
+        }
+
+        self.globally_paused.set(paused);
+
+        #[cfg(feature = "log-events")]
+        evm::log(events::GlobalPauseUpdated {
+            paused,
+            sender: msg::sender(),
+        });
+
+        Ok(())
+    }
+
+    /// Sets the operations paused in a pool, as a combination of the `PAUSE_*` flags in [pool].
+    /// Unlike disabling the pool, this can halt swaps while still letting LPs withdraw.
+    ///
+    /// # Errors
+    /// Requires the caller to have the [roles::PAUSER] role to pause operations, and the
+    /// [roles::POOL_CREATOR] role to unpause them. Requires the flags to be valid.
+    #[allow(non_snake_case)]
+    pub fn set_pool_pause_flags_132_B43_A7(
+        &mut self,
+        pool: Address,
+        flags: u8,
+    ) -> Result<(), Revert> {
+        let old_flags = self.pools.getter(pool).get_pause_flags();
+
+        if flags & !old_flags != 0 {
+            self.require_role(roles::PAUSER)?;
+        }
+        if old_flags & !flags != 0 {
+            self.require_role(roles::POOL_CREATOR)?;
+        }
+
+        self.pools.setter(pool).set_pause_flags(flags)?;
+
+        #[cfg(feature = "log-events")]
+        evm::log(events::PoolPauseFlagsUpdated {
+            pool,
+            flags,
+            sender: msg::sender(),
+        });
+
         Ok(())
     }
 
+    /// Returns if the global pause is active, and the operations paused in a pool.
+    #[allow(non_snake_case)]
+    pub fn pause_state_109451_A3(&self, pool: Address) -> Result<(bool, u8), Revert> {
+        Ok((
+            self.globally_paused.get(),
+            self.pools.getter(pool).get_pause_flags(),
+        ))
+    }
+
+    /// Authorises an account to create and enable pools, granting or revoking the
+    /// [roles::POOL_CREATOR] role.
+    ///
+    /// # Errors
+    /// Requires the caller to be the seawater admin.
     #[allow(non_snake_case)]
     pub fn authorise_enabler_5_B_17_C_274(
         &mut self,
         enabler: Address,
         enabled: bool,
     ) -> Result<(), Revert> {
-        assert_or!(
-            self.seawater_admin.get() == msg::sender(),
-            Error::SeawaterAdminOnly
+        self.require_admin()?;
+
+        self.set_role(roles::POOL_CREATOR, enabler, enabled);
+
+        #[cfg(feature = "log-events")]
+        evm::log(events::EnablerAuthorised { enabler, enabled });
+
+        Ok(())
+    }
+
+    /// Grants a role to an account. See [roles].
+    ///
+    /// # Errors
+    /// Requires the caller to be the seawater admin.
+    #[allow(non_snake_case)]
+    pub fn grant_role_106_A_D_F_C9(&mut self, role: B256, account: Address) -> Result<(), Revert> {
+        self.require_admin()?;
+
+        self.set_role(role, account, true);
+
+        Ok(())
+    }
+
+    /// Revokes a role from an account. Roles held implicitly, by the seawater admin or the
+    /// emergency council, can't be revoked.
+    ///
+    /// # Errors
+    /// Requires the caller to be the seawater admin.
+    #[allow(non_snake_case)]
+    pub fn revoke_role_105_C41_F0(&mut self, role: B256, account: Address) -> Result<(), Revert> {
+        self.require_admin()?;
+
+        self.set_role(role, account, false);
+
+        Ok(())
+    }
+
+    /// Gives up a role held by the caller.
+    #[allow(non_snake_case)]
+    pub fn renounce_role_10_A8689_C(&mut self, role: B256) -> Result<(), Revert> {
+        self.set_role(role, msg::sender(), false);
+
+        Ok(())
+    }
+
+    /// Checks if an account holds a role, including roles held implicitly by the seawater admin,
+    /// the emergency council, and authorised enablers.
+    #[allow(non_snake_case)]
+    pub fn has_role_10_A57_E_B4(&self, role: B256, account: Address) -> Result<bool, Revert> {
+        Ok(self.has_role(role, account))
+    }
+
+    /// Proposes a new seawater admin, who has no control until they accept with
+    /// [Self::accept_admin_1023_F2_F_A]. Proposing the zero address cancels a pending transfer.
+    ///
+    /// # Errors
+    /// Requires the caller to be the seawater admin.
+    #[allow(non_snake_case)]
+    pub fn transfer_admin_1068832_B(&mut self, new_admin: Address) -> Result<(), Revert> {
+        self.require_admin()?;
+
+        self.pending_admin.set(new_admin);
+
+        #[cfg(feature = "log-events")]
+        evm::log(events::AdminTransferStarted {
+            currentAdmin: msg::sender(),
+            pendingAdmin: new_admin,
+        });
+
+        Ok(())
+    }
+
+    /// Accepts a proposed admin transfer, making the caller the seawater admin.
+    ///
+    /// # Errors
+    /// Requires the caller to be the pending admin.
+    #[allow(non_snake_case)]
+    pub fn accept_admin_1023_F2_F_A(&mut self) -> Result<(), Revert> {
+        let new_admin = msg::sender();
+        assert_eq_or!(new_admin, self.pending_admin.get(), Error::PendingAdminOnly);
+
+        #[cfg(feature = "log-events")]
+        evm::log(events::AdminTransferred {
+            oldAdmin: self.seawater_admin.get(),
+            newAdmin: new_admin,
+        });
+
+        self.seawater_admin.set(new_admin);
+        self.pending_admin.erase();
+
+        Ok(())
+    }
+
+    /// Returns the admin proposed by the current admin, or the zero address if there isn't one.
+    #[allow(non_snake_case)]
+    pub fn pending_admin_1062_B366(&self) -> Result<Address, Revert> {
+        Ok(self.pending_admin.get())
+    }
+
+    /// Queues a timelocked admin call, which can be executed once [timelock::TIMELOCK_DELAY] has
+    /// passed. Returns the timestamp the call can be executed from.
+    ///
+    /// # Arguments
+    /// * `id` - The keccak256 hash of the calldata of the call. See [timelock].
+    ///
+    /// # Errors
+    /// Requires the caller to be the seawater admin. Requires the call to not already be queued.
+    #[allow(non_snake_case)]
+    pub fn queue_timelocked_call_10_C89285(&mut self, id: B256) -> Result<u64, Revert> {
+        self.require_admin()?;
+
+        assert_eq_or!(
+            self.timelocked_calls.get(id).sys(),
+            0,
+            Error::TimelockAlreadyQueued
+        );
+
+        let eta = block::timestamp() + timelock::TIMELOCK_DELAY;
+        self.timelocked_calls.setter(id).set(U64::lib(&eta));
+
+        #[cfg(feature = "log-events")]
+        evm::log(events::TimelockedCallQueued { id, eta });
+
+        Ok(eta)
+    }
+
+    /// Cancels a queued timelocked admin call.
+    ///
+    /// # Errors
+    /// Requires the caller to be the seawater admin. Requires the call to be queued.
+    #[allow(non_snake_case)]
+    pub fn cancel_timelocked_call_114_D_F1_E3(&mut self, id: B256) -> Result<(), Revert> {
+        self.require_admin()?;
+
+        assert_neq_or!(
+            self.timelocked_calls.get(id).sys(),
+            0,
+            Error::TimelockNotQueued
         );
 
-        self.authorised_enablers.setter(enabler).set(enabled);
+        self.timelocked_calls.setter(id).erase();
+
+        #[cfg(feature = "log-events")]
+        evm::log(events::TimelockedCallCancelled { id });
 
         Ok(())
     }
+
+    /// Returns the timestamp a timelocked call can be executed from, or 0 if it isn't queued.
+    #[allow(non_snake_case)]
+    pub fn timelocked_call_eta_106_B4_D2_F(&self, id: B256) -> Result<u64, Revert> {
+        Ok(self.timelocked_calls.get(id).sys())
+    }
 }
 
 ///! Migrations code that should only be used in a testing environment, or in a rescue
@@ -1208,27 +2857,109 @@ impl Pools {
 #[cfg_attr(feature = "migrations", external)]
 impl Pools {
     pub fn disable_pools(&mut self, pools: Vec<Address>) -> Result<(), Vec<u8>> {
-        assert_eq_or!(
-            msg::sender(),
-            self.seawater_admin.get(),
-            Error::SeawaterAdminOnly
-        );
+        self.require_role(roles::PAUSER)?;
 
         for pool in pools {
             self.pools.setter(pool).set_enabled(false);
+
+            #[cfg(feature = "log-events")]
+            evm::log(events::SetPoolEnabled {
+                pool,
+                enabled: false,
+                sender: msg::sender(),
+            });
         }
 
         Ok(())
     }
 
+    /// Sends tokens held by the AMM to the caller.
+    ///
+    /// # Errors
+    /// Requires the caller to have the [roles::MIGRATOR] role. Requires the call to have been
+    /// queued by the seawater admin with [Self::queue_timelocked_call_10_C89285], and its delay
+    /// to have passed.
     pub fn send_token_to_sender(&mut self, token: Address, amount: U256) -> Result<(), Vec<u8>> {
+        self.require_role(roles::MIGRATOR)?;
+
+        self.execute_timelocked_call(timelock::call_id(
+            timelock::SEND_TOKEN_TO_SENDER_SELECTOR,
+            &[timelock::address_arg(token), timelock::uint_arg(amount)],
+        ))?;
+
+        erc20::transfer_to_sender(token, amount)?;
+
+        #[cfg(feature = "log-events")]
+        evm::log(events::EmergencyWithdrawal {
+            token,
+            to: msg::sender(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Rebuilds a pool's tick bitmap between two ticks from its initialised ticks, so liquidity
+    /// below tick 0 that was added before the bitmap set negative ticks becomes visible to swaps.
+    ///
+    /// # Errors
+    /// Requires the caller to have the [roles::MIGRATOR] role. Requires lower to be at most upper.
+    pub fn rebuild_tick_bitmap(
+        &mut self,
+        pool: Address,
+        lower: i32,
+        upper: i32,
+    ) -> Result<(), Revert> {
+        self.require_role(roles::MIGRATOR)?;
+
+        self.pools.setter(pool).rebuild_tick_bitmap(lower, upper)
+    }
+
+    /// Rewrites the enumeration of an owner's positions, for owners holding positions granted
+    /// before positions were enumerated. The IDs must be every position the owner holds.
+    ///
+    /// # Errors
+    /// Requires the caller to have the [roles::MIGRATOR] role. Requires every ID to be owned by
+    /// the owner, and there to be as many distinct IDs as positions the owner holds.
+    pub fn backfill_position_enumeration(
+        &mut self,
+        owner: Address,
+        ids: Vec<U256>,
+    ) -> Result<(), Revert> {
+        self.require_role(roles::MIGRATOR)?;
+
         assert_eq_or!(
-            msg::sender(),
-            self.seawater_admin.get(),
-            Error::SeawaterAdminOnly
+            U256::from(ids.len()),
+            self.owned_positions.get(owner),
+            Error::PositionIndexOutOfBounds
         );
 
-        erc20::transfer_to_sender(token, amount)?;
+        for (index, &id) in ids.iter().enumerate() {
+            assert_eq_or!(
+                self.position_owners.get(id),
+                owner,
+                Error::PositionOwnerOnly
+            );
+
+            let index = U256::from(index);
+            self.owned_position_ids.setter(owner).setter(index).set(id);
+            self.owned_position_index
+                .setter(id)
+                .set(index + U256::one());
+        }
+
+        // a repeated ID would leave its first index pointing at a later one
+        for (index, &id) in ids.iter().enumerate() {
+            assert_eq_or!(
+                self.owned_position_index.get(id),
+                U256::from(index + 1),
+                Error::PositionIndexOutOfBounds
+            );
+        }
+
+        self.enumerated_positions
+            .setter(owner)
+            .set(U256::from(ids.len()));
 
         Ok(())
     }
@@ -1239,14 +2970,17 @@ impl Pools {
         recipient_addrs: Vec<Address>,
         recipient_amounts: Vec<U256>,
     ) -> Result<(), Revert> {
-        assert_eq_or!(
-            msg::sender(),
-            self.seawater_admin.get(),
-            Error::SeawaterAdminOnly
-        );
+        self.require_role(roles::MIGRATOR)?;
 
         for (addr, amount) in recipient_addrs.iter().zip(recipient_amounts.iter()) {
             erc20::take_from_to(token, *addr, *amount)?;
+
+            #[cfg(feature = "log-events")]
+            evm::log(events::EmergencyDistribution {
+                token,
+                recipient: *addr,
+                amount: *amount,
+            });
         }
 
         Ok(())
@@ -3,6 +3,25 @@
 use crate::tick::TickBitmap;
 use crate::types::{U256Extension, U256};
 use crate::{error::Error, maths::bit_math};
+use alloc::collections::BTreeMap;
+
+/// A tick bitmap that can be read a word at a time, either from storage or from memory.
+pub trait BitmapWords {
+    /// Gets a word of the bitmap, which is zero if no ticks in it are initialised.
+    fn word(&self, word_pos: i16) -> U256;
+}
+
+impl BitmapWords for TickBitmap {
+    fn word(&self, word_pos: i16) -> U256 {
+        self.get(word_pos)
+    }
+}
+
+impl BitmapWords for BTreeMap<i16, U256> {
+    fn word(&self, word_pos: i16) -> U256 {
+        self.get(&word_pos).copied().unwrap_or_default()
+    }
+}
 
 //Returns next and initialized
 //current_word is the current word in the TickBitmap of the pool based on `tick`. TickBitmap[word_pos] = current_word
@@ -12,7 +31,7 @@ use crate::{error::Error, maths::bit_math};
 ///
 /// Returns the tick index and if it was initialised.
 pub fn next_initialized_tick_within_one_word(
-    tick_bitmap: &TickBitmap,
+    tick_bitmap: &impl BitmapWords,
     tick: i32,
     tick_spacing: i32,
     lte: bool,
@@ -28,7 +47,7 @@ pub fn next_initialized_tick_within_one_word(
 
         let mask = (U256::one() << bit_pos) - U256::one() + (U256::one() << bit_pos);
 
-        let masked = tick_bitmap.get(word_pos) & mask;
+        let masked = tick_bitmap.word(word_pos) & mask;
 
         let initialized = !masked.is_zero();
 
@@ -48,7 +67,7 @@ pub fn next_initialized_tick_within_one_word(
 
         let mask = !((U256::one() << bit_pos) - U256::one());
 
-        let masked = tick_bitmap.get(word_pos) & mask;
+        let masked = tick_bitmap.word(word_pos) & mask;
 
         let initialized = !masked.is_zero();
 
@@ -100,12 +100,40 @@ pub fn get_max_tick(spacing: u8) -> i32 {
     (MAX_TICK / spacing) * spacing
 }
 
+/// Derives the maximum liquidity that can reference a single tick from the tick spacing, so that
+/// liquidity can't overflow a u128 even if every usable tick is referenced.
+pub fn tick_spacing_to_max_liquidity_per_tick(spacing: u8) -> u128 {
+    let num_ticks = (get_max_tick(spacing) - get_min_tick(spacing)) / spacing as i32 + 1;
+    u128::MAX / num_ticks as u128
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::types::U256;
     use std::ops::Sub;
 
+    #[test]
+    fn tick_spacing_to_max_liquidity_per_tick_values() {
+        // taken from uniswap's tests
+        assert_eq!(
+            tick_spacing_to_max_liquidity_per_tick(10),
+            1917569901783203986719870431555990
+        );
+        assert_eq!(
+            tick_spacing_to_max_liquidity_per_tick(60),
+            11505743598341114571880798222544994
+        );
+        assert_eq!(
+            tick_spacing_to_max_liquidity_per_tick(200),
+            38350317471085141830651933667504588
+        );
+        assert_eq!(
+            tick_spacing_to_max_liquidity_per_tick(1),
+            u128::MAX / 1774545
+        );
+    }
+
     #[test]
     fn get_sqrt_ratio_at_tick_bounds() {
         // the function should return an error if the tick is out of bounds
@@ -0,0 +1,366 @@
+//! Price and liquidity observations for a pool, kept in a ring buffer so integrations can compute
+//! time weighted averages that are hard to manipulate. A port of uniswap v3's `Oracle` library.
+
+use crate::error::Error;
+use crate::types::{WrappedNative, I64, U16, U160, U256, U32};
+use alloc::vec::Vec;
+use stylus_sdk::{prelude::*, storage::*};
+
+/// An observation of a pool's cumulative tick and seconds per liquidity at a point in time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Observation {
+    pub block_timestamp: u32,
+    /// The tick, multiplied by the seconds it was the current tick for, summed since the pool
+    /// was initialised.
+    pub tick_cumulative: i64,
+    /// The seconds elapsed divided by the in range liquidity, as an X128 number, summed since
+    /// the pool was initialised.
+    pub seconds_per_liquidity_cumulative_x128: U160,
+    pub initialised: bool,
+}
+
+impl Observation {
+    /// Moves an observation forward to a later time, given the tick and liquidity in between.
+    fn transform(&self, block_timestamp: u32, tick: i32, liquidity: u128) -> Observation {
+        let delta = block_timestamp.wrapping_sub(self.block_timestamp);
+        let liquidity = U160::from(u128::max(liquidity, 1));
+
+        Observation {
+            block_timestamp,
+            tick_cumulative: self
+                .tick_cumulative
+                .wrapping_add(tick as i64 * delta as i64),
+            seconds_per_liquidity_cumulative_x128: self
+                .seconds_per_liquidity_cumulative_x128
+                .wrapping_add((U160::from(delta) << 128) / liquidity),
+            initialised: true,
+        }
+    }
+}
+
+/// Storage type for an [Observation].
+#[solidity_storage]
+pub struct StorageObservation {
+    block_timestamp: StorageU32,
+    tick_cumulative: StorageI64,
+    seconds_per_liquidity_cumulative_x128: StorageU160,
+    initialised: StorageBool,
+}
+
+impl StorageObservation {
+    fn get(&self) -> Observation {
+        Observation {
+            block_timestamp: self.block_timestamp.get().sys(),
+            tick_cumulative: self.tick_cumulative.get().sys(),
+            seconds_per_liquidity_cumulative_x128: self.seconds_per_liquidity_cumulative_x128.get(),
+            initialised: self.initialised.get(),
+        }
+    }
+
+    fn set(&mut self, observation: Observation) {
+        self.block_timestamp
+            .set(U32::lib(&observation.block_timestamp));
+        self.tick_cumulative
+            .set(I64::lib(&observation.tick_cumulative));
+        self.seconds_per_liquidity_cumulative_x128
+            .set(observation.seconds_per_liquidity_cumulative_x128);
+        self.initialised.set(observation.initialised);
+    }
+}
+
+/// Storage type for a pool's ring buffer of observations.
+#[solidity_storage]
+pub struct StorageOracle {
+    observations: StorageMap<u16, StorageObservation>,
+    // the most recently written observation
+    index: StorageU16,
+    // the number of observations in use
+    cardinality: StorageU16,
+    // the number of observations that will be in use once the buffer wraps around
+    cardinality_next: StorageU16,
+}
+
+/// Compares two timestamps that might have overflowed, which must both be at or before `time`.
+fn lte(time: u32, a: u32, b: u32) -> bool {
+    if a <= time && b <= time {
+        return a <= b;
+    }
+
+    let a_adjusted = if a > time {
+        a as u64
+    } else {
+        a as u64 + (1 << 32)
+    };
+    let b_adjusted = if b > time {
+        b as u64
+    } else {
+        b as u64 + (1 << 32)
+    };
+    a_adjusted <= b_adjusted
+}
+
+impl StorageOracle {
+    /// Initialises the oracle with its first observation.
+    pub fn initialise(&mut self, time: u32) {
+        self.observations.setter(0).set(Observation {
+            block_timestamp: time,
+            initialised: true,
+            ..Default::default()
+        });
+        self.index.set(U16::ZERO);
+        self.cardinality.set(U16::lib(&1));
+        self.cardinality_next.set(U16::lib(&1));
+    }
+
+    /// Gets the index of the most recent observation, the number of observations in use, and
+    /// the number that will be in use once the buffer wraps around.
+    pub fn state(&self) -> (u16, u16, u16) {
+        (
+            self.index.get().sys(),
+            self.cardinality.get().sys(),
+            self.cardinality_next.get().sys(),
+        )
+    }
+
+    /// Writes an observation, using the tick and liquidity since the last observation. Only one
+    /// observation is written per block. Initialises the oracle instead if it hasn't been, as
+    /// with pools created before observations were recorded.
+    pub fn write(&mut self, time: u32, tick: i32, liquidity: u128) {
+        let (index, cardinality, cardinality_next) = self.state();
+        if cardinality == 0 {
+            return self.initialise(time);
+        }
+
+        let last = self.observations.getter(index).get();
+        if last.block_timestamp == time {
+            return;
+        }
+
+        // grow the buffer once we reach the end of it
+        let cardinality = match cardinality_next > cardinality && index == cardinality - 1 {
+            true => cardinality_next,
+            false => cardinality,
+        };
+        let index = ((index as u32 + 1) % cardinality as u32) as u16;
+
+        self.observations
+            .setter(index)
+            .set(last.transform(time, tick, liquidity));
+        self.index.set(U16::lib(&index));
+        self.cardinality.set(U16::lib(&cardinality));
+    }
+
+    /// Increases the number of observations kept, returning the old and new number. Newly
+    /// available observations are written to, so swaps don't pay for the first write to them.
+    ///
+    /// # Errors
+    /// Requires the oracle to be initialised.
+    pub fn grow(&mut self, next: u16) -> Result<(u16, u16), Error> {
+        let (_, cardinality, current) = self.state();
+        assert_neq_or!(cardinality, 0, Error::OracleNotInitialised);
+
+        if next <= current {
+            return Ok((current, current));
+        }
+
+        for i in current..next {
+            self.observations
+                .setter(i)
+                .block_timestamp
+                .set(U32::lib(&1));
+        }
+        self.cardinality_next.set(U16::lib(&next));
+
+        Ok((current, next))
+    }
+
+    /// Finds the observations before or at, and at or after, a target time, using a binary
+    /// search over the buffer.
+    fn binary_search(
+        &self,
+        time: u32,
+        target: u32,
+        index: u16,
+        cardinality: u16,
+    ) -> (Observation, Observation) {
+        let cardinality = cardinality as u32;
+        let mut l = (index as u32 + 1) % cardinality; // oldest observation
+        let mut r = l + cardinality - 1; // newest observation
+
+        loop {
+            let i = (l + r) / 2;
+
+            let before_or_at = self.observations.getter((i % cardinality) as u16).get();
+
+            // we've landed on an uninitialised observation, keep searching higher
+            if !before_or_at.initialised {
+                l = i + 1;
+                continue;
+            }
+
+            let at_or_after = self
+                .observations
+                .getter(((i + 1) % cardinality) as u16)
+                .get();
+
+            let target_at_or_after = lte(time, before_or_at.block_timestamp, target);
+
+            // check if we've found the answer
+            if target_at_or_after && lte(time, target, at_or_after.block_timestamp) {
+                return (before_or_at, at_or_after);
+            }
+
+            match target_at_or_after {
+                false => r = i - 1,
+                true => l = i + 1,
+            }
+        }
+    }
+
+    /// Finds the observations surrounding a target time. The observation at or after the target
+    /// is simulated if the target is after the most recent observation.
+    ///
+    /// # Errors
+    /// Requires the target to be at or after the oldest observation.
+    fn get_surrounding_observations(
+        &self,
+        time: u32,
+        target: u32,
+        tick: i32,
+        liquidity: u128,
+    ) -> Result<(Observation, Observation), Error> {
+        let (index, cardinality, _) = self.state();
+
+        // optimistically set before to the newest observation
+        let before_or_at = self.observations.getter(index).get();
+
+        // if the target is chronologically at or after the newest observation, we can early return
+        if lte(time, before_or_at.block_timestamp, target) {
+            return match before_or_at.block_timestamp == target {
+                true => Ok((before_or_at, Observation::default())),
+                false => Ok((
+                    before_or_at,
+                    before_or_at.transform(target, tick, liquidity),
+                )),
+            };
+        }
+
+        // now, set before to the oldest observation
+        let mut before_or_at = self
+            .observations
+            .getter(((index as u32 + 1) % cardinality as u32) as u16)
+            .get();
+        if !before_or_at.initialised {
+            before_or_at = self.observations.getter(0).get();
+        }
+
+        // ensure that the target is chronologically at or after the oldest observation
+        assert_or!(
+            lte(time, before_or_at.block_timestamp, target),
+            Error::ObservationTooOld
+        );
+
+        Ok(self.binary_search(time, target, index, cardinality))
+    }
+
+    /// Gets the cumulative tick and seconds per liquidity now, given the current tick and
+    /// liquidity. Both are zero if the oracle hasn't been initialised, as they will be once it is.
+    pub fn observe_current(&self, time: u32, tick: i32, liquidity: u128) -> (i64, U160) {
+        let (index, cardinality, _) = self.state();
+        if cardinality == 0 {
+            return (0, U160::ZERO);
+        }
+
+        let mut last = self.observations.getter(index).get();
+        if last.block_timestamp != time {
+            last = last.transform(time, tick, liquidity);
+        }
+        (
+            last.tick_cumulative,
+            last.seconds_per_liquidity_cumulative_x128,
+        )
+    }
+
+    /// Gets the cumulative tick and seconds per liquidity at some number of seconds ago,
+    /// interpolating between observations.
+    fn observe_single(
+        &self,
+        time: u32,
+        seconds_ago: u32,
+        tick: i32,
+        liquidity: u128,
+    ) -> Result<(i64, U160), Error> {
+        if seconds_ago == 0 {
+            return Ok(self.observe_current(time, tick, liquidity));
+        }
+
+        let target = time.wrapping_sub(seconds_ago);
+
+        let (before_or_at, at_or_after) =
+            self.get_surrounding_observations(time, target, tick, liquidity)?;
+
+        if target == before_or_at.block_timestamp {
+            // we're at the left boundary
+            Ok((
+                before_or_at.tick_cumulative,
+                before_or_at.seconds_per_liquidity_cumulative_x128,
+            ))
+        } else if target == at_or_after.block_timestamp {
+            // we're at the right boundary
+            Ok((
+                at_or_after.tick_cumulative,
+                at_or_after.seconds_per_liquidity_cumulative_x128,
+            ))
+        } else {
+            // we're in the middle
+            let observation_time_delta = at_or_after
+                .block_timestamp
+                .wrapping_sub(before_or_at.block_timestamp);
+            let target_delta = target.wrapping_sub(before_or_at.block_timestamp);
+
+            let tick_cumulative_delta = at_or_after
+                .tick_cumulative
+                .wrapping_sub(before_or_at.tick_cumulative);
+            let seconds_per_liquidity_delta = U256::from(
+                at_or_after
+                    .seconds_per_liquidity_cumulative_x128
+                    .wrapping_sub(before_or_at.seconds_per_liquidity_cumulative_x128),
+            );
+
+            Ok((
+                before_or_at.tick_cumulative.wrapping_add(
+                    (tick_cumulative_delta / observation_time_delta as i64) * target_delta as i64,
+                ),
+                before_or_at
+                    .seconds_per_liquidity_cumulative_x128
+                    .wrapping_add(U160::wrapping_from(
+                        seconds_per_liquidity_delta * U256::from(target_delta)
+                            / U256::from(observation_time_delta),
+                    )),
+            ))
+        }
+    }
+
+    /// Gets the cumulative tick and seconds per liquidity at each of a number of seconds ago,
+    /// given the current tick and liquidity.
+    ///
+    /// # Errors
+    /// Requires the oracle to be initialised, and each time to be at or after the oldest
+    /// observation.
+    pub fn observe(
+        &self,
+        time: u32,
+        seconds_agos: &[u32],
+        tick: i32,
+        liquidity: u128,
+    ) -> Result<(Vec<i64>, Vec<U160>), Error> {
+        let (_, cardinality, _) = self.state();
+        assert_neq_or!(cardinality, 0, Error::OracleNotInitialised);
+
+        seconds_agos
+            .iter()
+            .map(|&seconds_ago| self.observe_single(time, seconds_ago, tick, liquidity))
+            .collect::<Result<Vec<_>, _>>()
+            .map(|observations| observations.into_iter().unzip())
+    }
+}
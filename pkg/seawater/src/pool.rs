@@ -1,12 +1,15 @@
 //! The [StoragePool] struct, containing most of the core AMM functions.
 
+use crate::block;
 use crate::error::Error;
-use crate::maths::{full_math, liquidity_math, sqrt_price_math, swap_math, tick_bitmap, tick_math};
+use crate::maths::{full_math, liquidity_math, sqrt_price_math, tick_bitmap, tick_math};
+use crate::oracle;
 use crate::position;
+use crate::swap;
 use crate::tick;
-use crate::types::{I256Extension, U256Extension, WrappedNative, I256, I32, U128, U256, U32, U8};
+use crate::types::{Address, I256Extension, WrappedNative, I256, I32, U128, U160, U256, U32, U8};
 use alloc::vec::Vec;
-use stylus_sdk::{prelude::*, storage::*};
+use stylus_sdk::{crypto, prelude::*, storage::*};
 
 use num_traits::ToPrimitive;
 
@@ -18,6 +21,36 @@ use crate::current_test;
 
 type Revert = Vec<u8>;
 
+/// Pauses swaps, and quotes, in a pool.
+pub const PAUSE_SWAPS: u8 = 1 << 0;
+/// Pauses adding liquidity to positions in a pool.
+pub const PAUSE_ADDS: u8 = 1 << 1;
+/// Pauses removing liquidity from positions in a pool.
+pub const PAUSE_REMOVALS: u8 = 1 << 2;
+/// Pauses collecting LP and protocol fees from a pool.
+pub const PAUSE_FEE_COLLECTION: u8 = 1 << 3;
+/// Every pause flag.
+pub const PAUSE_ALL: u8 = PAUSE_SWAPS | PAUSE_ADDS | PAUSE_REMOVALS | PAUSE_FEE_COLLECTION;
+
+/// The operations paused in every pool while the global pause is active. LPs can still withdraw
+/// their liquidity and fees.
+pub const GLOBAL_PAUSE_FLAGS: u8 = PAUSE_SWAPS | PAUSE_ADDS;
+
+/// The most words of the tick bitmap [StoragePool::get_initialised_ticks] reads in one call, so
+/// paging through a sparse range has a bounded cost.
+pub const MAX_TICK_BITMAP_WORDS: u32 = 256;
+
+/// Derives the ID of the pool for a pair of tokens and fee tier, the address it's stored at in
+/// [crate::Pools::pools]. Pools with the fluid token always have it as `token_1`. Pools created
+/// before pools had fee tiers are stored at the address of their token instead.
+pub fn pool_id(token_0: Address, token_1: Address, fee: u32) -> Address {
+    let mut key = [0_u8; 96];
+    key[12..32].copy_from_slice(token_0.as_slice());
+    key[44..64].copy_from_slice(token_1.as_slice());
+    key[92..96].copy_from_slice(&fee.to_be_bytes());
+    Address::from_word(crypto::keccak(key))
+}
+
 /// The storage type for an AMM pool.
 #[solidity_storage]
 pub struct StoragePool {
@@ -41,7 +74,26 @@ pub struct StoragePool {
     pub positions: position::StoragePositions,
 
     ticks: tick::StorageTicks,
-    tick_bitmap: tick::StorageTickBitmap,
+    pub tick_bitmap: tick::StorageTickBitmap,
+
+    // operations paused in this pool, see [PAUSE_ALL]
+    pause_flags: StorageU8,
+
+    // token 0 of the pool, or zero if the pool is stored at the token's address
+    token: StorageAddress,
+
+    // token 1 of the pool, or zero if it's the fluid token
+    token_1: StorageAddress,
+
+    // observations of the pool's tick and liquidity over time
+    pub oracle: oracle::StorageOracle,
+
+    // range orders, filled when swaps cross their ticks
+    pub range_orders: tick::StorageRangeOrders,
+
+    // tokens held for this pool, counted since reserves were tracked. flash loans can't be larger
+    reserve_0: StorageU256,
+    reserve_1: StorageU256,
 }
 
 impl StoragePool {
@@ -68,6 +120,8 @@ impl StoragePool {
         self.max_liquidity_per_tick
             .set(U128::lib(&max_liquidity_per_tick));
 
+        self.oracle.initialise(block::timestamp() as u32);
+
         Ok(())
     }
 
@@ -96,10 +150,31 @@ impl StoragePool {
         let upper = position.upper.get().sys();
 
         // update the ticks
+        let (fee_growth_inside_0, fee_growth_inside_1) = self.update_range(lower, upper, delta)?;
+
+        // update the position
+        self.positions
+            .update(id, delta, fee_growth_inside_0, fee_growth_inside_1)?;
+
+        let (amount_0, amount_1) = self.update_range_liquidity(lower, upper, delta)?;
+        self.track_reserves(amount_0, amount_1);
+
+        Ok((amount_0, amount_1))
+    }
+
+    /// Updates the ticks at either end of a range with a change in liquidity, returning the fee
+    /// growth inside the range.
+    fn update_range(
+        &mut self,
+        lower: i32,
+        upper: i32,
+        delta: i128,
+    ) -> Result<(U256, U256), Revert> {
         let cur_tick = self.cur_tick.get().sys();
         let fee_growth_global_0 = self.fee_growth_global_0.get();
         let fee_growth_global_1 = self.fee_growth_global_1.get();
         let max_liquidity_per_tick = self.max_liquidity_per_tick.get().sys();
+        let cumulatives = self.cumulatives();
 
         #[cfg(feature = "testing-dbg")]
         dbg!((
@@ -120,6 +195,7 @@ impl StoragePool {
                 delta,
                 &fee_growth_global_0,
                 &fee_growth_global_1,
+                &cumulatives,
                 false,
                 max_liquidity_per_tick,
             )?;
@@ -130,6 +206,7 @@ impl StoragePool {
                 delta,
                 &fee_growth_global_0,
                 &fee_growth_global_1,
+                &cumulatives,
                 true,
                 max_liquidity_per_tick,
             )?;
@@ -146,7 +223,6 @@ impl StoragePool {
             }
         }
 
-        // update the position
         let (fee_growth_inside_0, fee_growth_inside_1) = self.ticks.get_fee_growth_inside(
             lower,
             upper,
@@ -155,9 +231,6 @@ impl StoragePool {
             &self.fee_growth_global_1.get(),
         )?;
 
-        self.positions
-            .update(id, delta, fee_growth_inside_0, fee_growth_inside_1)?;
-
         if delta < 0 {
             if flipped_lower {
                 self.ticks.clear(lower);
@@ -167,6 +240,19 @@ impl StoragePool {
             }
         }
 
+        Ok((fee_growth_inside_0, fee_growth_inside_1))
+    }
+
+    /// Applies a change in liquidity over a range to the pool's active liquidity, returning the
+    /// amounts of each token the change is worth.
+    fn update_range_liquidity(
+        &mut self,
+        lower: i32,
+        upper: i32,
+        delta: i128,
+    ) -> Result<(I256, I256), Revert> {
+        let cur_tick = self.cur_tick.get().sys();
+
         // calculate liquidity change and the amount of each token we need
         if delta != 0 {
             let (amount_0, amount_1) = if self.cur_tick.get().sys() < lower {
@@ -192,7 +278,12 @@ impl StoragePool {
                 )
             } else if self.cur_tick.get().sys() < upper {
                 // we're inside the range, the liquidity is active and we need both tokens
-                let new_liquidity = liquidity_math::add_delta(self.liquidity.get().sys(), delta)?;
+                let liquidity = self.liquidity.get().sys();
+                let new_liquidity = liquidity_math::add_delta(liquidity, delta)?;
+
+                // record the liquidity before it changes
+                self.oracle
+                    .write(block::timestamp() as u32, cur_tick, liquidity);
 
                 #[cfg(feature = "testing-dbg")]
                 dbg!((
@@ -299,239 +390,263 @@ impl StoragePool {
         self.update_position(id, delta)
     }
 
-    /// Performs a swap on this pool.
-    pub fn swap(
+    /// Places a range order, selling a token over the single tick spacing range starting at
+    /// `lower`. Returns the epoch the order was batched into, and the amount of the token sold
+    /// that's needed.
+    ///
+    /// The order is filled, and its liquidity removed from the pool, once a swap crosses the
+    /// upper tick of the range if selling token 0, or the lower tick if selling token 1.
+    ///
+    /// # Errors
+    /// Requires the pool to be enabled, and the range to be valid and entirely above the current
+    /// tick if selling token 0, or entirely below it if selling token 1.
+    pub fn place_range_order(
         &mut self,
+        owner: Address,
+        lower: i32,
         zero_for_one: bool,
-        amount: I256,
-        mut price_limit: U256,
-    ) -> Result<(I256, I256, i32), Revert> {
+        liquidity: u128,
+    ) -> Result<(U256, U256), Revert> {
         assert_or!(self.enabled.get(), Error::PoolDisabled);
+        assert_neq_or!(liquidity, 0, Error::LiquidityIsZero);
+
+        let spacing = self.tick_spacing.get().sys();
+        assert_or!(lower % spacing as i32 == 0, Error::InvalidTickSpacing);
+        let upper = lower + spacing as i32;
+        assert_or!(
+            lower >= tick_math::get_min_tick(spacing),
+            Error::InvalidTick
+        );
+        assert_or!(
+            upper <= tick_math::get_max_tick(spacing),
+            Error::InvalidTick
+        );
 
-        // ensure the price limit is within bounds
+        let cur_tick = self.cur_tick.get().sys();
         match zero_for_one {
-            true => {
-                if price_limit == U256::MAX {
-                    price_limit = tick_math::MIN_SQRT_RATIO + U256::one();
-                }
-                if price_limit >= self.sqrt_price.get() || price_limit <= tick_math::MIN_SQRT_RATIO
-                {
-                    Err(Error::PriceLimitTooLow)?;
-                }
-            }
-            false => {
-                if price_limit == U256::MAX {
-                    price_limit = tick_math::MAX_SQRT_RATIO - U256::one();
-                }
-                if price_limit <= self.sqrt_price.get() || price_limit >= tick_math::MAX_SQRT_RATIO
-                {
-                    Err(Error::PriceLimitTooHigh)?;
-                }
-            }
-        };
-
-        #[cfg(feature = "testing-dbg")]
-        dbg!((
-            "inside swap pool function",
-            current_test!(),
-            price_limit.to_string()
-        ));
-
-        // is the swap exact in or exact out
-        let exact_in = amount > I256::zero();
-
-        // select either the high or low 4 bits
-        let fee_protocol = match zero_for_one {
-            true => self.fee_protocol.get().sys() % 16,
-            false => self.fee_protocol.get().sys() >> 4,
-        };
-
-        // group all our cached storage state into a struct
-        struct SwapState {
-            amount_remaining: I256,
-            amount_calculated: I256,
-            price: U256,
-            tick: i32,
-            fee_growth_global: U256,
-            protocol_fee: u128,
-            liquidity: u128,
+            true => assert_or!(cur_tick < lower, Error::RangeOrderInRange),
+            false => assert_or!(cur_tick >= upper, Error::RangeOrderInRange),
         }
 
-        let mut state = SwapState {
-            amount_remaining: amount,
-            amount_calculated: I256::zero(),
-            price: self.sqrt_price.get(),
-            tick: self.cur_tick.get().sys(),
-            fee_growth_global: match zero_for_one {
-                true => self.fee_growth_global_0.get(),
-                false => self.fee_growth_global_1.get(),
-            },
-            protocol_fee: 0,
-            liquidity: self.liquidity.get().sys(),
-        };
-
-        let fee = self.fee.get().sys();
-
-        // continue swapping while there's tokens left to swap
-        // and we haven't reached the price limit
-        let mut iters = 0;
-        while !state.amount_remaining.is_zero() && state.price != price_limit {
-            iters += 1;
-            debug_assert!(iters < 500);
-
-            let step_initial_price = state.price;
+        let delta = liquidity.to_i128().ok_or(Error::LiquidityAmountTooWide)?;
 
-            // find the next tick based on which direction we're swapping
-            let (step_next_tick, step_next_tick_initialised) =
-                tick_bitmap::next_initialized_tick_within_one_word(
-                    &self.tick_bitmap.bitmap,
-                    state.tick,
-                    self.tick_spacing.get().sys().into(),
-                    zero_for_one,
-                )?;
+        let (fee_growth_inside_0, fee_growth_inside_1) = self.update_range(lower, upper, delta)?;
+        let id = self.range_orders.place(
+            owner,
+            lower,
+            upper,
+            zero_for_one,
+            liquidity,
+            fee_growth_inside_0,
+            fee_growth_inside_1,
+        )?;
 
-            // make sure the next tick's within bounds
-            let step_next_tick = step_next_tick.clamp(tick_math::MIN_TICK, tick_math::MAX_TICK);
+        let (amount_0, amount_1) = self.update_range_liquidity(lower, upper, delta)?;
+        self.track_reserves(amount_0, amount_1);
+        let amount = match zero_for_one {
+            true => amount_0,
+            false => amount_1,
+        };
 
-            let step_next_price = tick_math::get_sqrt_ratio_at_tick(step_next_tick)?;
+        Ok((id, amount.abs_pos()?))
+    }
 
-            #[cfg(feature = "testing-dbg")]
-            dbg!((
-                "swapping",
-                state.amount_remaining.to_string(),
-                state.price.to_string(),
-                price_limit.to_string(),
-                iters,
-                step_next_tick,
-                step_next_tick_initialised,
-                step_next_price.to_string()
-            ));
-
-            // swap til the tick is reached or the price limit is reached or the in/out amount is
-            // used
-            // (price limits are checked in the while loop)
-            let hit_limit = match zero_for_one {
-                true => step_next_price < price_limit,
-                false => step_next_price > price_limit,
-            };
-            let step_clamped_price = match hit_limit {
-                true => price_limit,
-                false => step_next_price,
-            };
+    /// Cancels an unfilled range order, removing its liquidity from the pool. Returns the amounts
+    /// of each token the order is owed, including its share of the fees it earned.
+    ///
+    /// # Errors
+    /// Requires the pool to be enabled, the order's epoch to be unfilled, and the owner to have
+    /// an order in it.
+    pub fn cancel_range_order(&mut self, owner: Address, id: U256) -> Result<(U256, U256), Revert> {
+        assert_or!(self.enabled.get(), Error::PoolDisabled);
 
-            // step_fee_amount is reduced by protocol fee later
-            let (next_sqrt_price, step_amount_in, step_amount_out, mut step_fee_amount) =
-                swap_math::compute_swap_step(
-                    state.price,
-                    step_clamped_price,
-                    state.liquidity,
-                    state.amount_remaining,
-                    fee,
-                )?;
+        let (lower, upper, _, filled, liquidity) = self.range_orders.get(id, owner);
+        assert_or!(!filled, Error::RangeOrderFilled);
+        assert_neq_or!(liquidity, 0, Error::RangeOrderEmpty);
 
-            state.price = next_sqrt_price;
+        // the liquidity was checked when the order was placed
+        let delta = -(liquidity as i128);
 
-            // update state
-            match exact_in {
-                true => {
-                    state.amount_remaining -=
-                        I256::unchecked_from(step_amount_in + step_fee_amount);
-                    state.amount_calculated -= I256::unchecked_from(step_amount_out);
-                }
-                false => {
-                    state.amount_remaining += I256::unchecked_from(step_amount_out);
-                    state.amount_calculated +=
-                        I256::unchecked_from(step_amount_in + step_fee_amount);
-                }
-            }
+        let (fee_growth_inside_0, fee_growth_inside_1) = self.update_range(lower, upper, delta)?;
+        let (_, fees_0, fees_1) =
+            self.range_orders
+                .cancel(owner, id, fee_growth_inside_0, fee_growth_inside_1)?;
 
-            // set fees
-            if fee_protocol > 0 {
-                let delta = step_fee_amount.wrapping_div(U256::from(fee_protocol));
-                step_fee_amount -= delta;
-                state.protocol_fee += u128::try_from(delta).or(Err(Error::FeeTooHigh))?;
-            }
+        let (amount_0, amount_1) = self.update_range_liquidity(lower, upper, delta)?;
+        let (amount_0, amount_1) = (amount_0.abs_neg()? + fees_0, amount_1.abs_neg()? + fees_1);
+        self.release_reserves(amount_0, amount_1);
 
-            // update fees
-            if state.liquidity > 0 {
-                // normalise fee growth
-                state.fee_growth_global += full_math::mul_div(
-                    step_fee_amount,
-                    full_math::Q128,
-                    U256::from(state.liquidity),
-                )?;
-            }
+        Ok((amount_0, amount_1))
+    }
 
-            // shift tick
-            if state.price == step_next_price {
-                if step_next_tick_initialised {
-                    let (fee_0, fee_1) = match zero_for_one {
-                        true => (state.fee_growth_global, self.fee_growth_global_1.get()),
-                        false => (self.fee_growth_global_0.get(), state.fee_growth_global),
-                    };
+    /// Claims a filled range order, returning the amounts of each token it's owed, including its
+    /// share of the fees it earned.
+    ///
+    /// # Errors
+    /// Requires the order's epoch to be filled, and the owner to have an order in it.
+    pub fn claim_range_order(&mut self, owner: Address, id: U256) -> Result<(U256, U256), Revert> {
+        let (amount_0, amount_1) = self.range_orders.claim(owner, id)?;
+        self.release_reserves(amount_0, amount_1);
+        Ok((amount_0, amount_1))
+    }
 
-                    let liquidity_net = self.ticks.cross(step_next_tick, &fee_0, &fee_1);
+    /// Performs a swap on this pool.
+    pub fn swap(
+        &mut self,
+        zero_for_one: bool,
+        amount: I256,
+        price_limit: U256,
+    ) -> Result<(I256, I256, i32), Revert> {
+        let (amount_0, amount_1, tick, _) =
+            self.swap_counting_ticks(zero_for_one, amount, price_limit)?;
+        Ok((amount_0, amount_1, tick))
+    }
 
-                    // flip the liquidity delta if we're moving leftwards
-                    let liquidity_net = match zero_for_one {
-                        true => liquidity_net.wrapping_neg(),
-                        false => liquidity_net,
-                    };
+    /// Performs a swap on this pool, also returning the number of initialised ticks crossed.
+    pub fn swap_counting_ticks(
+        &mut self,
+        zero_for_one: bool,
+        amount: I256,
+        price_limit: U256,
+    ) -> Result<(I256, I256, i32, u32), Revert> {
+        assert_or!(self.enabled.get(), Error::PoolDisabled);
 
-                    state.liquidity = liquidity_math::add_delta(state.liquidity, liquidity_net)?;
-                }
+        let start = swap::SwapStart {
+            sqrt_price: self.sqrt_price.get(),
+            tick: self.cur_tick.get().sys(),
+            liquidity: self.liquidity.get().sys(),
+            fee: self.fee.get().sys(),
+            tick_spacing: self.tick_spacing.get().sys(),
+            fee_protocol: self.fee_protocol.get().sys(),
+            fee_growth_global_0: self.fee_growth_global_0.get(),
+            fee_growth_global_1: self.fee_growth_global_1.get(),
+        };
 
-                state.tick = match zero_for_one {
-                    true => step_next_tick - 1,
-                    false => step_next_tick,
-                };
-            } else if state.price != step_initial_price {
-                // recompute tick in case we've moved past ticks with no liquidity
-                state.tick = tick_math::get_tick_at_sqrt_ratio(state.price)?;
-            }
-        }
+        let outcome = swap::swap(self, start, zero_for_one, amount, price_limit)?;
 
         // write state
-        // update price and tick
-        self.sqrt_price.set(state.price);
-        if state.tick != self.cur_tick.get().sys() {
-            self.cur_tick.set(I32::unchecked_from(state.tick));
+        // update price and tick, recording the tick and liquidity before the swap if it moved
+        self.sqrt_price.set(outcome.sqrt_price);
+        if outcome.tick != start.tick {
+            self.oracle
+                .write(block::timestamp() as u32, start.tick, start.liquidity);
+            self.cur_tick.set(I32::unchecked_from(outcome.tick));
         }
 
         // update liquidity
-        if self.liquidity.get().sys() != state.liquidity {
-            self.liquidity.set(U128::lib(&state.liquidity));
+        if start.liquidity != outcome.liquidity {
+            self.liquidity.set(U128::lib(&outcome.liquidity));
         }
 
         // update fees
-        if fee != 0 {
+        if start.fee != 0 {
             match zero_for_one {
                 true => {
-                    self.fee_growth_global_0.set(state.fee_growth_global);
-                    if state.protocol_fee > 0 {
+                    self.fee_growth_global_0.set(outcome.fee_growth_global);
+                    if outcome.protocol_fee > 0 {
                         let new_protocol_fee =
-                            self.protocol_fee_0.get() + U128::lib(&state.protocol_fee);
+                            self.protocol_fee_0.get() + U128::lib(&outcome.protocol_fee);
                         self.protocol_fee_0.set(new_protocol_fee);
                     }
                 }
                 false => {
-                    self.fee_growth_global_1.set(state.fee_growth_global);
-                    if state.protocol_fee > 0 {
+                    self.fee_growth_global_1.set(outcome.fee_growth_global);
+                    if outcome.protocol_fee > 0 {
                         let new_protocol_fee =
-                            self.protocol_fee_1.get() + U128::lib(&state.protocol_fee);
+                            self.protocol_fee_1.get() + U128::lib(&outcome.protocol_fee);
                         self.protocol_fee_1.set(new_protocol_fee);
                     }
                 }
             }
         }
 
-        let token0_is_input = (zero_for_one && exact_in) || (!zero_for_one && !exact_in);
-        let (amount_0, amount_1) = match token0_is_input {
-            true => (amount - state.amount_remaining, state.amount_calculated),
-            false => (state.amount_calculated, amount - state.amount_remaining),
-        };
+        self.track_reserves(outcome.amount_0, outcome.amount_1);
+
+        Ok((
+            outcome.amount_0,
+            outcome.amount_1,
+            outcome.tick,
+            outcome.ticks_crossed,
+        ))
+    }
+
+    /// Finds the initialised ticks between `from` and `to` inclusive, walking the tick bitmap a
+    /// word at a time.
+    ///
+    /// Returns at most `limit` ticks, as (tick, liquidity gross, liquidity net, fee growth outside
+    /// 0, fee growth outside 1), and the tick to start from to get the next page of ticks. A page
+    /// ends early after reading [MAX_TICK_BITMAP_WORDS] words, so it may have fewer than `limit`
+    /// ticks before the end of the range. The returned tick is greater than `to` once every tick
+    /// has been returned.
+    pub fn get_initialised_ticks(
+        &self,
+        from: i32,
+        to: i32,
+        limit: u32,
+    ) -> Result<(Vec<tick::InitialisedTick>, i32), Revert> {
+        let spacing = self.tick_spacing.get().sys() as i32;
+        let to = to.min(tick_math::MAX_TICK);
+
+        let mut ticks = Vec::new();
+
+        // the bitmap is searched for ticks strictly greater than the current tick
+        let mut cur = from.max(tick_math::MIN_TICK) - 1;
+        let mut words = 0;
+        loop {
+            let (next, initialised) = tick_bitmap::next_initialized_tick_within_one_word(
+                &self.tick_bitmap.bitmap,
+                cur,
+                spacing,
+                false,
+            )?;
+
+            if next > to {
+                return Ok((ticks, next));
+            }
+
+            if initialised {
+                if ticks.len() == limit as usize {
+                    return Ok((ticks, next));
+                }
+
+                let (liquidity_gross, liquidity_net, fee_growth_outside_0, fee_growth_outside_1) =
+                    self.ticks.get_info(next);
+                ticks.push((
+                    next,
+                    liquidity_gross,
+                    liquidity_net,
+                    fee_growth_outside_0,
+                    fee_growth_outside_1,
+                ));
+            } else {
+                // the search only stops without finding a tick at the end of a word
+                words += 1;
+                if words == MAX_TICK_BITMAP_WORDS {
+                    return Ok((ticks, next + 1));
+                }
+            }
+
+            cur = next;
+        }
+    }
+
+    /// Rebuilds the tick bitmap words covering the ticks between lower and upper, inclusive, from
+    /// the ticks that are initialised.
+    pub fn rebuild_tick_bitmap(&mut self, lower: i32, upper: i32) -> Result<(), Revert> {
+        assert_or!(lower <= upper, Error::InvalidTick);
+
+        let spacing = self.tick_spacing.get().sys();
+        let lower = lower.max(tick_math::MIN_TICK).div_euclid(spacing as i32);
+        let upper = upper.min(tick_math::MAX_TICK).div_euclid(spacing as i32);
 
-        Ok((amount_0, amount_1, state.tick))
+        let (first_word, _) = tick_bitmap::position(lower);
+        let (last_word, _) = tick_bitmap::position(upper);
+        for word_pos in first_word..=last_word {
+            self.tick_bitmap
+                .rebuild_word(word_pos, spacing, &self.ticks);
+        }
+
+        Ok(())
     }
 
     /// Collects protocol (admin) fees.
@@ -555,13 +670,189 @@ impl StoragePool {
             self.protocol_fee_1.set(U128::lib(&(owed_1 - amount_1)));
         }
 
+        self.release_reserves(U256::from(amount_0), U256::from(amount_1));
+
         Ok((amount_0, amount_1))
     }
 
+    /// Sets the share of swap fees taken by the protocol, as in uniswap's `setFeeProtocol`.
+    ///
+    /// Each fee protocol is either 0 (no protocol fee), or between 4 and 10, where the protocol
+    /// takes `1/n` of the swap fee. `fee_protocol_0` applies to swaps paid in token 0,
+    /// `fee_protocol_1` to swaps paid in token 1.
+    pub fn set_fee_protocol(
+        &mut self,
+        fee_protocol_0: u8,
+        fee_protocol_1: u8,
+    ) -> Result<(), Revert> {
+        assert_or!(
+            fee_protocol_0 == 0 || (4..=10).contains(&fee_protocol_0),
+            Error::InvalidFeeProtocol
+        );
+        assert_or!(
+            fee_protocol_1 == 0 || (4..=10).contains(&fee_protocol_1),
+            Error::InvalidFeeProtocol
+        );
+
+        // packed token0 in the low bits, token1 in the high bits
+        self.fee_protocol
+            .set(U8::lib(&(fee_protocol_0 | (fee_protocol_1 << 4))));
+
+        Ok(())
+    }
+
+    /// Gets the protocol fee settings for the pool, as (token0, token1).
+    pub fn get_fee_protocol(&self) -> (u8, u8) {
+        let fee_protocol = self.fee_protocol.get().sys();
+        (fee_protocol % 16, fee_protocol >> 4)
+    }
+
+    /// Gets the cumulative tick and seconds per liquidity at each of a number of seconds ago. See
+    /// [oracle::StorageOracle::observe].
+    pub fn observe(&self, seconds_agos: &[u32]) -> Result<(Vec<i64>, Vec<U160>), Revert> {
+        Ok(self.oracle.observe(
+            block::timestamp() as u32,
+            seconds_agos,
+            self.cur_tick.get().sys(),
+            self.liquidity.get().sys(),
+        )?)
+    }
+
+    /// Gets the pool's cumulative tick and seconds per liquidity now, along with the time.
+    fn cumulatives(&self) -> tick::Cumulatives {
+        let time = block::timestamp() as u32;
+        let (tick_cumulative, seconds_per_liquidity_cumulative_x128) = self.oracle.observe_current(
+            time,
+            self.cur_tick.get().sys(),
+            self.liquidity.get().sys(),
+        );
+        tick::Cumulatives {
+            tick_cumulative,
+            seconds_per_liquidity_cumulative_x128,
+            time,
+        }
+    }
+
+    /// Gets the seconds per liquidity, as an X128 number, and the seconds, that the pool's price
+    /// has spent inside a range. These are only meaningful compared to an earlier value taken
+    /// while the range's ticks were initialised, such as while a position over it has liquidity.
+    ///
+    /// # Errors
+    /// Requires both ticks to be initialised.
+    pub fn seconds_per_liquidity_inside(
+        &self,
+        lower: i32,
+        upper: i32,
+    ) -> Result<(U160, u32), Revert> {
+        let (_, seconds_per_liquidity_inside, seconds_inside) = self.ticks.get_cumulatives_inside(
+            lower,
+            upper,
+            self.cur_tick.get().sys(),
+            &self.cumulatives(),
+        )?;
+        Ok((seconds_per_liquidity_inside, seconds_inside))
+    }
+
+    /// Calculates the fees owed for a flash loan of the pool's tokens, rounding up.
+    ///
+    /// # Errors
+    /// Requires the pool to be enabled, to have liquidity in range to earn the fees, and the
+    /// amounts to be no more than the pool's reserves.
+    pub fn flash_fees(&self, amount_0: U256, amount_1: U256) -> Result<(U256, U256), Revert> {
+        assert_or!(self.enabled.get(), Error::PoolDisabled);
+        assert_neq_or!(self.liquidity.get(), U128::ZERO, Error::LiquidityIsZero);
+        assert_or!(
+            amount_0 <= self.reserve_0.get() && amount_1 <= self.reserve_1.get(),
+            Error::FlashLoanTooLarge
+        );
+
+        let fee = U256::from(self.fee.get());
+        let denominator = U256::from(1_000_000);
+        Ok((
+            full_math::mul_div_rounding_up(amount_0, fee, denominator)?,
+            full_math::mul_div_rounding_up(amount_1, fee, denominator)?,
+        ))
+    }
+
+    /// Credits the fees paid for a flash loan to in range liquidity providers, after taking the
+    /// protocol's share as with swap fees.
+    pub fn credit_flash_fees(&mut self, paid_0: U256, paid_1: U256) -> Result<(), Revert> {
+        self.reserve_0.set(self.reserve_0.get() + paid_0);
+        self.reserve_1.set(self.reserve_1.get() + paid_1);
+
+        let liquidity = U256::from(self.liquidity.get());
+        let (fee_protocol_0, fee_protocol_1) = self.get_fee_protocol();
+
+        if paid_0 > U256::ZERO {
+            let (fee_growth, protocol_fee) =
+                Self::split_flash_fee(paid_0, fee_protocol_0, liquidity)?;
+            self.fee_growth_global_0
+                .set(self.fee_growth_global_0.get() + fee_growth);
+            if protocol_fee > 0 {
+                let new_protocol_fee = self.protocol_fee_0.get() + U128::lib(&protocol_fee);
+                self.protocol_fee_0.set(new_protocol_fee);
+            }
+        }
+        if paid_1 > U256::ZERO {
+            let (fee_growth, protocol_fee) =
+                Self::split_flash_fee(paid_1, fee_protocol_1, liquidity)?;
+            self.fee_growth_global_1
+                .set(self.fee_growth_global_1.get() + fee_growth);
+            if protocol_fee > 0 {
+                let new_protocol_fee = self.protocol_fee_1.get() + U128::lib(&protocol_fee);
+                self.protocol_fee_1.set(new_protocol_fee);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Splits a flash loan fee into the fee growth for liquidity providers, and the protocol fee.
+    fn split_flash_fee(
+        paid: U256,
+        fee_protocol: u8,
+        liquidity: U256,
+    ) -> Result<(U256, u128), Error> {
+        let protocol_fee = match fee_protocol {
+            0 => U256::ZERO,
+            _ => paid / U256::from(fee_protocol),
+        };
+        let fee_growth = full_math::mul_div(paid - protocol_fee, full_math::Q128, liquidity)?;
+        let protocol_fee = u128::try_from(protocol_fee).or(Err(Error::FeeTooHigh))?;
+        Ok((fee_growth, protocol_fee))
+    }
+
     /// Collects fees earned by a liquidity provider.
     pub fn collect(&mut self, id: U256) -> Result<(u128, u128), Revert> {
         assert_or!(self.enabled.get(), Error::PoolDisabled);
-        Ok(self.positions.collect_fees(id))
+        let (amount_0, amount_1) = self.positions.collect_fees(id);
+        self.release_reserves(U256::from(amount_0), U256::from(amount_1));
+        Ok((amount_0, amount_1))
+    }
+
+    /// Updates the pool's reserves with amounts moving in and out of it, where positive amounts
+    /// are paid to the pool.
+    fn track_reserves(&mut self, amount_0: I256, amount_1: I256) {
+        let track = |reserve: U256, amount: I256| match amount.is_negative() {
+            true => reserve.saturating_sub(amount.unsigned_abs()),
+            false => reserve + amount.into_raw(),
+        };
+        self.reserve_0.set(track(self.reserve_0.get(), amount_0));
+        self.reserve_1.set(track(self.reserve_1.get(), amount_1));
+    }
+
+    /// Takes amounts paid out of the pool from its reserves. Pools created before their reserves
+    /// were tracked hold more than was counted, so the reserves stop at zero.
+    fn release_reserves(&mut self, amount_0: U256, amount_1: U256) {
+        self.reserve_0
+            .set(self.reserve_0.get().saturating_sub(amount_0));
+        self.reserve_1
+            .set(self.reserve_1.get().saturating_sub(amount_1));
+    }
+
+    /// Returns the tokens held for the pool that can be flash loaned, as (token 0, token 1).
+    pub fn get_reserves(&self) -> (U256, U256) {
+        (self.reserve_0.get(), self.reserve_1.get())
     }
 
     /// Returns the amount of liquidity in a position.
@@ -600,6 +891,48 @@ impl StoragePool {
         self.positions.fees_owed(id)
     }
 
+    /// Gets a position's bounds, liquidity, the amount of each token its liquidity is worth at the
+    /// current price, and the fees it's owed including fees earned since it was last updated.
+    ///
+    /// Unlike [Self::update_position], this doesn't checkpoint the position's fees, so it doesn't
+    /// modify storage.
+    pub fn get_position_info(&self, id: U256) -> Result<position::PositionInfo, Revert> {
+        let position = self.positions.positions.get(id);
+        let lower = position.lower.get().sys();
+        let upper = position.upper.get().sys();
+        let liquidity = position.liquidity.get().sys();
+
+        // amounts are what the position would receive if its liquidity were removed, so they're
+        // rounded down
+        let (amount_0, amount_1) = sqrt_price_math::get_amounts_for_delta(
+            self.sqrt_price.get(),
+            tick_math::get_sqrt_ratio_at_tick(lower)?,
+            tick_math::get_sqrt_ratio_at_tick(upper)?,
+            -i128::try_from(liquidity).map_err(|_| Error::LiquidityTooHigh)?,
+        )?;
+
+        let (fee_growth_inside_0, fee_growth_inside_1) = self.ticks.get_fee_growth_inside(
+            lower,
+            upper,
+            self.cur_tick.get().sys(),
+            &self.fee_growth_global_0.get(),
+            &self.fee_growth_global_1.get(),
+        )?;
+        let (fees_owed_0, fees_owed_1) =
+            self.positions
+                .fees_owed_at(id, fee_growth_inside_0, fee_growth_inside_1)?;
+
+        Ok((
+            lower,
+            upper,
+            liquidity,
+            amount_0.unsigned_abs(),
+            amount_1.unsigned_abs(),
+            fees_owed_0,
+            fees_owed_1,
+        ))
+    }
+
     /// Get the tick spacing for the pool given.
     pub fn get_tick_spacing(&self) -> U8 {
         self.tick_spacing.get()
@@ -627,6 +960,89 @@ impl StoragePool {
     pub fn get_enabled(&self) -> bool {
         self.enabled.get()
     }
+
+    /// Sets token 0 of the pool.
+    pub fn set_token(&mut self, token: Address) {
+        self.token.set(token);
+    }
+
+    /// Gets token 0 of the pool, or the zero address for pools created before pools had fee
+    /// tiers, which are stored at their token's address.
+    pub fn get_token(&self) -> Address {
+        self.token.get()
+    }
+
+    /// Sets token 1 of the pool, for pools that don't use the fluid token.
+    pub fn set_token_1(&mut self, token_1: Address) {
+        self.token_1.set(token_1);
+    }
+
+    /// Gets token 1 of the pool, or the zero address if it's the fluid token.
+    pub fn get_token_1(&self) -> Address {
+        self.token_1.get()
+    }
+
+    /// Sets the operations paused in the pool, a combination of the `PAUSE_*` flags.
+    pub fn set_pause_flags(&mut self, flags: u8) -> Result<(), Revert> {
+        assert_eq_or!(flags & !PAUSE_ALL, 0, Error::InvalidPauseFlags);
+        self.pause_flags.set(U8::lib(&flags));
+        Ok(())
+    }
+
+    /// Gets the operations paused in the pool, a combination of the `PAUSE_*` flags.
+    pub fn get_pause_flags(&self) -> u8 {
+        self.pause_flags.get().sys()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl StoragePool {
+    /// Takes an in memory snapshot of the pool, for simulating swaps off-chain. See
+    /// [crate::snapshot::simulate_swap].
+    pub fn snapshot(&self) -> crate::snapshot::PoolSnapshot {
+        let tick_spacing = self.tick_spacing.get().sys();
+        let spacing = tick_spacing as i32;
+
+        let mut snapshot = crate::snapshot::PoolSnapshot {
+            sqrt_price: self.sqrt_price.get(),
+            tick: self.cur_tick.get().sys(),
+            liquidity: self.liquidity.get().sys(),
+            fee: self.fee.get().sys(),
+            tick_spacing,
+            ..Default::default()
+        };
+
+        // walk every word that could hold a usable tick
+        let (min_word, _) = tick_bitmap::position(tick_math::get_min_tick(tick_spacing) / spacing);
+        let (max_word, _) = tick_bitmap::position(tick_math::get_max_tick(tick_spacing) / spacing);
+
+        for word_pos in min_word..=max_word {
+            let word = self.tick_bitmap.bitmap.get(word_pos);
+            if word.is_zero() {
+                continue;
+            }
+
+            for bit_pos in 0..256 {
+                if word.bit(bit_pos) {
+                    let tick = ((word_pos as i32) * 256 + bit_pos as i32) * spacing;
+                    let (liquidity_gross, liquidity_net, _, _) = self.ticks.get_info(tick);
+                    snapshot.liquidity_net.insert(tick, liquidity_net);
+                    snapshot.liquidity_gross.insert(tick, liquidity_gross);
+
+                    if let Some(order) = self.range_orders.pending(tick, true) {
+                        snapshot.range_orders_0.insert(tick, order);
+                    }
+                    if let Some(order) = self.range_orders.pending(tick, false) {
+                        snapshot.range_orders_1.insert(tick, order);
+                    }
+                }
+            }
+
+            snapshot.tick_bitmap.insert(word_pos, word);
+        }
+
+        snapshot
+    }
 }
 
 #[cfg(all(not(target_arch = "wasm32"), feature = "testing"))]
@@ -635,3 +1051,47 @@ impl test_utils::StorageNew for StoragePool {
         unsafe { <Self as stylus_sdk::storage::StorageType>::new(i, v) }
     }
 }
+
+impl swap::SwapTicks for StoragePool {
+    fn next_initialized_tick_within_one_word(
+        &self,
+        tick: i32,
+        tick_spacing: i32,
+        lte: bool,
+    ) -> Result<(i32, bool), Error> {
+        tick_bitmap::next_initialized_tick_within_one_word(
+            &self.tick_bitmap.bitmap,
+            tick,
+            tick_spacing,
+            lte,
+        )
+    }
+
+    fn cross(
+        &mut self,
+        tick: i32,
+        fee_growth_global_0: &U256,
+        fee_growth_global_1: &U256,
+        zero_for_one: bool,
+    ) -> Result<i128, Error> {
+        // the pool's tick and liquidity are still from the start of the swap, like the oracle
+        // observation written after it
+        let cumulatives = self.cumulatives();
+        let (liquidity_net, flipped) = self.ticks.cross(
+            tick,
+            fee_growth_global_0,
+            fee_growth_global_1,
+            &cumulatives,
+            zero_for_one,
+            &mut self.range_orders,
+        )?;
+
+        // ticks emptied by filling range orders aren't initialised any more
+        let spacing = self.tick_spacing.get().sys();
+        for tick in flipped {
+            self.tick_bitmap.flip(tick, spacing);
+        }
+
+        Ok(liquidity_net)
+    }
+}
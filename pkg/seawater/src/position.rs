@@ -8,6 +8,10 @@ use crate::{
 
 use stylus_sdk::{prelude::*, storage::*};
 
+/// A position's lower tick, upper tick, liquidity, the amounts of token 0 and token 1 its liquidity
+/// is worth, and the fees of token 0 and token 1 it's owed.
+pub type PositionInfo = (i32, i32, u128, U256, U256, u128, u128);
+
 /// Storage type for the details on a position.
 #[solidity_storage]
 pub struct StoragePositionInfo {
@@ -20,6 +24,21 @@ pub struct StoragePositionInfo {
     pub token_owed_1: StorageU128,
 }
 
+/// The fees earned by a position's liquidity since its fee growth was last checkpointed.
+pub fn owed_fees(
+    fee_growth_inside: U256,
+    fee_growth_inside_last: U256,
+    liquidity: U128,
+) -> Result<U256, Error> {
+    full_math::mul_div(
+        fee_growth_inside
+            .checked_sub(fee_growth_inside_last)
+            .ok_or(Error::FeeGrowthSubPos)?,
+        U256::from(liquidity),
+        full_math::Q128,
+    )
+}
+
 /// Container type for the map of position ID to position details.
 #[solidity_storage]
 pub struct StoragePositions {
@@ -49,20 +68,15 @@ impl StoragePositions {
     ) -> Result<(), Error> {
         let mut info = self.positions.setter(id);
 
-        let owed_fees_0 = full_math::mul_div(
-            fee_growth_inside_0
-                .checked_sub(info.fee_growth_inside_0.get())
-                .ok_or(Error::FeeGrowthSubPos)?,
-            U256::from(info.liquidity.get()),
-            full_math::Q128,
+        let owed_fees_0 = owed_fees(
+            fee_growth_inside_0,
+            info.fee_growth_inside_0.get(),
+            info.liquidity.get(),
         )?;
-
-        let owed_fees_1 = full_math::mul_div(
-            fee_growth_inside_1
-                .checked_sub(info.fee_growth_inside_1.get())
-                .ok_or(Error::FeeGrowthSubPos)?,
-            U256::from(info.liquidity.get()),
-            full_math::Q128,
+        let owed_fees_1 = owed_fees(
+            fee_growth_inside_1,
+            info.fee_growth_inside_1.get(),
+            info.liquidity.get(),
         )?;
 
         let liquidity_next = liquidity_math::add_delta(info.liquidity.get().sys(), delta)?;
@@ -100,6 +114,40 @@ impl StoragePositions {
         )
     }
 
+    /// Gets the fees a position would be owed if it were updated with the given fee growth,
+    /// without modifying it.
+    pub fn fees_owed_at(
+        &self,
+        id: U256,
+        fee_growth_inside_0: U256,
+        fee_growth_inside_1: U256,
+    ) -> Result<(u128, u128), Error> {
+        let info = self.positions.getter(id);
+
+        let owed_fees_0 = owed_fees(
+            fee_growth_inside_0,
+            info.fee_growth_inside_0.get(),
+            info.liquidity.get(),
+        )?;
+        let owed_fees_1 = owed_fees(
+            fee_growth_inside_1,
+            info.fee_growth_inside_1.get(),
+            info.liquidity.get(),
+        )?;
+
+        // wraps the same way as update
+        Ok((
+            info.token_owed_0
+                .get()
+                .wrapping_add(U128::wrapping_from(owed_fees_0))
+                .sys(),
+            info.token_owed_1
+                .get()
+                .wrapping_add(U128::wrapping_from(owed_fees_1))
+                .sys(),
+        ))
+    }
+
     /// Collects fees from a position, returning the amount of each token collected.
     ///
     /// # Arguments
@@ -0,0 +1,30 @@
+//! Roles that can be granted to accounts, to let them use a subset of the admin functions.
+//!
+//! The seawater admin implicitly holds every role, and is the only account that can grant or
+//! revoke them. For backwards compatibility, the emergency council holds [PAUSER], and accounts
+//! authorised as pool enablers before roles were introduced hold [POOL_CREATOR].
+//!
+//! Roles are identified by the keccak256 hash of their name, following OpenZeppelin's
+//! `AccessControl`.
+
+use stylus_sdk::alloy_primitives::B256;
+
+/// Can create pools, and enable or disable them.
+pub const POOL_CREATOR: B256 = role(b"POOL_CREATOR");
+
+/// Can collect the protocol's share of the swap fees.
+pub const FEE_COLLECTOR: B256 = role(b"FEE_COLLECTOR");
+
+/// Can disable pools, but not enable them.
+pub const PAUSER: B256 = role(b"PAUSER");
+
+/// Can override the price of a pool, once the admin has queued the change.
+pub const PRICE_SETTER: B256 = role(b"PRICE_SETTER");
+
+/// Can use the migration functions to move tokens in and out of the AMM.
+pub const MIGRATOR: B256 = role(b"MIGRATOR");
+
+/// Computes the identifier of a role from its name.
+pub const fn role(name: &[u8]) -> B256 {
+    B256::new(keccak_const::Keccak256::new().update(name).finalize())
+}
@@ -0,0 +1,143 @@
+//! In memory snapshots of a pool, used to simulate swaps off-chain.
+//!
+//! Swaps are simulated with the same swap loop as [crate::pool::StoragePool::swap], so a
+//! simulated swap gives the same result as the swap would on-chain, so long as the pool hasn't
+//! changed since the snapshot was taken.
+
+use crate::error::Error;
+use crate::maths::tick_bitmap;
+use crate::swap::{self, SwapOutcome, SwapStart, SwapTicks};
+use crate::types::{U256Extension, I256, U256};
+use alloc::collections::BTreeMap;
+
+/// The state of a pool needed to simulate a swap, without any Stylus storage.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PoolSnapshot {
+    pub sqrt_price: U256,
+    pub tick: i32,
+    pub liquidity: u128,
+    pub fee: u32,
+    pub tick_spacing: u8,
+    /// The words of the tick bitmap which have initialised ticks in them.
+    pub tick_bitmap: BTreeMap<i16, U256>,
+    /// The liquidity net of every initialised tick.
+    pub liquidity_net: BTreeMap<i32, i128>,
+    /// The liquidity gross of every initialised tick.
+    pub liquidity_gross: BTreeMap<i32, u128>,
+    /// The unfilled range orders selling token 0, by the upper tick that fills them, as (lower
+    /// tick, upper tick, liquidity).
+    pub range_orders_0: BTreeMap<i32, (i32, i32, u128)>,
+    /// The unfilled range orders selling token 1, by the lower tick that fills them, as (lower
+    /// tick, upper tick, liquidity).
+    pub range_orders_1: BTreeMap<i32, (i32, i32, u128)>,
+}
+
+/// The ticks of a snapshot. Crossing a tick only reads its liquidity net, so simulating a swap
+/// never modifies the snapshot.
+struct SnapshotTicks<'a>(&'a PoolSnapshot);
+
+impl SwapTicks for SnapshotTicks<'_> {
+    fn next_initialized_tick_within_one_word(
+        &self,
+        tick: i32,
+        tick_spacing: i32,
+        lte: bool,
+    ) -> Result<(i32, bool), Error> {
+        tick_bitmap::next_initialized_tick_within_one_word(
+            &self.0.tick_bitmap,
+            tick,
+            tick_spacing,
+            lte,
+        )
+    }
+
+    fn cross(&mut self, tick: i32, _: &U256, _: &U256, _: bool) -> Result<i128, Error> {
+        // range orders filled by crossing a tick are only removed from ticks behind the swap, so
+        // they don't change the outcome, and are removed by [PoolSnapshot::apply] afterwards
+        Ok(self.0.liquidity_net.get(&tick).copied().unwrap_or_default())
+    }
+}
+
+/// Simulates a swap over a pool snapshot, without modifying it.
+///
+/// See [crate::pool::StoragePool::swap] for the arguments. The snapshot doesn't track fee growth,
+/// so only the amounts, price, tick, liquidity and ticks crossed of the outcome are meaningful.
+///
+/// # Errors
+/// Returns the same errors as the on-chain swap, other than the pool being disabled.
+pub fn simulate_swap(
+    snapshot: &PoolSnapshot,
+    zero_for_one: bool,
+    amount: I256,
+    price_limit: U256,
+) -> Result<SwapOutcome, Error> {
+    let start = SwapStart {
+        sqrt_price: snapshot.sqrt_price,
+        tick: snapshot.tick,
+        liquidity: snapshot.liquidity,
+        fee: snapshot.fee,
+        tick_spacing: snapshot.tick_spacing,
+        fee_protocol: 0,
+        fee_growth_global_0: U256::ZERO,
+        fee_growth_global_1: U256::ZERO,
+    };
+
+    swap::swap(
+        &mut SnapshotTicks(snapshot),
+        start,
+        zero_for_one,
+        amount,
+        price_limit,
+    )
+}
+
+impl PoolSnapshot {
+    /// Updates the snapshot to the state of the pool after a swap, so swaps can be chained.
+    ///
+    /// The range orders waiting for the swap to cross a tick are filled, removing their liquidity
+    /// from the ticks the same as [crate::tick::StorageTicks::cross] does.
+    pub fn apply(&mut self, outcome: &SwapOutcome) {
+        // swaps moving left cross the ticks after the new tick, up to and including the old one,
+        // and fill the orders selling token 1. swaps moving right cross the ticks after the old
+        // tick, up to and including the new one, and fill the orders selling token 0
+        let (range_orders, crossed) = match outcome.tick < self.tick {
+            true => (&mut self.range_orders_1, outcome.tick + 1..=self.tick),
+            false => (&mut self.range_orders_0, self.tick + 1..=outcome.tick),
+        };
+        let filling: Vec<i32> = range_orders.range(crossed).map(|(tick, _)| *tick).collect();
+        let filled: Vec<_> = filling
+            .into_iter()
+            .filter_map(|tick| range_orders.remove(&tick))
+            .collect();
+
+        for (lower, upper, liquidity) in filled {
+            self.remove_liquidity(lower, liquidity as i128);
+            self.remove_liquidity(upper, -(liquidity as i128));
+        }
+
+        self.sqrt_price = outcome.sqrt_price;
+        self.tick = outcome.tick;
+        self.liquidity = outcome.liquidity;
+    }
+
+    /// Removes liquidity from a tick, clearing it from the bitmap once it has none left.
+    /// `net_delta` is the liquidity net to remove, which is negated for upper ticks.
+    fn remove_liquidity(&mut self, tick: i32, net_delta: i128) {
+        let gross = self.liquidity_gross.entry(tick).or_default();
+        *gross -= net_delta.unsigned_abs();
+        if *gross != 0 {
+            *self.liquidity_net.entry(tick).or_default() -= net_delta;
+            return;
+        }
+
+        self.liquidity_gross.remove(&tick);
+        self.liquidity_net.remove(&tick);
+
+        let (word_pos, bit_pos) = tick_bitmap::position(tick / self.tick_spacing as i32);
+        let word = self.tick_bitmap.entry(word_pos).or_default();
+        *word ^= U256::one() << bit_pos;
+        if word.is_zero() {
+            self.tick_bitmap.remove(&word_pos);
+        }
+    }
+}
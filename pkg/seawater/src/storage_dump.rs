@@ -0,0 +1,302 @@
+//! Versioned dumps of the host storage, for reproducing pool states locally.
+//!
+//! A dump is every non-zero slot in [crate::host_test_shims::storage::STORAGE], which holds the whole
+//! state of the contract under test (pools, ticks, bitmap words, positions, fee growth and
+//! protocol fees). Dumps can be encoded as JSON, or as a compact binary format:
+//!
+//! ```text
+//! JSON:   { "version": 1, "slots": { "0x<key>": "0x<value>", ... } }
+//! binary: "SWSD" | version (1 byte) | slot count (4 bytes BE) | (key (32 bytes) | value (32 bytes))*
+//! ```
+//!
+//! Slots are always sorted by key, so encoding the same storage twice gives the same output.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use stylus_sdk::storage::StorageCache;
+use thiserror::Error;
+
+use crate::host_test_shims::{self, storage::Word};
+
+/// The magic bytes at the start of a binary dump.
+pub const MAGIC: [u8; 4] = *b"SWSD";
+
+/// The version of the format written by this module.
+pub const VERSION: u8 = 1;
+
+/// Errors returned when loading a dump.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum DumpError {
+    #[error("Unsupported dump version {0}")]
+    UnsupportedVersion(u64),
+
+    #[error("Malformed dump: {0}")]
+    Malformed(&'static str),
+
+    #[error("Failed to read or write dump: {0}")]
+    Io(String),
+}
+
+/// A snapshot of every non-zero storage slot.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct StorageDump {
+    pub slots: BTreeMap<Word, Word>,
+}
+
+impl StorageDump {
+    /// Dumps the current contents of the host storage.
+    pub fn capture() -> Self {
+        // make sure any cached writes have made it to the host storage
+        StorageCache::flush();
+
+        let slots = host_test_shims::storage::STORAGE.with(|storage| {
+            storage
+                .borrow()
+                .iter()
+                .filter(|(_, value)| **value != Word::default())
+                .map(|(key, value)| (*key, *value))
+                .collect()
+        });
+
+        StorageDump { slots }
+    }
+
+    /// Replaces the contents of the host storage with this dump.
+    pub fn restore(&self) {
+        StorageCache::clear();
+
+        host_test_shims::storage::STORAGE.with(|storage| {
+            let mut storage = storage.borrow_mut();
+            storage.clear();
+            storage.extend(self.slots.iter().map(|(key, value)| (*key, *value)));
+        });
+    }
+
+    /// Encodes the dump as JSON.
+    pub fn to_json(&self) -> String {
+        let slots = self
+            .slots
+            .iter()
+            .map(|(key, value)| {
+                format!(
+                    "    \"{}\": \"{}\"",
+                    const_hex::encode_prefixed(key),
+                    const_hex::encode_prefixed(value)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",\n");
+
+        format!("{{\n  \"version\": {VERSION},\n  \"slots\": {{\n{slots}\n  }}\n}}\n")
+    }
+
+    /// Decodes a JSON dump, as written by [Self::to_json].
+    pub fn from_json(json: &str) -> Result<Self, DumpError> {
+        let mut parser = JsonParser(json);
+
+        let mut version = None;
+        let mut slots = None;
+
+        parser.expect('{')?;
+        loop {
+            match parser.string()? {
+                "version" => {
+                    parser.expect(':')?;
+                    version = Some(parser.number()?);
+                }
+                "slots" => {
+                    parser.expect(':')?;
+                    slots = Some(parser.slots()?);
+                }
+                _ => return Err(DumpError::Malformed("unknown field")),
+            }
+
+            if !parser.next_item('}')? {
+                break;
+            }
+        }
+        parser.end()?;
+
+        match version {
+            Some(1) => {}
+            Some(version) => return Err(DumpError::UnsupportedVersion(version)),
+            None => return Err(DumpError::Malformed("missing version")),
+        }
+
+        Ok(StorageDump {
+            slots: slots.ok_or(DumpError::Malformed("missing slots"))?,
+        })
+    }
+
+    /// Encodes the dump in the compact binary format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(4 + 1 + 4 + self.slots.len() * 64);
+
+        bytes.extend_from_slice(&MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&(self.slots.len() as u32).to_be_bytes());
+        for (key, value) in &self.slots {
+            bytes.extend_from_slice(key);
+            bytes.extend_from_slice(value);
+        }
+
+        bytes
+    }
+
+    /// Decodes a binary dump, as written by [Self::to_bytes].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DumpError> {
+        let (magic, bytes) = bytes
+            .split_first_chunk::<4>()
+            .ok_or(DumpError::Malformed("missing magic"))?;
+        if *magic != MAGIC {
+            return Err(DumpError::Malformed("bad magic"));
+        }
+
+        let (version, bytes) = bytes
+            .split_first()
+            .ok_or(DumpError::Malformed("missing version"))?;
+        if *version != VERSION {
+            return Err(DumpError::UnsupportedVersion(*version as u64));
+        }
+
+        let (count, bytes) = bytes
+            .split_first_chunk::<4>()
+            .ok_or(DumpError::Malformed("missing slot count"))?;
+        let count = u32::from_be_bytes(*count) as usize;
+
+        if bytes.len() != count * 64 {
+            return Err(DumpError::Malformed("slot count doesn't match length"));
+        }
+
+        let slots = bytes
+            .chunks_exact(64)
+            .map(|slot| {
+                let (key, value) = slot.split_at(32);
+                // chunks are always 64 bytes, so these can't fail
+                (word(key), word(value))
+            })
+            .collect();
+
+        Ok(StorageDump { slots })
+    }
+
+    /// Writes the dump to a file, as JSON if the file ends in `.json`, and as binary otherwise.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), DumpError> {
+        let path = path.as_ref();
+        let contents = match is_json(path) {
+            true => self.to_json().into_bytes(),
+            false => self.to_bytes(),
+        };
+
+        std::fs::write(path, contents).map_err(|e| DumpError::Io(e.to_string()))
+    }
+
+    /// Reads a dump from a file, as JSON if the file ends in `.json`, and as binary otherwise.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, DumpError> {
+        let path = path.as_ref();
+        let contents = std::fs::read(path).map_err(|e| DumpError::Io(e.to_string()))?;
+
+        match is_json(path) {
+            true => Self::from_json(
+                std::str::from_utf8(&contents).map_err(|_| DumpError::Malformed("not utf-8"))?,
+            ),
+            false => Self::from_bytes(&contents),
+        }
+    }
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "json")
+}
+
+fn word(bytes: &[u8]) -> Word {
+    let mut word = Word::default();
+    word.copy_from_slice(bytes);
+    word
+}
+
+/// A minimal parser for the subset of JSON used by dumps.
+struct JsonParser<'a>(&'a str);
+
+impl<'a> JsonParser<'a> {
+    fn skip_whitespace(&mut self) {
+        self.0 = self.0.trim_start();
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), DumpError> {
+        self.skip_whitespace();
+        self.0 = self
+            .0
+            .strip_prefix(c)
+            .ok_or(DumpError::Malformed("unexpected character"))?;
+        Ok(())
+    }
+
+    /// Consumes either a comma, returning true, or the closing character, returning false.
+    fn next_item(&mut self, close: char) -> Result<bool, DumpError> {
+        self.skip_whitespace();
+        if let Some(rest) = self.0.strip_prefix(',') {
+            self.0 = rest;
+            Ok(true)
+        } else {
+            self.expect(close)?;
+            Ok(false)
+        }
+    }
+
+    fn string(&mut self) -> Result<&'a str, DumpError> {
+        self.expect('"')?;
+        let (s, rest) = self
+            .0
+            .split_once('"')
+            .ok_or(DumpError::Malformed("unterminated string"))?;
+        self.0 = rest;
+        Ok(s)
+    }
+
+    fn number(&mut self) -> Result<u64, DumpError> {
+        self.skip_whitespace();
+        let len = self
+            .0
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.0.len());
+        let (n, rest) = self.0.split_at(len);
+        self.0 = rest;
+        n.parse().map_err(|_| DumpError::Malformed("bad number"))
+    }
+
+    fn word(&mut self) -> Result<Word, DumpError> {
+        const_hex::decode_to_array(self.string()?).map_err(|_| DumpError::Malformed("bad word"))
+    }
+
+    fn slots(&mut self) -> Result<BTreeMap<Word, Word>, DumpError> {
+        let mut slots = BTreeMap::new();
+
+        self.expect('{')?;
+        self.skip_whitespace();
+        if let Some(rest) = self.0.strip_prefix('}') {
+            self.0 = rest;
+            return Ok(slots);
+        }
+
+        loop {
+            let key = self.word()?;
+            self.expect(':')?;
+            let value = self.word()?;
+            slots.insert(key, value);
+
+            if !self.next_item('}')? {
+                return Ok(slots);
+            }
+        }
+    }
+
+    fn end(&mut self) -> Result<(), DumpError> {
+        self.skip_whitespace();
+        match self.0.is_empty() {
+            true => Ok(()),
+            false => Err(DumpError::Malformed("trailing data")),
+        }
+    }
+}
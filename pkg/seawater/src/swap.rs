@@ -0,0 +1,278 @@
+//! The swap loop, shared between [crate::pool::StoragePool] and in memory pool snapshots.
+
+use crate::error::Error;
+use crate::maths::{full_math, liquidity_math, swap_math, tick_math};
+use crate::types::{I256Extension, U256Extension, I256, U256};
+
+#[cfg(feature = "testing-dbg")]
+use crate::current_test;
+
+/// The ticks a swap moves through, either backed by storage or by an in memory snapshot.
+pub trait SwapTicks {
+    /// Finds the next initialised tick within the same word as the current tick. See
+    /// [crate::maths::tick_bitmap::next_initialized_tick_within_one_word].
+    fn next_initialized_tick_within_one_word(
+        &self,
+        tick: i32,
+        tick_spacing: i32,
+        lte: bool,
+    ) -> Result<(i32, bool), Error>;
+
+    /// Crosses an initialised tick in the direction of the swap, returning its liquidity net.
+    fn cross(
+        &mut self,
+        tick: i32,
+        fee_growth_global_0: &U256,
+        fee_growth_global_1: &U256,
+        zero_for_one: bool,
+    ) -> Result<i128, Error>;
+}
+
+/// The state of a pool at the start of a swap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapStart {
+    pub sqrt_price: U256,
+    pub tick: i32,
+    pub liquidity: u128,
+    pub fee: u32,
+    pub tick_spacing: u8,
+    // packed token0[4], token1[4]
+    pub fee_protocol: u8,
+    pub fee_growth_global_0: U256,
+    pub fee_growth_global_1: U256,
+}
+
+/// The state of a pool after a swap, and the amounts swapped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapOutcome {
+    pub amount_0: I256,
+    pub amount_1: I256,
+    pub sqrt_price: U256,
+    pub tick: i32,
+    pub liquidity: u128,
+    /// The fee growth of the input token.
+    pub fee_growth_global: U256,
+    /// The protocol fee taken in the input token.
+    pub protocol_fee: u128,
+    /// The number of initialised ticks crossed.
+    pub ticks_crossed: u32,
+}
+
+/// Performs a swap over a set of ticks, starting from the given pool state.
+///
+/// This doesn't modify any pool state besides crossing ticks - the caller is expected to write
+/// the outcome back to the pool.
+///
+/// # Errors
+/// Requires the price limit to be in the direction of the swap, and within the bounds of the
+/// possible prices. A price limit of [U256::MAX] is treated as no price limit.
+pub fn swap(
+    ticks: &mut impl SwapTicks,
+    start: SwapStart,
+    zero_for_one: bool,
+    amount: I256,
+    mut price_limit: U256,
+) -> Result<SwapOutcome, Error> {
+    // ensure the price limit is within bounds
+    match zero_for_one {
+        true => {
+            if price_limit == U256::MAX {
+                price_limit = tick_math::MIN_SQRT_RATIO + U256::one();
+            }
+            if price_limit >= start.sqrt_price || price_limit <= tick_math::MIN_SQRT_RATIO {
+                Err(Error::PriceLimitTooLow {
+                    limit: price_limit,
+                    price: start.sqrt_price,
+                })?;
+            }
+        }
+        false => {
+            if price_limit == U256::MAX {
+                price_limit = tick_math::MAX_SQRT_RATIO - U256::one();
+            }
+            if price_limit <= start.sqrt_price || price_limit >= tick_math::MAX_SQRT_RATIO {
+                Err(Error::PriceLimitTooHigh {
+                    limit: price_limit,
+                    price: start.sqrt_price,
+                })?;
+            }
+        }
+    };
+
+    #[cfg(feature = "testing-dbg")]
+    dbg!((
+        "inside swap pool function",
+        current_test!(),
+        price_limit.to_string()
+    ));
+
+    // is the swap exact in or exact out
+    let exact_in = amount > I256::zero();
+
+    // select either the high or low 4 bits
+    let fee_protocol = match zero_for_one {
+        true => start.fee_protocol % 16,
+        false => start.fee_protocol >> 4,
+    };
+
+    // group all our cached storage state into a struct
+    struct SwapState {
+        amount_remaining: I256,
+        amount_calculated: I256,
+        price: U256,
+        tick: i32,
+        fee_growth_global: U256,
+        protocol_fee: u128,
+        liquidity: u128,
+    }
+
+    let mut state = SwapState {
+        amount_remaining: amount,
+        amount_calculated: I256::zero(),
+        price: start.sqrt_price,
+        tick: start.tick,
+        fee_growth_global: match zero_for_one {
+            true => start.fee_growth_global_0,
+            false => start.fee_growth_global_1,
+        },
+        protocol_fee: 0,
+        liquidity: start.liquidity,
+    };
+
+    let fee = start.fee;
+
+    // continue swapping while there's tokens left to swap
+    // and we haven't reached the price limit
+    let mut iters = 0;
+    let mut ticks_crossed = 0;
+    while !state.amount_remaining.is_zero() && state.price != price_limit {
+        iters += 1;
+        debug_assert!(iters < 500);
+
+        let step_initial_price = state.price;
+
+        // find the next tick based on which direction we're swapping
+        let (step_next_tick, step_next_tick_initialised) = ticks
+            .next_initialized_tick_within_one_word(
+                state.tick,
+                start.tick_spacing.into(),
+                zero_for_one,
+            )?;
+
+        // make sure the next tick's within bounds
+        let step_next_tick = step_next_tick.clamp(tick_math::MIN_TICK, tick_math::MAX_TICK);
+
+        let step_next_price = tick_math::get_sqrt_ratio_at_tick(step_next_tick)?;
+
+        #[cfg(feature = "testing-dbg")]
+        dbg!((
+            "swapping",
+            state.amount_remaining.to_string(),
+            state.price.to_string(),
+            price_limit.to_string(),
+            iters,
+            step_next_tick,
+            step_next_tick_initialised,
+            step_next_price.to_string()
+        ));
+
+        // swap til the tick is reached or the price limit is reached or the in/out amount is
+        // used
+        // (price limits are checked in the while loop)
+        let hit_limit = match zero_for_one {
+            true => step_next_price < price_limit,
+            false => step_next_price > price_limit,
+        };
+        let step_clamped_price = match hit_limit {
+            true => price_limit,
+            false => step_next_price,
+        };
+
+        // step_fee_amount is reduced by protocol fee later
+        let (next_sqrt_price, step_amount_in, step_amount_out, mut step_fee_amount) =
+            swap_math::compute_swap_step(
+                state.price,
+                step_clamped_price,
+                state.liquidity,
+                state.amount_remaining,
+                fee,
+            )?;
+
+        state.price = next_sqrt_price;
+
+        // update state
+        match exact_in {
+            true => {
+                state.amount_remaining -= I256::unchecked_from(step_amount_in + step_fee_amount);
+                state.amount_calculated -= I256::unchecked_from(step_amount_out);
+            }
+            false => {
+                state.amount_remaining += I256::unchecked_from(step_amount_out);
+                state.amount_calculated += I256::unchecked_from(step_amount_in + step_fee_amount);
+            }
+        }
+
+        // set fees
+        if fee_protocol > 0 {
+            let delta = step_fee_amount.wrapping_div(U256::from(fee_protocol));
+            step_fee_amount -= delta;
+            state.protocol_fee += u128::try_from(delta).or(Err(Error::FeeTooHigh))?;
+        }
+
+        // update fees
+        if state.liquidity > 0 {
+            // normalise fee growth
+            state.fee_growth_global += full_math::mul_div(
+                step_fee_amount,
+                full_math::Q128,
+                U256::from(state.liquidity),
+            )?;
+        }
+
+        // shift tick
+        if state.price == step_next_price {
+            if step_next_tick_initialised {
+                let (fee_0, fee_1) = match zero_for_one {
+                    true => (state.fee_growth_global, start.fee_growth_global_1),
+                    false => (start.fee_growth_global_0, state.fee_growth_global),
+                };
+
+                let liquidity_net = ticks.cross(step_next_tick, &fee_0, &fee_1, zero_for_one)?;
+                ticks_crossed += 1;
+
+                // flip the liquidity delta if we're moving leftwards
+                let liquidity_net = match zero_for_one {
+                    true => liquidity_net.wrapping_neg(),
+                    false => liquidity_net,
+                };
+
+                state.liquidity = liquidity_math::add_delta(state.liquidity, liquidity_net)?;
+            }
+
+            state.tick = match zero_for_one {
+                true => step_next_tick - 1,
+                false => step_next_tick,
+            };
+        } else if state.price != step_initial_price {
+            // recompute tick in case we've moved past ticks with no liquidity
+            state.tick = tick_math::get_tick_at_sqrt_ratio(state.price)?;
+        }
+    }
+
+    let token0_is_input = (zero_for_one && exact_in) || (!zero_for_one && !exact_in);
+    let (amount_0, amount_1) = match token0_is_input {
+        true => (amount - state.amount_remaining, state.amount_calculated),
+        false => (state.amount_calculated, amount - state.amount_remaining),
+    };
+
+    Ok(SwapOutcome {
+        amount_0,
+        amount_1,
+        sqrt_price: state.price,
+        tick: state.tick,
+        liquidity: state.liquidity,
+        fee_growth_global: state.fee_growth_global,
+        protocol_fee: state.protocol_fee,
+        ticks_crossed,
+    })
+}
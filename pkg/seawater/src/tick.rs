@@ -1,8 +1,10 @@
 //! Structures and functions to track and update details on a pool's ticks.
 
 use crate::error::*;
-use crate::maths::liquidity_math;
+use crate::maths::{full_math, liquidity_math, sqrt_price_math, tick_bitmap, tick_math};
+use crate::position;
 use crate::types::*;
+use alloc::vec::Vec;
 
 #[cfg(feature = "testing-dbg")]
 use crate::current_test;
@@ -26,9 +28,10 @@ impl StorageTickBitmap {
         assert!(tick % spacing == 0); // ensure the tick lies on a valid space
 
         let spaced_tick = tick / spacing;
-        let (word_pos, bit_pos) = ((spaced_tick >> 8) as i16, (spaced_tick % 256));
+        // the bit position must wrap for negative ticks, the same as when the bitmap is read
+        let (word_pos, bit_pos) = tick_bitmap::position(spaced_tick);
 
-        let mask = U256::one().wrapping_shl(bit_pos as usize);
+        let mask = U256::one() << bit_pos;
         let bitmap = self.bitmap.get(word_pos) ^ mask;
 
         #[cfg(feature = "testing-dbg")]
@@ -41,6 +44,24 @@ impl StorageTickBitmap {
 
         self.bitmap.setter(word_pos).set(bitmap);
     }
+
+    /// Rewrites a word of the bitmap from the ticks that are initialised, restoring bits that
+    /// were never set for negative ticks before [Self::flip] wrapped their bit positions.
+    pub fn rebuild_word(&mut self, word_pos: i16, spacing: u8, ticks: &StorageTicks) {
+        let spacing = spacing as i32;
+
+        let mut word = U256::ZERO;
+        for bit_pos in 0..=u8::MAX {
+            let tick = (word_pos as i32 * 256 + bit_pos as i32) * spacing;
+            if (tick_math::MIN_TICK..=tick_math::MAX_TICK).contains(&tick)
+                && ticks.initialised(tick)
+            {
+                word |= U256::one() << bit_pos;
+            }
+        }
+
+        self.bitmap.setter(word_pos).set(word);
+    }
 }
 
 /// Storage type for details on a tick.
@@ -57,6 +78,23 @@ pub struct StorageTickInfo {
     initialised: StorageBool,
 }
 
+/// The pool's cumulative values at the current time, which ticks record the part of that happened
+/// on their other side from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Cumulatives {
+    /// The tick, multiplied by the seconds it was the current tick for. See [crate::oracle].
+    pub tick_cumulative: i64,
+    /// The seconds elapsed divided by the in range liquidity, as an X128 number. See
+    /// [crate::oracle].
+    pub seconds_per_liquidity_cumulative_x128: U160,
+    /// The current block timestamp, truncated.
+    pub time: u32,
+}
+
+/// An initialised tick, as (tick, liquidity gross, liquidity net, fee growth outside 0, fee growth
+/// outside 1).
+pub type InitialisedTick = (i32, u128, i128, U256, U256);
+
 /// Container type for the map of tick indexes to ticks.
 #[solidity_storage]
 pub struct StorageTicks {
@@ -74,6 +112,7 @@ impl StorageTicks {
         liquidity_delta: i128,
         fee_growth_global_0: &U256,
         fee_growth_global_1: &U256,
+        cumulatives: &Cumulatives,
         upper: bool,
         max_liquidity: u128,
     ) -> Result<bool, Error> {
@@ -93,10 +132,16 @@ impl StorageTicks {
         if liquidity_gross_before == 0 {
             // initialise ourself
 
-            // if we're below the current tick then set fee growth outside
+            // if we're below the current tick then set fee growth outside, and assume the time
+            // so far was spent below us too
             if tick <= cur_amm_tick {
                 info.fee_growth_outside_0.set(*fee_growth_global_0);
                 info.fee_growth_outside_1.set(*fee_growth_global_1);
+                info.tick_cumulative_outside
+                    .set(I64::lib(&cumulatives.tick_cumulative));
+                info.seconds_per_liquidity_outside
+                    .set(cumulatives.seconds_per_liquidity_cumulative_x128);
+                info.seconds_outside.set(U32::lib(&cumulatives.time));
             }
             info.initialised.set(true);
         }
@@ -123,7 +168,7 @@ impl StorageTicks {
 
     /// Gets the fee growth inside a tick range.
     pub fn get_fee_growth_inside(
-        &mut self,
+        &self,
         lower_tick: i32,
         upper_tick: i32,
         cur_tick: i32,
@@ -245,13 +290,81 @@ impl StorageTicks {
         ))
     }
 
-    /// Updates a tick's fee information when the tick is crossed.
+    /// Gets the cumulative tick, seconds per liquidity, and seconds spent inside a tick range,
+    /// which are only meaningful compared to an earlier snapshot taken while both ticks were
+    /// initialised. A port of uniswap v3's `snapshotCumulativesInside`.
+    ///
+    /// # Errors
+    /// Requires both ticks to be initialised.
+    pub fn get_cumulatives_inside(
+        &self,
+        lower_tick: i32,
+        upper_tick: i32,
+        cur_tick: i32,
+        cumulatives: &Cumulatives,
+    ) -> Result<(i64, U160, u32), Error> {
+        let lower = self.ticks.get(lower_tick);
+        let upper = self.ticks.get(upper_tick);
+        assert_or!(lower.initialised.get(), Error::TickNotInitialised);
+        assert_or!(upper.initialised.get(), Error::TickNotInitialised);
+
+        let (tick_cumulative_lower, tick_cumulative_upper) = (
+            lower.tick_cumulative_outside.get().sys(),
+            upper.tick_cumulative_outside.get().sys(),
+        );
+        let (seconds_per_liquidity_lower, seconds_per_liquidity_upper) = (
+            lower.seconds_per_liquidity_outside.get(),
+            upper.seconds_per_liquidity_outside.get(),
+        );
+        let (seconds_lower, seconds_upper) = (
+            lower.seconds_outside.get().sys(),
+            upper.seconds_outside.get().sys(),
+        );
+
+        Ok(if cur_tick < lower_tick {
+            (
+                tick_cumulative_lower.wrapping_sub(tick_cumulative_upper),
+                seconds_per_liquidity_lower.wrapping_sub(seconds_per_liquidity_upper),
+                seconds_lower.wrapping_sub(seconds_upper),
+            )
+        } else if cur_tick < upper_tick {
+            (
+                cumulatives
+                    .tick_cumulative
+                    .wrapping_sub(tick_cumulative_lower)
+                    .wrapping_sub(tick_cumulative_upper),
+                cumulatives
+                    .seconds_per_liquidity_cumulative_x128
+                    .wrapping_sub(seconds_per_liquidity_lower)
+                    .wrapping_sub(seconds_per_liquidity_upper),
+                cumulatives
+                    .time
+                    .wrapping_sub(seconds_lower)
+                    .wrapping_sub(seconds_upper),
+            )
+        } else {
+            (
+                tick_cumulative_upper.wrapping_sub(tick_cumulative_lower),
+                seconds_per_liquidity_upper.wrapping_sub(seconds_per_liquidity_lower),
+                seconds_upper.wrapping_sub(seconds_lower),
+            )
+        })
+    }
+
+    /// Updates a tick's fee and time information when the tick is crossed, and fills the range
+    /// orders waiting for a swap in this direction to cross it.
+    ///
+    /// Returns the tick's liquidity net from before any orders were filled, and the ticks left
+    /// with no liquidity by filling them, which need to be flipped in the tick bitmap.
     pub fn cross(
         &mut self,
         tick: i32,
         fee_growth_global_0: &U256,
         fee_growth_global_1: &U256,
-    ) -> i128 {
+        cumulatives: &Cumulatives,
+        zero_for_one: bool,
+        range_orders: &mut StorageRangeOrders,
+    ) -> Result<(i128, Vec<i32>), Error> {
         let mut info = self.ticks.setter(tick);
 
         let new_fee_growth_outside_0 = fee_growth_global_0 - info.fee_growth_outside_0.get();
@@ -260,10 +373,118 @@ impl StorageTicks {
         let new_fee_growth_outside_1 = fee_growth_global_1 - info.fee_growth_outside_1.get();
         info.fee_growth_outside_1.set(new_fee_growth_outside_1);
 
+        let new_tick_cumulative_outside = cumulatives
+            .tick_cumulative
+            .wrapping_sub(info.tick_cumulative_outside.get().sys());
+        info.tick_cumulative_outside
+            .set(I64::lib(&new_tick_cumulative_outside));
+
+        let new_seconds_per_liquidity_outside = cumulatives
+            .seconds_per_liquidity_cumulative_x128
+            .wrapping_sub(info.seconds_per_liquidity_outside.get());
+        info.seconds_per_liquidity_outside
+            .set(new_seconds_per_liquidity_outside);
+
+        let new_seconds_outside = cumulatives
+            .time
+            .wrapping_sub(info.seconds_outside.get().sys());
+        info.seconds_outside.set(U32::lib(&new_seconds_outside));
+
         let r = info.liquidity_net.sys();
         #[cfg(feature = "testing-dbg")]
         dbg!(("liquidity net", r));
-        r
+
+        let flipped = self.fill_range_orders(
+            tick,
+            fee_growth_global_0,
+            fee_growth_global_1,
+            cumulatives,
+            zero_for_one,
+            range_orders,
+        )?;
+
+        Ok((r, flipped))
+    }
+
+    /// Fills the range orders waiting for a tick to be crossed, removing their liquidity from
+    /// the range and crediting them with the tokens it's now worth. Returns the ticks left with no
+    /// liquidity, which have been cleared.
+    fn fill_range_orders(
+        &mut self,
+        tick: i32,
+        fee_growth_global_0: &U256,
+        fee_growth_global_1: &U256,
+        cumulatives: &Cumulatives,
+        zero_for_one: bool,
+        range_orders: &mut StorageRangeOrders,
+    ) -> Result<Vec<i32>, Error> {
+        let id = range_orders.take_pending(tick, zero_for_one);
+        if id.is_zero() {
+            return Ok(Vec::new());
+        }
+
+        let mut epoch = range_orders.epochs.setter(id);
+        let lower = epoch.lower.get().sys();
+        let upper = epoch.upper.get().sys();
+        let liquidity = epoch.liquidity_total.get().sys();
+
+        // the tick the pool will be at once this tick is crossed
+        let cur_tick = match zero_for_one {
+            true => tick - 1,
+            false => tick,
+        };
+
+        let (fee_growth_inside_0, fee_growth_inside_1) = self.get_fee_growth_inside(
+            lower,
+            upper,
+            cur_tick,
+            fee_growth_global_0,
+            fee_growth_global_1,
+        )?;
+        epoch.accrue(fee_growth_inside_0, fee_growth_inside_1)?;
+        epoch.fill(lower, upper, liquidity)?;
+
+        let delta = -(liquidity as i128);
+        let mut flipped = Vec::new();
+        for (t, upper) in [(lower, false), (upper, true)] {
+            let tick_flipped = self.update(
+                t,
+                cur_tick,
+                delta,
+                fee_growth_global_0,
+                fee_growth_global_1,
+                cumulatives,
+                upper,
+                u128::MAX,
+            )?;
+            if tick_flipped {
+                self.clear(t);
+                flipped.push(t);
+            }
+        }
+
+        Ok(flipped)
+    }
+
+    /// Gets a tick's liquidity gross, liquidity net, and fee growth outside for both tokens.
+    pub fn get_info(&self, tick: i32) -> (u128, i128, U256, U256) {
+        let info = self.ticks.get(tick);
+        (
+            info.liquidity_gross.get().sys(),
+            info.liquidity_net.get().sys(),
+            info.fee_growth_outside_0.get(),
+            info.fee_growth_outside_1.get(),
+        )
+    }
+
+    /// Returns if a tick has liquidity referencing it.
+    pub fn initialised(&self, tick: i32) -> bool {
+        self.ticks.get(tick).initialised.get()
+    }
+
+    /// Gets the net liquidity added when a tick is crossed from left to right.
+    pub fn liquidity_net(&self, tick: i32) -> i128 {
+        self.ticks.get(tick).liquidity_net.get().sys()
     }
 
     /// Deletes a tick from the map, freeing storage slots.
@@ -272,3 +493,268 @@ impl StorageTicks {
         self.ticks.delete(tick);
     }
 }
+
+/// Storage type for a batch of range orders over the same range, selling the same token. The
+/// orders are filled together when the pool's price crosses the far side of their range, and
+/// share the tokens they're filled with and the fees they earn by liquidity.
+#[solidity_storage]
+pub struct StorageRangeOrderEpoch {
+    pub lower: StorageI32,
+    pub upper: StorageI32,
+    // true if the orders are selling token 0 for token 1
+    pub zero_for_one: StorageBool,
+    pub filled: StorageBool,
+    pub liquidity_total: StorageU128,
+    pub liquidity: StorageMap<Address, StorageU128>,
+    fee_growth_inside_0: StorageU256,
+    fee_growth_inside_1: StorageU256,
+    // tokens owed to the orders, from fees and from being filled
+    amount_0: StorageU256,
+    amount_1: StorageU256,
+}
+
+impl StorageRangeOrderEpoch {
+    /// Credits the orders with the fees their liquidity has earned since they were last updated.
+    fn accrue(
+        &mut self,
+        fee_growth_inside_0: U256,
+        fee_growth_inside_1: U256,
+    ) -> Result<(), Error> {
+        let liquidity = self.liquidity_total.get();
+
+        let fees_0 = position::owed_fees(
+            fee_growth_inside_0,
+            self.fee_growth_inside_0.get(),
+            liquidity,
+        )?;
+        let fees_1 = position::owed_fees(
+            fee_growth_inside_1,
+            self.fee_growth_inside_1.get(),
+            liquidity,
+        )?;
+
+        self.fee_growth_inside_0.set(fee_growth_inside_0);
+        self.fee_growth_inside_1.set(fee_growth_inside_1);
+        if !fees_0.is_zero() {
+            let amount_0 = self.amount_0.get() + fees_0;
+            self.amount_0.set(amount_0);
+        }
+        if !fees_1.is_zero() {
+            let amount_1 = self.amount_1.get() + fees_1;
+            self.amount_1.set(amount_1);
+        }
+
+        Ok(())
+    }
+
+    /// Marks the orders as filled, crediting them with their liquidity's worth of the token
+    /// they're buying.
+    fn fill(&mut self, lower: i32, upper: i32, liquidity: u128) -> Result<(), Error> {
+        let sqrt_lower = tick_math::get_sqrt_ratio_at_tick(lower)?;
+        let sqrt_upper = tick_math::get_sqrt_ratio_at_tick(upper)?;
+
+        match self.zero_for_one.get() {
+            true => {
+                let amount_1 = self.amount_1.get()
+                    + sqrt_price_math::_get_amount_1_delta(
+                        sqrt_lower, sqrt_upper, liquidity, false,
+                    )?;
+                self.amount_1.set(amount_1);
+            }
+            false => {
+                let amount_0 = self.amount_0.get()
+                    + sqrt_price_math::_get_amount_0_delta(
+                        sqrt_lower, sqrt_upper, liquidity, false,
+                    )?;
+                self.amount_0.set(amount_0);
+            }
+        }
+        self.filled.set(true);
+
+        Ok(())
+    }
+
+    /// Removes a user's order, returning its liquidity and its share of the tokens owed to the
+    /// orders.
+    ///
+    /// # Errors
+    /// Requires the user to have an order in this epoch.
+    fn withdraw(&mut self, owner: Address) -> Result<(u128, U256, U256), Error> {
+        let liquidity = self.liquidity.get(owner);
+        assert_neq_or!(liquidity, U128::ZERO, Error::RangeOrderEmpty);
+
+        let liquidity_total = self.liquidity_total.get();
+        let amount_0 = self.amount_0.get();
+        let amount_1 = self.amount_1.get();
+        let share_0 =
+            full_math::mul_div(amount_0, U256::from(liquidity), U256::from(liquidity_total))?;
+        let share_1 =
+            full_math::mul_div(amount_1, U256::from(liquidity), U256::from(liquidity_total))?;
+
+        self.amount_0.set(amount_0 - share_0);
+        self.amount_1.set(amount_1 - share_1);
+        self.liquidity_total.set(liquidity_total - liquidity);
+        self.liquidity.delete(owner);
+
+        Ok((liquidity.sys(), share_0, share_1))
+    }
+}
+
+/// A user's range order, as (lower tick, upper tick, if it's selling token 0, if it's been
+/// filled, liquidity).
+pub type RangeOrder = (i32, i32, bool, bool, u128);
+
+/// Container type for a pool's range orders, batched into epochs. Orders placed in the same
+/// range and direction before the range is crossed share an epoch, and are filled by
+/// [StorageTicks::cross].
+#[solidity_storage]
+pub struct StorageRangeOrders {
+    pub epochs: StorageMap<U256, StorageRangeOrderEpoch>,
+    // the last epoch created, epochs start at 1
+    next_epoch: StorageU256,
+    // the unfilled epoch selling token 0, by its upper tick
+    pending_0: StorageMap<i32, StorageU256>,
+    // the unfilled epoch selling token 1, by its lower tick
+    pending_1: StorageMap<i32, StorageU256>,
+}
+
+impl StorageRangeOrders {
+    /// Gets a user's order in an epoch.
+    pub fn get(&self, id: U256, owner: Address) -> RangeOrder {
+        let epoch = self.epochs.getter(id);
+        (
+            epoch.lower.get().sys(),
+            epoch.upper.get().sys(),
+            epoch.zero_for_one.get(),
+            epoch.filled.get(),
+            epoch.liquidity.get(owner).sys(),
+        )
+    }
+
+    /// Adds a user's order to the unfilled epoch for its range and direction, creating one if
+    /// needed, and returns the epoch.
+    ///
+    /// # Calling requirements
+    /// Requires the liquidity to have been added to the range's ticks, and `fee_growth_inside_0`
+    /// and `fee_growth_inside_1` to be the range's fee growth after doing so.
+    #[allow(clippy::too_many_arguments)]
+    pub fn place(
+        &mut self,
+        owner: Address,
+        lower: i32,
+        upper: i32,
+        zero_for_one: bool,
+        liquidity: u128,
+        fee_growth_inside_0: U256,
+        fee_growth_inside_1: U256,
+    ) -> Result<U256, Error> {
+        let mut pending = match zero_for_one {
+            true => self.pending_0.setter(upper),
+            false => self.pending_1.setter(lower),
+        };
+
+        let mut id = pending.get();
+        if id.is_zero() {
+            id = self.next_epoch.get() + U256::one();
+            self.next_epoch.set(id);
+            pending.set(id);
+
+            let mut epoch = self.epochs.setter(id);
+            epoch.lower.set(I32::lib(&lower));
+            epoch.upper.set(I32::lib(&upper));
+            epoch.zero_for_one.set(zero_for_one);
+        }
+
+        let mut epoch = self.epochs.setter(id);
+        epoch.accrue(fee_growth_inside_0, fee_growth_inside_1)?;
+
+        let liquidity = U128::lib(&liquidity);
+        let liquidity_total = epoch.liquidity_total.get() + liquidity;
+        epoch.liquidity_total.set(liquidity_total);
+        let owner_liquidity = epoch.liquidity.get(owner) + liquidity;
+        epoch.liquidity.setter(owner).set(owner_liquidity);
+
+        Ok(id)
+    }
+
+    /// Removes a user's unfilled order, returning its liquidity and its share of the fees the
+    /// epoch has earned. The epoch stops taking orders once every order in it is cancelled.
+    ///
+    /// # Calling requirements
+    /// Requires the liquidity to have been removed from the range's ticks, and
+    /// `fee_growth_inside_0` and `fee_growth_inside_1` to be the range's fee growth after doing so.
+    ///
+    /// # Errors
+    /// Requires the epoch to be unfilled, and the user to have an order in it.
+    pub fn cancel(
+        &mut self,
+        owner: Address,
+        id: U256,
+        fee_growth_inside_0: U256,
+        fee_growth_inside_1: U256,
+    ) -> Result<(u128, U256, U256), Error> {
+        let mut epoch = self.epochs.setter(id);
+        assert_or!(!epoch.filled.get(), Error::RangeOrderFilled);
+
+        epoch.accrue(fee_growth_inside_0, fee_growth_inside_1)?;
+        let withdrawn = epoch.withdraw(owner)?;
+
+        if epoch.liquidity_total.get().is_zero() {
+            match epoch.zero_for_one.get() {
+                true => self.pending_0.delete(epoch.upper.get().sys()),
+                false => self.pending_1.delete(epoch.lower.get().sys()),
+            }
+        }
+
+        Ok(withdrawn)
+    }
+
+    /// Removes a user's filled order, returning its share of the tokens the epoch was filled
+    /// with and the fees it earned.
+    ///
+    /// # Errors
+    /// Requires the epoch to be filled, and the user to have an order in it.
+    pub fn claim(&mut self, owner: Address, id: U256) -> Result<(U256, U256), Error> {
+        let mut epoch = self.epochs.setter(id);
+        assert_or!(epoch.filled.get(), Error::RangeOrderNotFilled);
+
+        let (_, amount_0, amount_1) = epoch.withdraw(owner)?;
+        Ok((amount_0, amount_1))
+    }
+
+    /// Gets the unfilled epoch filled when a swap crosses a tick, as (lower tick, upper tick,
+    /// liquidity), if there is one. Epochs selling token 0 are filled by their upper tick, and
+    /// epochs selling token 1 by their lower tick.
+    pub fn pending(&self, tick: i32, zero_for_one: bool) -> Option<(i32, i32, u128)> {
+        let id = match zero_for_one {
+            true => self.pending_0.get(tick),
+            false => self.pending_1.get(tick),
+        };
+        if id.is_zero() {
+            return None;
+        }
+
+        let epoch = self.epochs.getter(id);
+        Some((
+            epoch.lower.get().sys(),
+            epoch.upper.get().sys(),
+            epoch.liquidity_total.get().sys(),
+        ))
+    }
+
+    /// Removes and returns the unfilled epoch waiting for a swap in a direction to cross a tick,
+    /// or zero if there isn't one.
+    fn take_pending(&mut self, tick: i32, zero_for_one: bool) -> U256 {
+        // swaps moving left fill orders selling token 1, and swaps moving right token 0
+        let pending = match zero_for_one {
+            true => &mut self.pending_1,
+            false => &mut self.pending_0,
+        };
+
+        let id = pending.get(tick);
+        if !id.is_zero() {
+            pending.delete(tick);
+        }
+        id
+    }
+}
@@ -0,0 +1,46 @@
+//! Identifiers and delays for admin calls that must be queued before they're executed.
+//!
+//! A timelocked call is identified by the keccak256 hash of its calldata, so the admin queues
+//! `keccak256(abi.encodeCall(...))` with [crate::Pools::queue_timelocked_call_10_C89285], then
+//! makes the same call once the delay has passed. Calls that aren't executed within the grace
+//! period after becoming ready expire, and must be queued again.
+
+use crate::eth_serde::selector;
+use crate::types::{Address, U256};
+use alloc::vec::Vec;
+use stylus_sdk::{alloy_primitives::B256, crypto};
+
+/// The minimum time between a call being queued and it being executable, in seconds.
+pub const TIMELOCK_DELAY: u64 = 2 * 24 * 60 * 60;
+
+/// The time after a call becomes executable that it expires, in seconds.
+pub const TIMELOCK_GRACE_PERIOD: u64 = 14 * 24 * 60 * 60;
+
+/// The selector of `setSqrtPriceFF4DB98C(address,uint256)`.
+pub const SET_SQRT_PRICE_SELECTOR: [u8; 4] = selector(b"setSqrtPriceFF4DB98C(address,uint256)");
+
+/// The selector of `updateNftManager9BDF41F6(address)`.
+pub const UPDATE_NFT_MANAGER_SELECTOR: [u8; 4] = selector(b"updateNftManager9BDF41F6(address)");
+
+/// The selector of `sendTokenToSender(address,uint256)`.
+pub const SEND_TOKEN_TO_SENDER_SELECTOR: [u8; 4] = selector(b"sendTokenToSender(address,uint256)");
+
+/// Computes the identifier of a call, the hash of its selector and ABI encoded static arguments.
+pub fn call_id(selector: [u8; 4], args: &[[u8; 32]]) -> B256 {
+    let mut calldata = Vec::with_capacity(4 + args.len() * 32);
+    calldata.extend_from_slice(&selector);
+    for arg in args {
+        calldata.extend_from_slice(arg);
+    }
+    crypto::keccak(calldata)
+}
+
+/// ABI encodes an address argument.
+pub fn address_arg(address: Address) -> [u8; 32] {
+    address.into_word().0
+}
+
+/// ABI encodes a uint256 argument.
+pub fn uint_arg(value: U256) -> [u8; 32] {
+    value.to_be_bytes()
+}
@@ -96,8 +96,12 @@ impl I256Extension for I256 {
 pub type U160 = stylus_sdk::alloy_primitives::U160;
 /// Re-export of the U128 type.
 pub type U128 = stylus_sdk::alloy_primitives::U128;
+/// Re-export of the U64 type.
+pub type U64 = stylus_sdk::alloy_primitives::U64;
 /// Re-export of the U32 type.
 pub type U32 = stylus_sdk::alloy_primitives::U32;
+/// Re-export of the U16 type.
+pub type U16 = stylus_sdk::alloy_primitives::U16;
 /// Re-export of the U8 type.
 pub type U8 = stylus_sdk::alloy_primitives::U8;
 
@@ -139,6 +143,15 @@ impl WrappedNative<u128> for U128 {
         U128::from_le_bytes(arg.to_le_bytes())
     }
 }
+impl WrappedNative<i64> for I64 {
+    fn sys(&self) -> i64 {
+        self.as_i64()
+    }
+
+    fn lib(arg: &i64) -> Self {
+        I64::unchecked_from(*arg)
+    }
+}
 impl WrappedNative<i32> for I32 {
     fn sys(&self) -> i32 {
         self.as_i32()
@@ -158,6 +171,16 @@ impl WrappedNative<u8> for U8 {
     }
 }
 
+impl WrappedNative<u64> for U64 {
+    fn sys(&self) -> u64 {
+        self.as_limbs()[0]
+    }
+
+    fn lib(arg: &u64) -> Self {
+        Self::from_limbs([*arg])
+    }
+}
+
 impl WrappedNative<u32> for U32 {
     fn sys(&self) -> u32 {
         self.as_limbs()[0] as u32
@@ -167,3 +190,13 @@ impl WrappedNative<u32> for U32 {
         Self::from_limbs([*arg as u64])
     }
 }
+
+impl WrappedNative<u16> for U16 {
+    fn sys(&self) -> u16 {
+        self.as_limbs()[0] as u16
+    }
+
+    fn lib(arg: &u16) -> Self {
+        Self::from_limbs([*arg as u64])
+    }
+}
@@ -76,6 +76,8 @@ const TRANSFER_FROM_SELECTOR: [u8; 4] = selector(
     b"transferFrom(address,address,uint256)",
     [0x23, 0xb8, 0x72, 0xdd],
 );
+/// The selector for `balanceOf(address)`
+const BALANCE_OF_SELECTOR: [u8; 4] = selector(b"balanceOf(address)", [0x70, 0xa0, 0x82, 0x31]);
 // The selector for `decimals()`. No generation function is needed to use this.
 const DECIMALS_SELECTOR: [u8; 4] = selector(b"decimals()", [0x31, 0x3c, 0xe5, 0x67]);
 const PERMIT_TRANSFER_FROM_SELECTOR: [u8; 4] = selector(
@@ -107,6 +109,15 @@ fn encode_transfer_from(from: Address, to: Address, amount: U256) -> [u8; 4 + 32
     data
 }
 
+/// Encodes a call to `balanceOf(address owner)`
+fn encode_balance_of(owner: Address) -> [u8; 4 + 32] {
+    let mut data = [0_u8; 4 + 32];
+    write_selector(&mut data, &BALANCE_OF_SELECTOR);
+    write_address(&mut data, 0, owner);
+
+    data
+}
+
 /// Calls the `transfer` function on a potentially noncomplient ERC20.
 fn safe_transfer(token: Address, to: Address, amount: U256) -> Result<(), Error> {
     call_optional_return(token, &encode_transfer(to, amount))
@@ -287,6 +298,18 @@ pub fn decimals(address: Address) -> Result<u8, Error> {
     }
 }
 
+/// Get this contract's balance of the token given using the "balanceOf" function.
+pub fn balance_of_self(token: Address) -> Result<U256, Error> {
+    match RawCall::new().call(token, &encode_balance_of(contract::address())) {
+        Err(revert) => Err(Error::Erc20Revert(revert)),
+        Ok(data) => match data.get(0..32) {
+            // no balance was returned to us!
+            None => Err(Error::Erc20RevertNoData),
+            Some(balance) => Ok(U256::from_be_slice(balance)),
+        },
+    }
+}
+
 #[cfg(test)]
 mod test {
     use ruint::uint;
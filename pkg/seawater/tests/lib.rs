@@ -1,8 +1,11 @@
 use libseawater::{
     error::Error,
-    eth_serde,
+    eth_serde::{self, QuoteResult},
+    immutables::FUSDC_ADDR,
     maths::{full_math, sqrt_price_math, tick_math},
-    test_utils,
+    pool, roles,
+    storage_dump::StorageDump,
+    test_shims, test_utils, timelock,
     types::I256Extension,
     types::*,
     Pools,
@@ -10,8 +13,8 @@ use libseawater::{
 use maplit::hashmap;
 use ruint_macro::uint;
 use stylus_sdk::{
-    alloy_primitives::{address, bytes},
-    msg,
+    alloy_primitives::{address, bytes, B256},
+    crypto, msg,
 };
 
 #[test]
@@ -786,3 +789,2302 @@ fn ethers_suite_swapping_with_permit2_blobs_no_permit2() {
         },
     );
 }
+
+#[test]
+fn test_set_fee_protocol_and_collect() {
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()), // sender
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+            contract.ctor(msg::sender(), Address::ZERO, Address::ZERO)?;
+            contract.create_pool_D650_E2_D0(
+                token0,
+                U256::from_limbs([0, 42949672960, 0, 0]), //792281625142643375935439503360
+                3000,                                     // fee
+                10,                                       // tick spacing
+                u128::MAX,
+            )?;
+            contract.enable_pool_579_D_A658(token0, true)?;
+            contract.mint_position_B_C5_B086_D(token0, 39120, 50100)?;
+            contract
+                .update_position_C_7_F_1_F_740(token0, U256::ZERO, 10_000_000)
+                .map(|_| ())?;
+
+            // no protocol fee is taken by default
+            contract.swap_904369_B_E(token0, true, I256::try_from(10_000).unwrap(), U256::MAX)?;
+            assert_eq!(
+                contract.collect_protocol_7540_F_A_9_F(
+                    token0,
+                    u128::MAX,
+                    u128::MAX,
+                    msg::sender()
+                )?,
+                (0, 0)
+            );
+
+            contract.set_fee_protocol_1004_E_D95(token0, 4, 5)?;
+
+            contract.swap_904369_B_E(token0, true, I256::try_from(10_000).unwrap(), U256::MAX)?;
+            contract.swap_904369_B_E(token0, false, I256::try_from(100_000).unwrap(), U256::MAX)?;
+
+            // 3000 fee on 10000 token0 is 30, a quarter goes to the protocol
+            // 3000 fee on 100000 fusdc is 300, a fifth goes to the protocol
+            let (amount_0, amount_1) = contract.collect_protocol_7540_F_A_9_F(
+                token0,
+                u128::MAX,
+                u128::MAX,
+                msg::sender(),
+            )?;
+            assert_eq!(amount_0, 7);
+            assert_eq!(amount_1, 60);
+
+            // fees can only be collected once
+            assert_eq!(
+                contract.collect_protocol_7540_F_A_9_F(
+                    token0,
+                    u128::MAX,
+                    u128::MAX,
+                    msg::sender()
+                )?,
+                (0, 0)
+            );
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_set_fee_protocol_reverts() {
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()), // sender
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+            contract.ctor(msg::sender(), Address::ZERO, Address::ZERO)?;
+            contract.create_pool_D650_E2_D0(
+                token0,
+                U256::from_limbs([0, 42949672960, 0, 0]), //792281625142643375935439503360
+                3000,                                     // fee
+                10,                                       // tick spacing
+                u128::MAX,
+            )?;
+
+            for (fee_protocol_0, fee_protocol_1) in [(3, 0), (0, 11), (1, 4), (10, 16)] {
+                assert_eq!(
+                    contract
+                        .set_fee_protocol_1004_E_D95(token0, fee_protocol_0, fee_protocol_1)
+                        .unwrap_err(),
+                    Vec::<u8>::from(Error::InvalidFeeProtocol)
+                );
+            }
+
+            contract.set_fee_protocol_1004_E_D95(token0, 0, 10)?;
+            contract.set_fee_protocol_1004_E_D95(token0, 4, 0)?;
+
+            // only the admin can change the protocol fee
+            contract.seawater_admin.set(Address::with_last_byte(1));
+
+            assert_eq!(
+                contract
+                    .set_fee_protocol_1004_E_D95(token0, 4, 4)
+                    .unwrap_err(),
+                Vec::<u8>::from(Error::SeawaterAdminOnly)
+            );
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn incr_position_permit2_takes_both_tokens() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("feb6034fc7df27df18a3a6bad5fb94c0d3dcb6d5").into_array()),
+        None,
+        Some(hashmap! {
+            token0 => U256::from(200_000),
+            FUSDC_ADDR => U256::from(200_000),
+        }),
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            contract.ctor(msg::sender(), msg::sender(), msg::sender())?;
+
+            contract.create_pool_D650_E2_D0(
+                token0,
+                test_utils::encode_sqrt_price(100, 1),
+                3000,
+                1,
+                u128::MAX,
+            )?;
+
+            contract.enable_pool_579_D_A658(token0, true)?;
+
+            let id = U256::from(0);
+
+            contract.mint_position_B_C5_B086_D(token0, -887272, 887272)?;
+
+            let (amount_0_taken, amount_1_taken) = contract.incr_position_permit_2_5468326_E(
+                token0,
+                id,
+                U256::zero(),
+                U256::zero(),
+                U256::from(1),
+                U256::MAX,
+                U256::from(100_000),
+                vec![0; 65],
+                U256::from(2),
+                U256::MAX,
+                U256::from(100_000),
+                vec![0; 65],
+            )?;
+
+            assert!(amount_0_taken > U256::zero());
+            assert!(amount_1_taken > U256::zero());
+
+            // both tokens were taken from the caller
+            test_shims::storage::CALLER_BALS.with(|bals| {
+                let bals = bals.borrow();
+                assert_eq!(bals[&token0], U256::from(200_000) - amount_0_taken);
+                assert_eq!(bals[&FUSDC_ADDR], U256::from(200_000) - amount_1_taken);
+            });
+
+            assert!(contract.position_liquidity_8_D11_C045(token0, id)? > 0);
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn incr_position_permit2_insufficient_token1() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("feb6034fc7df27df18a3a6bad5fb94c0d3dcb6d5").into_array()),
+        None,
+        Some(hashmap! {
+            token0 => U256::from(200_000),
+            FUSDC_ADDR => U256::from(10),
+        }),
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            contract.ctor(msg::sender(), msg::sender(), msg::sender())?;
+
+            contract.create_pool_D650_E2_D0(
+                token0,
+                test_utils::encode_sqrt_price(100, 1),
+                3000,
+                1,
+                u128::MAX,
+            )?;
+
+            contract.enable_pool_579_D_A658(token0, true)?;
+
+            contract.mint_position_B_C5_B086_D(token0, -887272, 887272)?;
+
+            assert_eq!(
+                contract
+                    .incr_position_permit_2_5468326_E(
+                        token0,
+                        U256::from(0),
+                        U256::zero(),
+                        U256::zero(),
+                        U256::from(1),
+                        U256::MAX,
+                        U256::from(100_000),
+                        vec![0; 65],
+                        U256::from(2),
+                        U256::MAX,
+                        U256::from(100_000),
+                        vec![0; 65],
+                    )
+                    .unwrap_err(),
+                Vec::<u8>::from(Error::Erc20RevertNoData)
+            );
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+// creates and enables pools for each token given, with full range liquidity
+#[test]
+fn multiple_fee_tier_pools() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+    let token1 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e1");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()),
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            // token1 has a pool stored at its address, created before fee tiers
+            setup_swap_path_pools(contract, &[token1])?;
+            contract.enable_fee_tier_120_D0_D_D_C(500, 10)?;
+            contract.enable_fee_tier_120_D0_D_D_C(3000, 60)?;
+
+            let price = test_utils::encode_sqrt_price(1, 1);
+            let pool_500 = contract.create_pool_111360_A2(token0, price, 500)?;
+            let pool_3000 = contract.create_pool_111360_A2(token0, price, 3000)?;
+            assert_ne!(pool_500, pool_3000);
+            assert_eq!(pool_500, pool::pool_id(token0, FUSDC_ADDR, 500));
+
+            assert_eq!(contract.get_pool_12523831(token0, 500)?, pool_500);
+            assert_eq!(contract.get_pool_12523831(token0, 3000)?, pool_3000);
+            assert_eq!(contract.get_pool_12523831(token0, 100)?, Address::ZERO);
+            assert_eq!(contract.get_pool_12523831(token1, 3000)?, token1);
+            assert_eq!(contract.pool_token_10162_F39(pool_500)?, token0);
+            assert_eq!(contract.pool_token_10162_F39(token1)?, token1);
+
+            // the existing pool for token1 takes up its fee tier
+            assert_eq!(
+                contract.create_pool_111360_A2(token1, price, 3000),
+                Err(Error::PoolAlreadyInitialised.into())
+            );
+            assert_eq!(
+                contract.create_pool_D650_E2_D0(token0, price, 500, 10, u128::MAX),
+                Err(Error::PoolAlreadyInitialised.into())
+            );
+            let pool_1_500 = contract.create_pool_111360_A2(token1, price, 500)?;
+
+            // pools need a token besides the fluid token
+            for token in [FUSDC_ADDR, Address::ZERO] {
+                assert_eq!(
+                    contract.create_pool_111360_A2(token, price, 500),
+                    Err(Error::InvalidPoolToken.into())
+                );
+            }
+
+            for (pool, lower, upper) in [
+                (pool_500, -600, 600),
+                (pool_3000, -600, 600),
+                (pool_1_500, -600, 600),
+            ] {
+                let id = contract.mint_position_B_C5_B086_D(pool, lower, upper)?;
+                contract.update_position_C_7_F_1_F_740(pool, id, 1_000_000_000)?;
+            }
+
+            // swapping in one pool leaves the other pool for the token alone
+            contract.swap_904369_B_E(pool_500, true, I256::unchecked_from(1000), U256::MAX)?;
+            assert_ne!(contract.sqrt_price_x967_B8_F5_F_C5(pool_500)?, price);
+            assert_eq!(contract.sqrt_price_x967_B8_F5_F_C5(pool_3000)?, price);
+
+            // two step swaps take pool ids, and can mix pools with and without fee tiers
+            let (_, out) = contract.swap_2_exact_in_41203_F1_D(
+                pool_3000,
+                token1,
+                U256::from(1000),
+                U256::ZERO,
+            )?;
+            assert!(out > U256::ZERO);
+            let (_, out) = contract.swap_path_exact_in_1033_D01_B(
+                vec![pool_3000, FUSDC_ADDR, pool_1_500],
+                U256::from(1000),
+                U256::ZERO,
+            )?;
+            assert!(out > U256::ZERO);
+            assert_ne!(contract.sqrt_price_x967_B8_F5_F_C5(pool_1_500)?, price);
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn pair_pools_use_their_tokens() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+    let token1 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e1");
+    let token2 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e2");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()),
+        None,
+        Some(hashmap! {
+            token0 => U256::from(1_000_000_000_000_000_u64),
+            token1 => U256::from(1_000_000_000_000_000_u64),
+            FUSDC_ADDR => U256::from(1_000_000_000_000_000_u64),
+        }),
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token2])?;
+            contract.enable_fee_tier_120_D0_D_D_C(3000, 60)?;
+            let price = test_utils::encode_sqrt_price(1, 1);
+
+            assert_eq!(
+                contract.create_pair_pool_111_D0_A09(token0, token0, price, 3000),
+                Err(Error::InvalidTokenPair.into())
+            );
+            assert_eq!(
+                contract.create_pair_pool_111_D0_A09(token0, FUSDC_ADDR, price, 3000),
+                Err(Error::InvalidTokenPair.into())
+            );
+
+            // the tokens are sorted, whichever order they're given in
+            let pool = contract.create_pair_pool_111_D0_A09(token1, token0, price, 3000)?;
+            assert_eq!(pool, pool::pool_id(token0, token1, 3000));
+            assert_eq!(contract.pool_tokens_10591443(pool)?, (token0, token1));
+            assert_eq!(contract.pool_tokens_10591443(token2)?, (token2, FUSDC_ADDR));
+            assert_eq!(
+                contract.create_pair_pool_111_D0_A09(token0, token1, price, 3000),
+                Err(Error::PoolAlreadyInitialised.into())
+            );
+
+            assert_eq!(
+                contract.get_pair_pool_11957_B4_F(token0, token1, 3000)?,
+                pool
+            );
+            assert_eq!(
+                contract.get_pair_pool_11957_B4_F(token1, token0, 3000)?,
+                pool
+            );
+            assert_eq!(
+                contract.get_pair_pool_11957_B4_F(token0, token1, 500)?,
+                Address::ZERO
+            );
+            assert_eq!(
+                contract.get_pair_pool_11957_B4_F(FUSDC_ADDR, token2, 3000)?,
+                token2
+            );
+
+            // liquidity and swaps use the pool's tokens, rather than the fluid token
+            let bal = |token| test_shims::storage::CALLER_BALS.with(|bals| bals.borrow()[&token]);
+            let (bal_0, bal_1, bal_fusdc) = (bal(token0), bal(token1), bal(FUSDC_ADDR));
+
+            let id = contract.mint_position_B_C5_B086_D(pool, -600, 600)?;
+            let (amount_0, amount_1) =
+                contract.update_position_C_7_F_1_F_740(pool, id, 1_000_000)?;
+            let (swap_0, swap_1) =
+                contract.swap_904369_B_E(pool, true, I256::unchecked_from(1000), U256::MAX)?;
+
+            assert_eq!(bal(token0), bal_0 - amount_0.into_raw() - swap_0.into_raw());
+            assert_eq!(bal(token1), bal_1 - amount_1.into_raw());
+            assert_eq!(bal(FUSDC_ADDR), bal_fusdc);
+            assert!(swap_1 < I256::zero());
+
+            // pair pools can't be hops in multi step swaps
+            assert_eq!(
+                contract.swap_2_exact_in_41203_F1_D(pool, token2, U256::from(1000), U256::ZERO),
+                Err(Error::InvalidSwapPath.into())
+            );
+            assert_eq!(
+                contract.swap_path_exact_in_1033_D01_B(
+                    vec![pool, FUSDC_ADDR, token2],
+                    U256::from(1000),
+                    U256::ZERO
+                ),
+                Err(Error::InvalidSwapPath.into())
+            );
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn flash_loans_repay_lps() {
+    use std::{cell::RefCell, rc::Rc};
+
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+    let recipient = address!("737B7865f84bDc86B5c8ca718a5B7a6d905776F6");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()),
+        None,
+        None,
+        Some(hashmap! {
+            token0 => U256::from(1_000_000_000),
+            FUSDC_ADDR => U256::from(1_000_000_000),
+        }),
+        |contract| -> Result<(), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token0])?;
+            contract.set_fee_protocol_1004_E_D95(token0, 0, 4)?;
+
+            let (amount_0, amount_1) = (U256::from(1_000_000), U256::from(2_000_000));
+
+            // the mock borrower repays the loan, with a shortfall of token0
+            let shortfall = Rc::new(RefCell::new(U256::ZERO));
+            let called_with = Rc::new(RefCell::new(None));
+            test_shims::set_flash_borrower({
+                let (shortfall, called_with) = (shortfall.clone(), called_with.clone());
+                move |fee_0, fee_1, data| {
+                    *called_with.borrow_mut() = Some((fee_0, fee_1, data.to_vec()));
+                    test_shims::give_amm_bal(token0, amount_0 + fee_0 - *shortfall.borrow());
+                    test_shims::give_amm_bal(FUSDC_ADDR, amount_1 + fee_1);
+                    Ok(())
+                }
+            });
+
+            *shortfall.borrow_mut() = U256::from(1);
+            assert_eq!(
+                contract.flash_1022_F44_D(token0, recipient, amount_0, amount_1, vec![1, 2]),
+                Err(Error::FlashLoanNotRepaid.into())
+            );
+
+            *shortfall.borrow_mut() = U256::ZERO;
+            let fee_growth_0 = contract.fee_growth_global_0_38_B5665_B(token0)?;
+            let fee_growth_1 = contract.fee_growth_global_1_A_33_A_5_A_1_B(token0)?;
+            contract.flash_1022_F44_D(token0, recipient, amount_0, amount_1, vec![1, 2])?;
+
+            // fees are rounded up, and passed to the borrower along with the data
+            let (fee_0, fee_1) = (U256::from(3000), U256::from(6000));
+            assert_eq!(*called_with.borrow(), Some((fee_0, fee_1, vec![1, 2])));
+
+            // token1 fees are split with the protocol, the rest goes to in range liquidity
+            let liquidity = U256::from(contract.pools.getter(token0).liquidity.get());
+            assert_eq!(
+                contract.fee_growth_global_0_38_B5665_B(token0)? - fee_growth_0,
+                full_math::mul_div(fee_0, full_math::Q128, liquidity)?
+            );
+            assert_eq!(
+                contract.fee_growth_global_1_A_33_A_5_A_1_B(token0)? - fee_growth_1,
+                full_math::mul_div(fee_1 - fee_1 / U256::from(4), full_math::Q128, liquidity)?
+            );
+            assert_eq!(
+                contract.collect_protocol_7540_F_A_9_F(token0, u128::MAX, u128::MAX, recipient)?,
+                (0, 1500)
+            );
+
+            // reverts from the borrower are passed on
+            test_shims::set_flash_borrower(|_, _, _| Err(vec![0xde, 0xad]));
+            assert_eq!(
+                contract.flash_1022_F44_D(token0, recipient, amount_0, amount_1, vec![]),
+                Err(vec![0xde, 0xad])
+            );
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn flash_loans_need_liquidity() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()),
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            contract.ctor(msg::sender(), Address::ZERO, Address::ZERO)?;
+            contract.create_pool_D650_E2_D0(
+                token0,
+                test_utils::encode_sqrt_price(1, 1),
+                3000,
+                10,
+                u128::MAX,
+            )?;
+            assert_eq!(
+                contract.flash_1022_F44_D(token0, msg::sender(), U256::from(1), U256::ZERO, vec![]),
+                Err(Error::PoolDisabled.into())
+            );
+
+            contract.enable_pool_579_D_A658(token0, true)?;
+            assert_eq!(
+                contract.flash_1022_F44_D(token0, msg::sender(), U256::from(1), U256::ZERO, vec![]),
+                Err(Error::LiquidityIsZero.into())
+            );
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn flash_loans_capped_at_reserves() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+    let token1 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e1");
+    let recipient = address!("737B7865f84bDc86B5c8ca718a5B7a6d905776F6");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()),
+        None,
+        None,
+        Some(hashmap! {
+            token0 => U256::from(1_000_000_000_000_000_u64),
+            token1 => U256::from(1_000_000_000_000_000_u64),
+            FUSDC_ADDR => U256::from(1_000_000_000_000_000_u64),
+        }),
+        |contract| -> Result<(), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token0, token1])?;
+
+            // the reserves follow the tokens paid in and out by swaps
+            let (reserve_0, reserve_1) = contract.pools.getter(token0).get_reserves();
+            assert!(reserve_0 > U256::ZERO && reserve_1 > U256::ZERO);
+            let (amount_0, amount_1) = contract.swap_904369_B_E(
+                token0,
+                true,
+                I256::unchecked_from(1_000_000),
+                U256::MAX,
+            )?;
+            let (reserve_0, reserve_1) = (
+                reserve_0 + amount_0.into_raw(),
+                reserve_1 - amount_1.unsigned_abs(),
+            );
+            assert_eq!(
+                contract.pools.getter(token0).get_reserves(),
+                (reserve_0, reserve_1)
+            );
+
+            test_shims::set_flash_borrower(move |fee_0, fee_1, _| {
+                test_shims::give_amm_bal(token0, reserve_0 + fee_0);
+                test_shims::give_amm_bal(FUSDC_ADDR, reserve_1 + fee_1);
+                Ok(())
+            });
+
+            // the fluid token held for the other pool can't be borrowed
+            assert_eq!(
+                contract.flash_1022_F44_D(
+                    token0,
+                    recipient,
+                    U256::ZERO,
+                    reserve_1 + U256::from(1),
+                    vec![]
+                ),
+                Err(Error::FlashLoanTooLarge.into())
+            );
+            assert_eq!(
+                contract.flash_1022_F44_D(
+                    token0,
+                    recipient,
+                    reserve_0 + U256::from(1),
+                    U256::ZERO,
+                    vec![]
+                ),
+                Err(Error::FlashLoanTooLarge.into())
+            );
+
+            // the whole of the pool's reserves can be borrowed
+            contract.flash_1022_F44_D(token0, recipient, reserve_0, reserve_1, vec![])?;
+            let (fee_0, fee_1) = contract
+                .pools
+                .getter(token0)
+                .flash_fees(reserve_0, reserve_1)?;
+            assert_eq!(
+                contract.pools.getter(token0).get_reserves(),
+                (reserve_0 + fee_0, reserve_1 + fee_1)
+            );
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn flash_loans_lock_seawater() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+    let recipient = address!("737B7865f84bDc86B5c8ca718a5B7a6d905776F6");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()),
+        None,
+        None,
+        Some(hashmap! {
+            token0 => U256::from(1_000_000_000),
+            FUSDC_ADDR => U256::from(1_000_000_000),
+        }),
+        |contract| -> Result<(), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token0])?;
+            let id = contract.next_position_id.get() - U256::from(1);
+
+            // the lock is released when the borrower reverts
+            test_shims::set_flash_borrower(|_, _, _| Err(vec![0xde, 0xad]));
+            assert_eq!(
+                contract.flash_1022_F44_D(token0, recipient, U256::from(1), U256::ZERO, vec![]),
+                Err(vec![0xde, 0xad])
+            );
+            assert!(!contract.flash_locked.get());
+
+            // swaps, positions, and flash loans are locked while a borrower is called back
+            contract.flash_locked.set(true);
+            let locked = Err(Error::FlashLoanLocked.into());
+            assert_eq!(
+                contract
+                    .swap_904369_B_E(token0, true, I256::unchecked_from(1000), U256::MAX)
+                    .map(|_| ()),
+                locked
+            );
+            assert_eq!(
+                contract
+                    .update_position_C_7_F_1_F_740(token0, id, 1000)
+                    .map(|_| ()),
+                locked
+            );
+            assert_eq!(
+                contract
+                    .update_position_C_7_F_1_F_740(token0, id, -1000)
+                    .map(|_| ()),
+                locked
+            );
+            assert_eq!(
+                contract
+                    .collect_7_F21947_C(vec![token0], vec![id])
+                    .map(|_| ()),
+                locked
+            );
+            assert_eq!(
+                contract.flash_1022_F44_D(token0, recipient, U256::from(1), U256::ZERO, vec![]),
+                locked
+            );
+
+            contract.flash_locked.set(false);
+            contract.swap_904369_B_E(token0, true, I256::unchecked_from(1000), U256::MAX)?;
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn oracle_observations() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()),
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            test_shims::set_timestamp(1000);
+            setup_swap_path_pools(contract, &[token0])?;
+            let liquidity = U256::from(contract.pools.getter(token0).liquidity.get());
+
+            assert_eq!(contract.observation_state_105820_A6(token0)?, (0, 1, 1));
+            assert_eq!(
+                contract.increase_observation_cardinality_next_100_D2_A_B9(Address::ZERO, 3),
+                Err(Error::OracleNotInitialised.into())
+            );
+            contract.increase_observation_cardinality_next_100_D2_A_B9(token0, 3)?;
+            assert_eq!(contract.observation_state_105820_A6(token0)?, (0, 1, 3));
+
+            // each swap that moves the tick records the tick and liquidity before it
+            let swap = |contract: &mut Pools| -> Result<i64, Vec<u8>> {
+                contract.swap_904369_B_E(
+                    token0,
+                    true,
+                    I256::unchecked_from(10_000_000_000_i64),
+                    U256::MAX,
+                )?;
+                Ok(contract.cur_tick181_C6_F_D9(token0)? as i64)
+            };
+
+            test_shims::advance_time(10);
+            let tick_1 = swap(contract)?;
+            test_shims::advance_time(20);
+            let tick_2 = swap(contract)?;
+            assert!(tick_2 < tick_1 && tick_1 < 0);
+            assert_eq!(contract.observation_state_105820_A6(token0)?, (2, 3, 3));
+
+            test_shims::advance_time(10);
+            let (tick_cumulatives, seconds_per_liquidity) =
+                contract.observe_106_B4_A_B6(token0, vec![0, 10, 20, 40])?;
+            assert_eq!(
+                tick_cumulatives,
+                vec![
+                    tick_1 * 20 + tick_2 * 10,
+                    tick_1 * 20,
+                    // interpolated between the observations at 1010 and 1030
+                    tick_1 * 10,
+                    0,
+                ]
+            );
+            assert_eq!(
+                seconds_per_liquidity,
+                vec![
+                    (U256::from(10) << 128) / liquidity
+                        + (U256::from(20) << 128) / liquidity
+                        + (U256::from(10) << 128) / liquidity,
+                    (U256::from(10) << 128) / liquidity + (U256::from(20) << 128) / liquidity,
+                    (U256::from(10) << 128) / liquidity + (U256::from(10) << 128) / liquidity,
+                    U256::ZERO,
+                ]
+            );
+
+            // the time weighted average tick since the last swap is the current tick
+            assert_eq!((tick_cumulatives[0] - tick_cumulatives[1]) / 10, tick_2);
+
+            assert_eq!(
+                contract.observe_106_B4_A_B6(token0, vec![41]),
+                Err(Error::ObservationTooOld.into())
+            );
+
+            // adding in range liquidity records an observation too, wrapping around the buffer
+            let id = contract.mint_position_B_C5_B086_D(token0, -1000, 1000)?;
+            contract.update_position_C_7_F_1_F_740(token0, id, 1_000_000)?;
+            assert_eq!(contract.observation_state_105820_A6(token0)?, (0, 3, 3));
+            assert_eq!(
+                contract.observe_106_B4_A_B6(token0, vec![40]),
+                Err(Error::ObservationTooOld.into())
+            );
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn range_orders_fill_when_crossed() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()),
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token0])?;
+            let liquidity = contract.pools.getter(token0).liquidity.get();
+
+            // orders must be out of range, on the side of the price they're selling from
+            assert_eq!(
+                contract.place_range_order_103_B25_D5(token0, -10, true, 1_000_000),
+                Err(Error::RangeOrderInRange.into())
+            );
+            assert_eq!(
+                contract.place_range_order_103_B25_D5(token0, 0, false, 1_000_000),
+                Err(Error::RangeOrderInRange.into())
+            );
+            assert_eq!(
+                contract.place_range_order_103_B25_D5(token0, 15, true, 1_000_000),
+                Err(Error::InvalidTickSpacing.into())
+            );
+
+            // orders in the same range and direction share an epoch
+            let (epoch, amount_a) =
+                contract.place_range_order_103_B25_D5(token0, 10, true, 1_000_000_000)?;
+            let (epoch_b, amount_b) =
+                contract.place_range_order_103_B25_D5(token0, 10, true, 3_000_000_000)?;
+            assert_eq!(epoch, epoch_b);
+            assert!(amount_a > U256::ZERO && amount_b > amount_a);
+            assert_eq!(
+                contract.claim_range_order_11_F9_C034(token0, epoch),
+                Err(Error::RangeOrderNotFilled.into())
+            );
+
+            // swapping up through the range fills the orders, and removes their liquidity
+            contract.swap_904369_B_E(
+                token0,
+                false,
+                I256::unchecked_from(100_000_000_000_i64),
+                tick_math::get_sqrt_ratio_at_tick(30)?,
+            )?;
+            assert_eq!(contract.cur_tick181_C6_F_D9(token0)?, 30);
+            let (_, _, _, filled, _) = contract
+                .pools
+                .getter(token0)
+                .range_orders
+                .get(epoch, msg::sender());
+            assert!(filled);
+            assert_eq!(
+                contract.ticks_in_range_132_A_C_C_E_E(token0, 10, 20, 10)?.0,
+                vec![]
+            );
+            assert_eq!(
+                contract.cancel_range_order_11_E_E2063(token0, epoch),
+                Err(Error::RangeOrderFilled.into())
+            );
+
+            // swapping back down doesn't add the liquidity back
+            contract.swap_904369_B_E(
+                token0,
+                true,
+                I256::unchecked_from(100_000_000_000_i64),
+                tick_math::get_sqrt_ratio_at_tick(-5)?,
+            )?;
+            assert_eq!(contract.pools.getter(token0).liquidity.get(), liquidity);
+
+            // the orders were filled with token1, at a better price than they sold for, and
+            // earned fees in it
+            let (amount_0, amount_1) = contract.claim_range_order_11_F9_C034(token0, epoch)?;
+            assert_eq!(amount_0, U256::ZERO);
+            assert!(amount_1 > amount_a + amount_b);
+            assert_eq!(
+                contract.claim_range_order_11_F9_C034(token0, epoch),
+                Err(Error::RangeOrderEmpty.into())
+            );
+
+            // cancelling an unfilled order returns its liquidity, and closes its epoch once empty
+            let (epoch_c, amount_c) =
+                contract.place_range_order_103_B25_D5(token0, -30, false, 1_000_000_000)?;
+            assert_ne!(epoch_c, epoch);
+            let (amount_0, amount_1) = contract.cancel_range_order_11_E_E2063(token0, epoch_c)?;
+            assert_eq!(amount_0, U256::ZERO);
+            assert!(amount_c - amount_1 <= U256::from(1));
+            assert_eq!(
+                contract.cancel_range_order_11_E_E2063(token0, epoch_c),
+                Err(Error::RangeOrderEmpty.into())
+            );
+            assert_eq!(
+                contract
+                    .ticks_in_range_132_A_C_C_E_E(token0, -30, -20, 10)?
+                    .0,
+                vec![]
+            );
+            let (epoch_d, _) =
+                contract.place_range_order_103_B25_D5(token0, -30, false, 1_000_000_000)?;
+            assert_ne!(epoch_d, epoch_c);
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn seconds_inside_tracked_across_crosses() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()),
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            test_shims::set_timestamp(1000);
+            setup_swap_path_pools(contract, &[token0])?;
+
+            assert_eq!(
+                contract.seconds_per_liquidity_inside_11_B8_A254(token0, 10, 20),
+                Err(Error::TickNotInitialised.into())
+            );
+
+            // a position above the price starts out of range
+            let id = contract.mint_position_B_C5_B086_D(token0, 10, 20)?;
+            contract.update_position_C_7_F_1_F_740(token0, id, 1_000_000_000)?;
+            let (seconds_per_liquidity_start, seconds_start) =
+                contract.seconds_per_liquidity_inside_11_B8_A254(token0, 10, 20)?;
+
+            let swap_to = |contract: &mut Pools, tick: i32| -> Result<(), Vec<u8>> {
+                let zero_for_one = tick < contract.cur_tick181_C6_F_D9(token0)?;
+                contract.swap_904369_B_E(
+                    token0,
+                    zero_for_one,
+                    I256::unchecked_from(100_000_000_000_i64),
+                    tick_math::get_sqrt_ratio_at_tick(tick)?,
+                )?;
+                assert_eq!(contract.cur_tick181_C6_F_D9(token0)?, tick);
+                Ok(())
+            };
+
+            // the price spends 20 seconds inside the range, between 30 seconds outside it
+            test_shims::advance_time(10);
+            swap_to(contract, 15)?;
+            let liquidity = U256::from(contract.pools.getter(token0).liquidity.get());
+            test_shims::advance_time(20);
+            swap_to(contract, 5)?;
+            test_shims::advance_time(30);
+
+            let (seconds_per_liquidity, seconds) =
+                contract.seconds_per_liquidity_inside_11_B8_A254(token0, 10, 20)?;
+            assert_eq!(seconds.wrapping_sub(seconds_start), 20);
+            assert_eq!(
+                seconds_per_liquidity.wrapping_sub(seconds_per_liquidity_start),
+                (U256::from(20) << 128) / liquidity
+            );
+
+            // time spent in range counts straight away
+            swap_to(contract, 15)?;
+            test_shims::advance_time(5);
+            let (_, seconds) = contract.seconds_per_liquidity_inside_11_B8_A254(token0, 10, 20)?;
+            assert_eq!(seconds.wrapping_sub(seconds_start), 25);
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+fn setup_swap_path_pools(contract: &mut Pools, tokens: &[Address]) -> Result<(), Vec<u8>> {
+    contract.ctor(msg::sender(), Address::ZERO, Address::ZERO)?;
+    for &token in tokens {
+        contract.create_pool_D650_E2_D0(
+            token,
+            test_utils::encode_sqrt_price(1, 1),
+            3000, // fee
+            10,   // tick spacing
+            u128::MAX,
+        )?;
+        contract.enable_pool_579_D_A658(token, true)?;
+        let id = contract.mint_position_B_C5_B086_D(
+            token,
+            tick_math::get_min_tick(10),
+            tick_math::get_max_tick(10),
+        )?;
+        contract.update_position_C_7_F_1_F_740(token, id, 1_000_000_000_000)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn swap_path_exact_in_matches_swap_2() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+    let token1 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e1");
+    let sender = address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array();
+
+    let swap_2 = test_utils::with_storage::<_, Pools, _>(
+        Some(sender),
+        None,
+        None,
+        None,
+        |contract| -> Result<(U256, U256), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token0, token1])?;
+            contract.swap_2_exact_in_41203_F1_D(token0, token1, U256::from(1_000_000), U256::ZERO)
+        },
+    )
+    .unwrap();
+
+    let swap_path = test_utils::with_storage::<_, Pools, _>(
+        Some(sender),
+        None,
+        None,
+        None,
+        |contract| -> Result<(U256, U256), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token0, token1])?;
+            contract.swap_path_exact_in_1033_D01_B(
+                vec![token0, FUSDC_ADDR, token1],
+                U256::from(1_000_000),
+                U256::ZERO,
+            )
+        },
+    )
+    .unwrap();
+
+    assert_eq!(swap_2, swap_path);
+    assert_eq!(swap_path.0, U256::from(1_000_000));
+}
+
+#[test]
+fn swap_path_single_hop_matches_swap() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()),
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token0])?;
+
+            let (amount_in, amount_out) = contract.swap_path_exact_in_1033_D01_B(
+                vec![FUSDC_ADDR, token0],
+                U256::from(1_000_000),
+                U256::ZERO,
+            )?;
+
+            // swap back the other way for exactly the amount we got out
+            let (amount_0, amount_1) = contract.swap_904369_B_E(
+                token0,
+                true,
+                I256::try_from(amount_out).unwrap(),
+                U256::MAX,
+            )?;
+
+            assert_eq!(amount_in, U256::from(1_000_000));
+            assert_eq!(amount_0, I256::try_from(amount_out).unwrap());
+            // fees were taken twice
+            assert!(amount_1.abs_neg()? < amount_in);
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn swap_path_exact_out() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+    let token1 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e1");
+    let token2 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e2");
+    let sender = address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array();
+
+    let path = vec![token0, FUSDC_ADDR, token1, FUSDC_ADDR, token2];
+
+    let (amount_in, amount_out) = test_utils::with_storage::<_, Pools, _>(
+        Some(sender),
+        None,
+        None,
+        None,
+        |contract| -> Result<(U256, U256), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token0, token1, token2])?;
+            contract.swap_path_exact_out_100_F69_C2(path.clone(), U256::from(1_000_000), U256::MAX)
+        },
+    )
+    .unwrap();
+
+    assert_eq!(amount_out, U256::from(1_000_000));
+    // four hops worth of fees
+    assert!(amount_in > amount_out);
+
+    // swapping exactly the amount in on fresh pools should give at least the amount out
+    let (_, amount_out_exact_in) = test_utils::with_storage::<_, Pools, _>(
+        Some(sender),
+        None,
+        None,
+        None,
+        |contract| -> Result<(U256, U256), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token0, token1, token2])?;
+            contract.swap_path_exact_in_1033_D01_B(path.clone(), amount_in, U256::ZERO)
+        },
+    )
+    .unwrap();
+
+    assert!(amount_out_exact_in >= amount_out);
+
+    // a max in lower than the amount needed reverts
+    test_utils::with_storage::<_, Pools, _>(
+        Some(sender),
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token0, token1, token2])?;
+            assert_eq!(
+                contract
+                    .swap_path_exact_out_100_F69_C2(
+                        path.clone(),
+                        U256::from(1_000_000),
+                        amount_in - U256::one()
+                    )
+                    .unwrap_err(),
+                Vec::<u8>::from(Error::MaxInExceeded)
+            );
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn swap_path_reverts() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+    let token1 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e1");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()),
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token0, token1])?;
+
+            for path in [
+                vec![],
+                vec![token0],
+                vec![token0, token1],
+                vec![FUSDC_ADDR, FUSDC_ADDR],
+                vec![token0, FUSDC_ADDR, FUSDC_ADDR, token1],
+            ] {
+                assert_eq!(
+                    contract
+                        .swap_path_exact_in_1033_D01_B(path, U256::from(1_000), U256::ZERO)
+                        .unwrap_err(),
+                    Vec::<u8>::from(Error::InvalidSwapPath)
+                );
+            }
+
+            assert_eq!(
+                contract
+                    .swap_path_exact_in_1033_D01_B(
+                        vec![token0, FUSDC_ADDR, token1],
+                        U256::from(1_000),
+                        U256::from(1_000),
+                    )
+                    .unwrap_err(),
+                Vec::<u8>::from(Error::MinOutNotReached)
+            );
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn swap_2_exact_out() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+    let token1 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e1");
+    let sender = address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array();
+
+    let (amount_in, amount_out) = test_utils::with_storage::<_, Pools, _>(
+        Some(sender),
+        None,
+        None,
+        None,
+        |contract| -> Result<(U256, U256), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token0, token1])?;
+            contract.swap_2_exact_out_1758_D1_A7(token0, token1, U256::from(1_000_000), U256::MAX)
+        },
+    )
+    .unwrap();
+
+    assert_eq!(amount_out, U256::from(1_000_000));
+    assert!(amount_in > amount_out);
+
+    // the same swap along a path should be identical
+    let swap_path = test_utils::with_storage::<_, Pools, _>(
+        Some(sender),
+        None,
+        None,
+        None,
+        |contract| -> Result<(U256, U256), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token0, token1])?;
+            contract.swap_path_exact_out_100_F69_C2(
+                vec![token0, FUSDC_ADDR, token1],
+                U256::from(1_000_000),
+                U256::MAX,
+            )
+        },
+    )
+    .unwrap();
+
+    assert_eq!((amount_in, amount_out), swap_path);
+
+    // swapping exactly the amount in should give at least the amount out
+    let (_, amount_out_exact_in) = test_utils::with_storage::<_, Pools, _>(
+        Some(sender),
+        None,
+        None,
+        None,
+        |contract| -> Result<(U256, U256), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token0, token1])?;
+            contract.swap_2_exact_in_41203_F1_D(token0, token1, amount_in, U256::ZERO)
+        },
+    )
+    .unwrap();
+
+    assert!(amount_out_exact_in >= amount_out);
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(sender),
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token0, token1])?;
+            assert_eq!(
+                contract
+                    .swap_2_exact_out_1758_D1_A7(
+                        token0,
+                        token1,
+                        U256::from(1_000_000),
+                        amount_in - U256::one()
+                    )
+                    .unwrap_err(),
+                Vec::<u8>::from(Error::MaxInExceeded)
+            );
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn quote_structured_matches_swap() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+    let sender = address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array();
+
+    // a narrow position for the swap to cross out of
+    let setup = |contract: &mut Pools| -> Result<(), Vec<u8>> {
+        setup_swap_path_pools(contract, &[token0])?;
+        let id = contract.mint_position_B_C5_B086_D(token0, -100, 100)?;
+        contract.update_position_C_7_F_1_F_740(token0, id, 1_000_000_000)?;
+        Ok(())
+    };
+
+    let quote = test_utils::with_storage::<_, Pools, _>(
+        Some(sender),
+        None,
+        None,
+        None,
+        |contract| -> Result<QuoteResult, Vec<u8>> {
+            setup(contract)?;
+            let revert = contract
+                .quote_structured_2065_E121(
+                    token0,
+                    false,
+                    I256::unchecked_from(100_000_000_000_i64),
+                    U256::MAX,
+                )
+                .unwrap_err();
+            Ok(QuoteResult::decode(&revert).unwrap())
+        },
+    )
+    .unwrap();
+
+    let swapped = test_utils::with_storage::<_, Pools, _>(
+        Some(sender),
+        None,
+        None,
+        None,
+        |contract| -> Result<QuoteResult, Vec<u8>> {
+            setup(contract)?;
+            let (amount_0, amount_1) = contract.swap_904369_B_E(
+                token0,
+                false,
+                I256::unchecked_from(100_000_000_000_i64),
+                U256::MAX,
+            )?;
+            Ok(QuoteResult {
+                amount_0,
+                amount_1,
+                sqrt_price_x96: contract.sqrt_price_x967_B8_F5_F_C5(token0)?,
+                tick: contract.cur_tick181_C6_F_D9(token0)?,
+                initialised_ticks_crossed: 1,
+            })
+        },
+    )
+    .unwrap();
+
+    assert_eq!(quote, swapped);
+    assert!(quote.tick > 100);
+
+    // errors are returned as normal
+    test_utils::with_storage::<_, Pools, _>(
+        Some(sender),
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            setup(contract)?;
+            contract.enable_pool_579_D_A658(token0, false)?;
+            assert_eq!(
+                contract
+                    .quote_structured_2065_E121(token0, true, I256::unchecked_from(1000), U256::MAX)
+                    .unwrap_err(),
+                Vec::<u8>::from(Error::PoolDisabled)
+            );
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn ticks_in_range() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()),
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token0])?;
+
+            for (lower, upper, delta) in [
+                (-3000, -100, 1_000_000_i128),
+                (-100, 100, 2_000_000),
+                (50, 3000, 3_000_000),
+            ] {
+                let id = contract.mint_position_B_C5_B086_D(token0, lower, upper)?;
+                contract.update_position_C_7_F_1_F_740(token0, id, delta)?;
+            }
+
+            let min_tick = tick_math::get_min_tick(10);
+            let max_tick = tick_math::get_max_tick(10);
+
+            // the range is too sparse to read in one call
+            let (ticks, next) =
+                contract.ticks_in_range_132_A_C_C_E_E(token0, min_tick, max_tick, 100)?;
+            assert_eq!(ticks.len(), 1);
+            assert!(next < -3000);
+
+            let mut ticks = vec![];
+            let mut from = min_tick;
+            while from <= max_tick {
+                let (page, next) =
+                    contract.ticks_in_range_132_A_C_C_E_E(token0, from, max_tick, 100)?;
+                ticks.extend(page);
+                from = next;
+            }
+
+            let summary: Vec<(i32, u128, i128)> = ticks
+                .iter()
+                .map(|&(tick, gross, net, _, _)| (tick, gross, net))
+                .collect();
+
+            let full_range = 1_000_000_000_000;
+            assert_eq!(
+                summary,
+                vec![
+                    (min_tick, full_range, full_range as i128),
+                    (-3000, 1_000_000, 1_000_000),
+                    (-100, 3_000_000, -1_000_000 + 2_000_000),
+                    (50, 3_000_000, 3_000_000),
+                    (100, 2_000_000, -2_000_000),
+                    (3000, 3_000_000, -3_000_000),
+                    (max_tick, full_range, -(full_range as i128)),
+                ]
+            );
+
+            // ranges are inclusive
+            let (ticks, _) = contract.ticks_in_range_132_A_C_C_E_E(token0, -100, 100, 100)?;
+            let found: Vec<i32> = ticks.iter().map(|t| t.0).collect();
+            assert_eq!(found, vec![-100, 50, 100]);
+
+            // paging through should return every tick once
+            let mut paged = vec![];
+            let mut from = min_tick;
+            while from <= max_tick {
+                let (ticks, next) =
+                    contract.ticks_in_range_132_A_C_C_E_E(token0, from, max_tick, 2)?;
+                assert!(ticks.len() <= 2);
+                paged.extend(ticks.iter().map(|t| t.0));
+                from = next;
+            }
+            assert_eq!(paged, summary.iter().map(|t| t.0).collect::<Vec<_>>());
+
+            // crossing a negative tick updates its fee growth outside
+            contract.swap_904369_B_E(
+                token0,
+                true,
+                I256::unchecked_from(100_000_000_000_i64),
+                U256::MAX,
+            )?;
+            assert!(contract.cur_tick181_C6_F_D9(token0)? < -100);
+
+            let (ticks, _) = contract.ticks_in_range_132_A_C_C_E_E(token0, -100, -100, 1)?;
+            assert_eq!(ticks.len(), 1);
+            assert_ne!(ticks[0].3, U256::ZERO);
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn rebuild_tick_bitmap_restores_negative_ticks() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()),
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token0])?;
+
+            for (lower, upper, delta) in [(-3000, -100, 1_000_000_i128), (50, 3000, 3_000_000)] {
+                let id = contract.mint_position_B_C5_B086_D(token0, lower, upper)?;
+                contract.update_position_C_7_F_1_F_740(token0, id, delta)?;
+            }
+
+            let ticks_between = |contract: &Pools, from, to| -> Result<Vec<i32>, Vec<u8>> {
+                let (ticks, _) = contract.ticks_in_range_132_A_C_C_E_E(token0, from, to, 100)?;
+                Ok(ticks.iter().map(|t| t.0).collect())
+            };
+
+            // clear the words holding the negative ticks, as flipping left them before
+            for word_pos in [-2, -1] {
+                contract
+                    .pools
+                    .setter(token0)
+                    .tick_bitmap
+                    .bitmap
+                    .setter(word_pos)
+                    .set(U256::ZERO);
+            }
+            assert_eq!(ticks_between(contract, -3000, 3000)?, vec![50, 3000]);
+
+            contract.rebuild_tick_bitmap(token0, -3000, 3000)?;
+            assert_eq!(
+                ticks_between(contract, -3000, 3000)?,
+                vec![-3000, -100, 50, 3000]
+            );
+
+            // rebuilding again doesn't change anything
+            contract.rebuild_tick_bitmap(token0, -3000, 3000)?;
+            assert_eq!(
+                ticks_between(contract, -3000, 3000)?,
+                vec![-3000, -100, 50, 3000]
+            );
+
+            // swaps see the restored liquidity
+            contract.swap_904369_B_E(
+                token0,
+                true,
+                I256::unchecked_from(100_000_000_000_i64),
+                U256::MAX,
+            )?;
+            assert!(contract.cur_tick181_C6_F_D9(token0)? < -100);
+            let (ticks, _) = contract.ticks_in_range_132_A_C_C_E_E(token0, -100, -100, 1)?;
+            assert_ne!(ticks[0].3, U256::ZERO);
+
+            contract
+                .seawater_admin
+                .set(address!("737B7865f84bDc86B5c8ca718a5B7a6d905776F6"));
+            assert_eq!(
+                contract.rebuild_tick_bitmap(token0, -3000, 3000),
+                Err(Error::MissingRole {
+                    role: roles::MIGRATOR,
+                    account: msg::sender(),
+                }
+                .into())
+            );
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn positions_of_owner() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+    let other = address!("737B7865f84bDc86B5c8ca718a5B7a6d905776F6");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()),
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            let owner = msg::sender();
+            // make the sender the nft manager, so it can transfer positions
+            contract.ctor(owner, owner, Address::ZERO)?;
+            contract.create_pool_D650_E2_D0(
+                token0,
+                test_utils::encode_sqrt_price(1, 1),
+                3000, // fee
+                10,   // tick spacing
+                u128::MAX,
+            )?;
+            contract.enable_pool_579_D_A658(token0, true)?;
+
+            let ids = (0..4)
+                .map(|_| contract.mint_position_B_C5_B086_D(token0, -100, 100))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            assert_eq!(
+                contract.positions_of_owner_103_E_C_F2_F(owner, U256::ZERO, U256::MAX)?,
+                ids
+            );
+            for (index, &id) in ids.iter().enumerate() {
+                assert_eq!(
+                    contract.position_of_owner_by_index_1068_E3_C7(owner, U256::from(index))?,
+                    id
+                );
+            }
+            assert_eq!(
+                contract.position_of_owner_by_index_1068_E3_C7(owner, U256::from(4)),
+                Err(Error::PositionIndexOutOfBounds.into())
+            );
+
+            // pages are clamped to the owner's positions
+            assert_eq!(
+                contract.positions_of_owner_103_E_C_F2_F(owner, U256::from(1), U256::from(2))?,
+                ids[1..3]
+            );
+            assert_eq!(
+                contract.positions_of_owner_103_E_C_F2_F(owner, U256::from(3), U256::from(10))?,
+                ids[3..]
+            );
+            assert_eq!(
+                contract.positions_of_owner_103_E_C_F2_F(owner, U256::from(5), U256::from(10))?,
+                vec![]
+            );
+
+            // removing a position moves the last position into its index
+            contract.transfer_position_E_E_C7_A3_C_D(ids[1], owner, other)?;
+            assert_eq!(
+                contract.positions_of_owner_103_E_C_F2_F(owner, U256::ZERO, U256::MAX)?,
+                vec![ids[0], ids[3], ids[2]]
+            );
+            assert_eq!(
+                contract.positions_of_owner_103_E_C_F2_F(other, U256::ZERO, U256::MAX)?,
+                vec![ids[1]]
+            );
+
+            // removing the last position just pops it
+            contract.burn_position_AE401070(ids[2])?;
+            assert_eq!(
+                contract.positions_of_owner_103_E_C_F2_F(owner, U256::ZERO, U256::MAX)?,
+                vec![ids[0], ids[3]]
+            );
+
+            contract.burn_position_AE401070(ids[0])?;
+            contract.burn_position_AE401070(ids[3])?;
+            assert_eq!(contract.position_balance_4_F32_C7_D_B(owner)?, U256::ZERO);
+            assert_eq!(
+                contract.positions_of_owner_103_E_C_F2_F(owner, U256::ZERO, U256::MAX)?,
+                vec![]
+            );
+
+            // indexes are reused once positions are removed
+            let id = contract.mint_position_B_C5_B086_D(token0, -100, 100)?;
+            assert_eq!(
+                contract.position_of_owner_by_index_1068_E3_C7(owner, U256::ZERO)?,
+                id
+            );
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn positions_of_owner_backfilled() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+    let other = address!("737B7865f84bDc86B5c8ca718a5B7a6d905776F6");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()),
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            let owner = msg::sender();
+            contract.ctor(owner, owner, Address::ZERO)?;
+            contract.create_pool_D650_E2_D0(
+                token0,
+                test_utils::encode_sqrt_price(1, 1),
+                3000, // fee
+                10,   // tick spacing
+                u128::MAX,
+            )?;
+            contract.enable_pool_579_D_A658(token0, true)?;
+
+            let ids = (0..4)
+                .map(|_| contract.mint_position_B_C5_B086_D(token0, -100, 100))
+                .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(ids[0], U256::ZERO);
+
+            // the first three positions were granted before positions were enumerated, and the last
+            // one after
+            for (index, &id) in ids.iter().enumerate() {
+                contract
+                    .owned_position_ids
+                    .setter(owner)
+                    .setter(U256::from(index))
+                    .set(U256::ZERO);
+                contract.owned_position_index.setter(id).set(U256::ZERO);
+            }
+            contract.enumerated_positions.setter(owner).set(U256::ZERO);
+            contract
+                .owned_position_ids
+                .setter(owner)
+                .setter(U256::ZERO)
+                .set(ids[3]);
+            contract
+                .owned_position_index
+                .setter(ids[3])
+                .set(U256::from(1));
+            contract
+                .enumerated_positions
+                .setter(owner)
+                .set(U256::from(1));
+
+            // only enumerated positions are listed
+            assert_eq!(
+                contract.position_balance_4_F32_C7_D_B(owner)?,
+                U256::from(4)
+            );
+            assert_eq!(
+                contract.positions_of_owner_103_E_C_F2_F(owner, U256::ZERO, U256::MAX)?,
+                vec![ids[3]]
+            );
+            assert_eq!(
+                contract.position_of_owner_by_index_1068_E3_C7(owner, U256::from(1)),
+                Err(Error::PositionIndexOutOfBounds.into())
+            );
+
+            // removing unenumerated positions, including position 0, leaves the enumeration
+            // alone
+            contract.burn_position_AE401070(ids[0])?;
+            contract.transfer_position_E_E_C7_A3_C_D(ids[1], owner, other)?;
+            assert_eq!(
+                contract.positions_of_owner_103_E_C_F2_F(owner, U256::ZERO, U256::MAX)?,
+                vec![ids[3]]
+            );
+            assert_eq!(
+                contract.positions_of_owner_103_E_C_F2_F(other, U256::ZERO, U256::MAX)?,
+                vec![ids[1]]
+            );
+
+            assert_eq!(
+                contract.backfill_position_enumeration(owner, vec![ids[2]]),
+                Err(Error::PositionIndexOutOfBounds.into())
+            );
+            assert_eq!(
+                contract.backfill_position_enumeration(owner, vec![ids[1], ids[2]]),
+                Err(Error::PositionOwnerOnly.into())
+            );
+            assert_eq!(
+                contract.backfill_position_enumeration(owner, vec![ids[2], ids[2]]),
+                Err(Error::PositionIndexOutOfBounds.into())
+            );
+
+            contract.backfill_position_enumeration(owner, vec![ids[2], ids[3]])?;
+            assert_eq!(
+                contract.positions_of_owner_103_E_C_F2_F(owner, U256::ZERO, U256::MAX)?,
+                vec![ids[2], ids[3]]
+            );
+
+            // backfilled positions are removed like any other
+            contract.burn_position_AE401070(ids[2])?;
+            assert_eq!(
+                contract.positions_of_owner_103_E_C_F2_F(owner, U256::ZERO, U256::MAX)?,
+                vec![ids[3]]
+            );
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn position_info() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()),
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token0])?;
+
+            let owner = msg::sender();
+            let liquidity = 2_000_000_000;
+            let id = contract.mint_position_B_C5_B086_D(token0, -600, 600)?;
+            contract.update_position_C_7_F_1_F_740(token0, id, liquidity)?;
+
+            // earn some fees in both tokens
+            contract.swap_904369_B_E(
+                token0,
+                true,
+                I256::unchecked_from(10_000_000_i64),
+                U256::MAX,
+            )?;
+            contract.swap_904369_B_E(
+                token0,
+                false,
+                I256::unchecked_from(4_000_000_i64),
+                U256::MAX,
+            )?;
+
+            // fees earned since the last update aren't checkpointed yet
+            assert_eq!(contract.fees_owed_22_F28_D_B_D(token0, id)?, (0, 0));
+
+            let dump = StorageDump::capture();
+            let (position_owner, lower, upper, position_liquidity, _, _, fees_0, fees_1) =
+                contract.position_info_110_D9723(token0, id)?;
+            assert_eq!(StorageDump::capture(), dump);
+
+            assert_eq!(position_owner, owner);
+            assert_eq!((lower, upper), (-600, 600));
+            assert_eq!(position_liquidity, liquidity as u128);
+            assert!(fees_0 > 0);
+            assert!(fees_1 > 0);
+
+            // checkpointing the fees gives the same amounts
+            contract.update_position_C_7_F_1_F_740(token0, id, 0)?;
+            assert_eq!(
+                contract.fees_owed_22_F28_D_B_D(token0, id)?,
+                (fees_0, fees_1)
+            );
+            let info = contract.position_info_110_D9723(token0, id)?;
+            assert_eq!((info.6, info.7), (fees_0, fees_1));
+
+            // the amounts are what removing the liquidity gives back
+            let (_, _, _, _, amount_0, amount_1, _, _) = info;
+            let (removed_0, removed_1) =
+                contract.update_position_C_7_F_1_F_740(token0, id, -liquidity)?;
+            assert_eq!(amount_0, removed_0.unsigned_abs());
+            assert_eq!(amount_1, removed_1.unsigned_abs());
+
+            // positions that don't exist are empty
+            let (position_owner, _, _, position_liquidity, amount_0, amount_1, _, _) =
+                contract.position_info_110_D9723(token0, U256::from(1000))?;
+            assert_eq!(position_owner, Address::ZERO);
+            assert_eq!(position_liquidity, 0);
+            assert_eq!((amount_0, amount_1), (U256::ZERO, U256::ZERO));
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn two_step_admin_transfer() {
+    let other = address!("737B7865f84bDc86B5c8ca718a5B7a6d905776F6");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()),
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            let sender = msg::sender();
+            contract.ctor(sender, Address::ZERO, Address::ZERO)?;
+
+            // proposing doesn't transfer any control
+            contract.transfer_admin_1068832_B(other)?;
+            assert_eq!(contract.pending_admin_1062_B366()?, other);
+            assert_eq!(contract.seawater_admin.get(), sender);
+
+            // only the pending admin can accept
+            assert_eq!(
+                contract.accept_admin_1023_F2_F_A(),
+                Err(Error::PendingAdminOnly.into())
+            );
+
+            // the sender can't change senders in the harness, so hand the admin to another
+            // account and have it propose the sender
+            contract.transfer_admin_1068832_B(sender)?;
+            contract.seawater_admin.set(other);
+            contract.accept_admin_1023_F2_F_A()?;
+            assert_eq!(contract.seawater_admin.get(), sender);
+            assert_eq!(contract.pending_admin_1062_B366()?, Address::ZERO);
+
+            // accepting can't be replayed
+            contract.seawater_admin.set(other);
+            assert_eq!(
+                contract.accept_admin_1023_F2_F_A(),
+                Err(Error::PendingAdminOnly.into())
+            );
+
+            // only the admin can propose
+            assert_eq!(
+                contract.transfer_admin_1068832_B(other),
+                Err(Error::SeawaterAdminOnly.into())
+            );
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+// the timelock ID of a call, computed from its Solidity signature and ABI encoded arguments
+fn timelocked_call_id(signature: &[u8], args: &[[u8; 32]]) -> B256 {
+    let mut calldata = eth_serde::selector(signature).to_vec();
+    for arg in args {
+        calldata.extend_from_slice(arg);
+    }
+    crypto::keccak(calldata)
+}
+
+#[test]
+fn timelocked_admin_calls() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+    let manager = address!("737B7865f84bDc86B5c8ca718a5B7a6d905776F6");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()),
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token0])?;
+            test_shims::set_timestamp(1_000_000);
+
+            let new_price = test_utils::encode_sqrt_price(4, 1);
+            let id = timelocked_call_id(
+                b"setSqrtPriceFF4DB98C(address,uint256)",
+                &[token0.into_word().0, new_price.to_be_bytes()],
+            );
+
+            // calls must be queued first
+            assert_eq!(
+                contract.set_sqrt_price_F_F_4_D_B_98_C(token0, new_price),
+                Err(Error::TimelockNotQueued.into())
+            );
+
+            let eta = contract.queue_timelocked_call_10_C89285(id)?;
+            assert_eq!(eta, 1_000_000 + timelock::TIMELOCK_DELAY);
+            assert_eq!(contract.timelocked_call_eta_106_B4_D2_F(id)?, eta);
+            assert_eq!(
+                contract.queue_timelocked_call_10_C89285(id),
+                Err(Error::TimelockAlreadyQueued.into())
+            );
+
+            // and can't be executed until the delay has passed
+            test_shims::advance_time(timelock::TIMELOCK_DELAY - 1);
+            assert_eq!(
+                contract.set_sqrt_price_F_F_4_D_B_98_C(token0, new_price),
+                Err(Error::TimelockNotReady.into())
+            );
+
+            // the ID covers the arguments
+            test_shims::advance_time(1);
+            assert_eq!(
+                contract.set_sqrt_price_F_F_4_D_B_98_C(token0, new_price + U256::from(1)),
+                Err(Error::TimelockNotQueued.into())
+            );
+
+            contract.set_sqrt_price_F_F_4_D_B_98_C(token0, new_price)?;
+            assert_eq!(contract.sqrt_price_x967_B8_F5_F_C5(token0)?, new_price);
+
+            // executing removes the call from the queue
+            assert_eq!(contract.timelocked_call_eta_106_B4_D2_F(id)?, 0);
+            assert_eq!(
+                contract.set_sqrt_price_F_F_4_D_B_98_C(token0, new_price),
+                Err(Error::TimelockNotQueued.into())
+            );
+
+            // cancelled calls can't be executed
+            let id = timelocked_call_id(
+                b"updateNftManager9BDF41F6(address)",
+                &[manager.into_word().0],
+            );
+            contract.queue_timelocked_call_10_C89285(id)?;
+            contract.cancel_timelocked_call_114_D_F1_E3(id)?;
+            assert_eq!(
+                contract.cancel_timelocked_call_114_D_F1_E3(id),
+                Err(Error::TimelockNotQueued.into())
+            );
+            test_shims::advance_time(timelock::TIMELOCK_DELAY);
+            assert_eq!(
+                contract.update_nft_manager_9_B_D_F_41_F_6(manager),
+                Err(Error::TimelockNotQueued.into())
+            );
+
+            // calls expire after the grace period
+            contract.queue_timelocked_call_10_C89285(id)?;
+            test_shims::advance_time(
+                timelock::TIMELOCK_DELAY + timelock::TIMELOCK_GRACE_PERIOD + 1,
+            );
+            assert_eq!(
+                contract.update_nft_manager_9_B_D_F_41_F_6(manager),
+                Err(Error::TimelockExpired.into())
+            );
+
+            // but can be requeued
+            contract.cancel_timelocked_call_114_D_F1_E3(id)?;
+            contract.queue_timelocked_call_10_C89285(id)?;
+            test_shims::advance_time(timelock::TIMELOCK_DELAY);
+            contract.update_nft_manager_9_B_D_F_41_F_6(manager)?;
+
+            let amount = U256::from(1000);
+            let id = timelocked_call_id(
+                b"sendTokenToSender(address,uint256)",
+                &[token0.into_word().0, amount.to_be_bytes()],
+            );
+            assert_eq!(
+                contract.send_token_to_sender(token0, amount),
+                Err(Error::TimelockNotQueued.into())
+            );
+            contract.queue_timelocked_call_10_C89285(id)?;
+            test_shims::advance_time(timelock::TIMELOCK_DELAY);
+            contract.send_token_to_sender(token0, amount)?;
+
+            // only the admin can queue or cancel calls
+            contract.seawater_admin.set(manager);
+            assert_eq!(
+                contract.queue_timelocked_call_10_C89285(id),
+                Err(Error::SeawaterAdminOnly.into())
+            );
+            assert_eq!(
+                contract.cancel_timelocked_call_114_D_F1_E3(id),
+                Err(Error::SeawaterAdminOnly.into())
+            );
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn update_emergency_council() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+    let admin = address!("737B7865f84bDc86B5c8ca718a5B7a6d905776F6");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()),
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token0])?;
+
+            contract.update_emergency_council_7_D_0_C_1_C_58(msg::sender())?;
+            contract.seawater_admin.set(admin);
+
+            // the sender is now the emergency council, so it can only disable pools
+            assert_eq!(
+                contract.enable_pool_579_D_A658(token0, true),
+                Err(Error::SeawaterEmergencyOnlyDisable.into())
+            );
+            contract.enable_pool_579_D_A658(token0, false)?;
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn role_based_access_control() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+    let token1 = address!("22b9895acA9FA2b8cd9A39D0BC0F8D4fF4e9F93d");
+    let admin = address!("737B7865f84bDc86B5c8ca718a5B7a6d905776F6");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()),
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token0])?;
+
+            let sender = msg::sender();
+            let missing_role = |role| -> Result<(), Vec<u8>> {
+                Err(Error::MissingRole {
+                    role,
+                    account: sender,
+                }
+                .into())
+            };
+
+            // hand the admin to another account, which implicitly holds every role
+            contract.seawater_admin.set(admin);
+            assert!(contract.has_role_10_A57_E_B4(roles::MIGRATOR, admin)?);
+            assert!(!contract.has_role_10_A57_E_B4(roles::POOL_CREATOR, sender)?);
+
+            assert_eq!(
+                contract.create_pool_D650_E2_D0(
+                    token1,
+                    test_utils::encode_sqrt_price(1, 1),
+                    3000,
+                    10,
+                    u128::MAX
+                ),
+                missing_role(roles::POOL_CREATOR)
+            );
+            assert_eq!(
+                contract
+                    .collect_protocol_7540_F_A_9_F(token0, 0, 0, sender)
+                    .map(|_| ()),
+                missing_role(roles::FEE_COLLECTOR)
+            );
+            assert_eq!(
+                contract.enable_pool_579_D_A658(token0, false),
+                missing_role(roles::POOL_CREATOR)
+            );
+            assert_eq!(
+                contract.disable_pools(vec![token0]),
+                missing_role(roles::PAUSER)
+            );
+            assert_eq!(
+                contract.set_sqrt_price_F_F_4_D_B_98_C(token0, test_utils::encode_sqrt_price(1, 2)),
+                missing_role(roles::PRICE_SETTER)
+            );
+            assert_eq!(
+                contract.send_token_to_sender(token0, U256::from(1)),
+                missing_role(roles::MIGRATOR)
+            );
+
+            // only the admin can grant roles
+            assert_eq!(
+                contract.grant_role_106_A_D_F_C9(roles::PAUSER, sender),
+                Err(Error::SeawaterAdminOnly.into())
+            );
+
+            contract.seawater_admin.set(sender);
+            contract.grant_role_106_A_D_F_C9(roles::PAUSER, sender)?;
+            contract.grant_role_106_A_D_F_C9(roles::FEE_COLLECTOR, sender)?;
+            contract.seawater_admin.set(admin);
+
+            contract.collect_protocol_7540_F_A_9_F(token0, 0, 0, sender)?;
+
+            // pausers can only disable pools
+            contract.enable_pool_579_D_A658(token0, false)?;
+            contract.disable_pools(vec![token0])?;
+            assert_eq!(
+                contract.enable_pool_579_D_A658(token0, true),
+                Err(Error::SeawaterEmergencyOnlyDisable.into())
+            );
+
+            // roles can't be used to call admin only functions
+            assert_eq!(
+                contract.set_fee_protocol_1004_E_D95(token0, 4, 4),
+                Err(Error::SeawaterAdminOnly.into())
+            );
+
+            contract.renounce_role_10_A8689_C(roles::PAUSER)?;
+            assert!(!contract.has_role_10_A57_E_B4(roles::PAUSER, sender)?);
+            assert_eq!(
+                contract.disable_pools(vec![token0]),
+                missing_role(roles::PAUSER)
+            );
+
+            // authorised enablers are pool creators
+            contract.seawater_admin.set(sender);
+            contract.authorise_enabler_5_B_17_C_274(sender, true)?;
+            contract.seawater_admin.set(admin);
+
+            assert!(contract.has_role_10_A57_E_B4(roles::POOL_CREATOR, sender)?);
+            contract.create_pool_D650_E2_D0(
+                token1,
+                test_utils::encode_sqrt_price(1, 1),
+                3000,
+                10,
+                u128::MAX,
+            )?;
+            contract.enable_pool_579_D_A658(token1, true)?;
+
+            contract.seawater_admin.set(sender);
+            contract.revoke_role_105_C41_F0(roles::POOL_CREATOR, sender)?;
+            contract.seawater_admin.set(admin);
+
+            assert!(!contract.has_role_10_A57_E_B4(roles::POOL_CREATOR, sender)?);
+            assert_eq!(
+                contract.enable_pool_579_D_A658(token1, false),
+                missing_role(roles::POOL_CREATOR)
+            );
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn pause_flags() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+    let admin = address!("737B7865f84bDc86B5c8ca718a5B7a6d905776F6");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()),
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            setup_swap_path_pools(contract, &[token0])?;
+
+            let sender = msg::sender();
+            let id = contract.mint_position_B_C5_B086_D(
+                token0,
+                tick_math::get_min_tick(10),
+                tick_math::get_max_tick(10),
+            )?;
+            contract.update_position_C_7_F_1_F_740(token0, id, 1_000_000_000)?;
+
+            let is_paused = |result: Result<(), Vec<u8>>| match result {
+                Err(err) if err == Vec::<u8>::from(Error::OperationPaused) => true,
+                // quotes always revert
+                Err(err) if QuoteResult::decode(&err).is_some() => false,
+                Err(err) => panic!("unexpected error {}", String::from_utf8_lossy(&err)),
+                Ok(()) => false,
+            };
+
+            for global in [false, true] {
+                contract.set_global_pause_10803050(global)?;
+
+                for flags in 0..=pool::PAUSE_ALL {
+                    contract.set_pool_pause_flags_132_B43_A7(token0, flags)?;
+                    assert_eq!(contract.pause_state_109451_A3(token0)?, (global, flags));
+
+                    let paused = match global {
+                        true => flags | pool::GLOBAL_PAUSE_FLAGS,
+                        false => flags,
+                    };
+                    let swaps = paused & pool::PAUSE_SWAPS != 0;
+                    let adds = paused & pool::PAUSE_ADDS != 0;
+                    let removals = paused & pool::PAUSE_REMOVALS != 0;
+                    let fee_collection = paused & pool::PAUSE_FEE_COLLECTION != 0;
+
+                    let zero_for_one = flags % 2 == 0;
+                    let amount = I256::unchecked_from(1000);
+                    let context = format!("flags {flags:#06b}, global {global}");
+
+                    let res = contract
+                        .swap_904369_B_E(token0, zero_for_one, amount, U256::MAX)
+                        .map(|_| ());
+                    assert_eq!(is_paused(res), swaps, "swap, {context}");
+                    let res = contract.quote_structured_2065_E121(
+                        token0,
+                        zero_for_one,
+                        amount,
+                        U256::MAX,
+                    );
+                    assert_eq!(is_paused(res), swaps, "quote, {context}");
+
+                    let res = contract
+                        .update_position_C_7_F_1_F_740(token0, id, 1000)
+                        .map(|_| ());
+                    assert_eq!(is_paused(res), adds, "add, {context}");
+                    let res = contract
+                        .incr_position_C_3_A_C_7_C_A_A(
+                            token0,
+                            id,
+                            U256::ZERO,
+                            U256::ZERO,
+                            U256::from(1000),
+                            U256::from(1000),
+                        )
+                        .map(|_| ());
+                    assert_eq!(is_paused(res), adds, "incr, {context}");
+
+                    let res = contract
+                        .update_position_C_7_F_1_F_740(token0, id, -1000)
+                        .map(|_| ());
+                    assert_eq!(is_paused(res), removals, "remove, {context}");
+                    let res = contract
+                        .decr_position_09293696(
+                            token0,
+                            id,
+                            U256::ZERO,
+                            U256::ZERO,
+                            U256::from(100),
+                            U256::from(100),
+                        )
+                        .map(|_| ());
+                    assert_eq!(is_paused(res), removals, "decr, {context}");
+
+                    let res = contract
+                        .collect_single_to_6_D_76575_F(token0, id, sender)
+                        .map(|_| ());
+                    assert_eq!(is_paused(res), fee_collection, "collect, {context}");
+                    let res = contract
+                        .collect_protocol_7540_F_A_9_F(token0, 0, 0, sender)
+                        .map(|_| ());
+                    assert_eq!(
+                        is_paused(res),
+                        fee_collection,
+                        "collect protocol, {context}"
+                    );
+
+                    // refreshing a position is never paused
+                    contract.update_position_C_7_F_1_F_740(token0, id, 0)?;
+                }
+            }
+
+            assert_eq!(
+                contract.set_pool_pause_flags_132_B43_A7(token0, pool::PAUSE_ALL + 1),
+                Err(Error::InvalidPauseFlags.into())
+            );
+
+            // pausers can pause, but only pool creators can unpause pools, and only the admin can
+            // lift the global pause
+            contract.set_pool_pause_flags_132_B43_A7(token0, 0)?;
+            contract.grant_role_106_A_D_F_C9(roles::PAUSER, sender)?;
+            contract.seawater_admin.set(admin);
+
+            contract.set_pool_pause_flags_132_B43_A7(token0, pool::PAUSE_SWAPS)?;
+            contract
+                .set_pool_pause_flags_132_B43_A7(token0, pool::PAUSE_SWAPS | pool::PAUSE_ADDS)?;
+            assert_eq!(
+                contract.set_pool_pause_flags_132_B43_A7(token0, pool::PAUSE_ADDS),
+                Err(Error::MissingRole {
+                    role: roles::POOL_CREATOR,
+                    account: sender,
+                }
+                .into())
+            );
+            assert_eq!(
+                contract.set_global_pause_10803050(false),
+                Err(Error::SeawaterAdminOnly.into())
+            );
+
+            contract.renounce_role_10_A8689_C(roles::PAUSER)?;
+            assert_eq!(
+                contract.set_global_pause_10803050(true),
+                Err(Error::MissingRole {
+                    role: roles::PAUSER,
+                    account: sender,
+                }
+                .into())
+            );
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn fee_tier_pool_creation() {
+    let token0 = address!("9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0");
+    let admin = address!("737B7865f84bDc86B5c8ca718a5B7a6d905776F6");
+
+    test_utils::with_storage::<_, Pools, _>(
+        Some(address!("3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E").into_array()),
+        None,
+        None,
+        None,
+        |contract| -> Result<(), Vec<u8>> {
+            contract.ctor(msg::sender(), Address::ZERO, Address::ZERO)?;
+            let price = test_utils::encode_sqrt_price(1, 1);
+
+            assert_eq!(
+                contract.enable_fee_tier_120_D0_D_D_C(1_000_000, 60),
+                Err(Error::InvalidFeeTier.into())
+            );
+            assert_eq!(
+                contract.enable_fee_tier_120_D0_D_D_C(3000, 0),
+                Err(Error::InvalidFeeTier.into())
+            );
+
+            contract.enable_fee_tier_120_D0_D_D_C(3000, 60)?;
+            assert_eq!(contract.fee_tier_tick_spacing_10_D66666(3000)?, 60);
+            assert_eq!(contract.fee_tier_tick_spacing_10_D66666(500)?, 0);
+            assert_eq!(
+                contract.enable_fee_tier_120_D0_D_D_C(3000, 10),
+                Err(Error::FeeTierAlreadyEnabled.into())
+            );
+
+            // pools can be created by anyone, but only the admin can add fee tiers
+            contract.seawater_admin.set(admin);
+            assert_eq!(
+                contract.enable_fee_tier_120_D0_D_D_C(500, 10),
+                Err(Error::SeawaterAdminOnly.into())
+            );
+            assert_eq!(
+                contract.create_pool_111360_A2(token0, price, 500),
+                Err(Error::FeeTierNotEnabled.into())
+            );
+
+            let pool = contract.create_pool_111360_A2(token0, price, 3000)?;
+            assert_eq!(
+                contract.create_pool_111360_A2(token0, price, 3000),
+                Err(Error::PoolAlreadyInitialised.into())
+            );
+
+            assert_eq!(contract.fee_B_B_3_C_F_608(pool)?, 3000);
+            assert_eq!(contract.tick_spacing_653_F_E28_F(pool)?, 60);
+            assert_eq!(
+                contract.pools.getter(pool).max_liquidity_per_tick.get(),
+                U128::from(tick_math::tick_spacing_to_max_liquidity_per_tick(60))
+            );
+
+            // the pool is enabled, and uses the tier's tick spacing
+            assert_eq!(
+                contract.mint_position_B_C5_B086_D(pool, -10, 10),
+                Err(Error::InvalidTickSpacing.into())
+            );
+            let id = contract.mint_position_B_C5_B086_D(pool, -600, 600)?;
+            contract.update_position_C_7_F_1_F_740(pool, id, 1_000_000)?;
+            contract.swap_904369_B_E(pool, true, I256::unchecked_from(100), U256::MAX)?;
+
+            Ok(())
+        },
+    )
+    .unwrap();
+}
@@ -1,4 +1,12 @@
-use libseawater::{error::Error, maths::tick_math, pool::*, test_utils, types::*};
+use libseawater::{
+    error::Error,
+    maths::tick_math,
+    pool::*,
+    snapshot::simulate_swap,
+    storage_dump::{DumpError, StorageDump},
+    test_utils,
+    types::*,
+};
 
 use ruint_macro::uint;
 
@@ -170,7 +178,11 @@ fn test_pool_swaps_reverts() {
 
         match pool.swap(true, I256::unchecked_from(1), sqrt_price + U256::from(1)) {
             Err(r) => assert_eq!(
-                Error::PriceLimitTooLow.to_string(),
+                Error::PriceLimitTooLow {
+                    limit: sqrt_price + U256::from(1),
+                    price: sqrt_price,
+                }
+                .to_string(),
                 String::from_utf8(r).unwrap()
             ),
             _ => panic!("expected PriceLimitTooLow"),
@@ -178,7 +190,11 @@ fn test_pool_swaps_reverts() {
 
         match pool.swap(true, I256::unchecked_from(1), tick_math::MIN_SQRT_RATIO) {
             Err(r) => assert_eq!(
-                Error::PriceLimitTooLow.to_string(),
+                Error::PriceLimitTooLow {
+                    limit: tick_math::MIN_SQRT_RATIO,
+                    price: sqrt_price,
+                }
+                .to_string(),
                 String::from_utf8(r).unwrap()
             ),
             _ => panic!("expected PriceLimitTooLow"),
@@ -186,7 +202,11 @@ fn test_pool_swaps_reverts() {
 
         match pool.swap(false, I256::unchecked_from(1), tick_math::MAX_SQRT_RATIO) {
             Err(r) => assert_eq!(
-                Error::PriceLimitTooHigh.to_string(),
+                Error::PriceLimitTooHigh {
+                    limit: tick_math::MAX_SQRT_RATIO,
+                    price: sqrt_price,
+                }
+                .to_string(),
                 String::from_utf8(r).unwrap()
             ),
             _ => panic!("expected PriceLimitTooHigh"),
@@ -194,7 +214,11 @@ fn test_pool_swaps_reverts() {
 
         match pool.swap(false, I256::unchecked_from(1), sqrt_price - U256::from(1)) {
             Err(r) => assert_eq!(
-                Error::PriceLimitTooHigh.to_string(),
+                Error::PriceLimitTooHigh {
+                    limit: sqrt_price - U256::from(1),
+                    price: sqrt_price,
+                }
+                .to_string(),
                 String::from_utf8(r).unwrap()
             ),
             _ => panic!("expected PriceLimitTooHigh"),
@@ -399,3 +423,330 @@ fn test_swap_inside_liq_range() -> Result<(), Vec<u8>> {
 
     Ok(())
 }
+
+// swaps on the pool, asserting that simulating the swap on a snapshot taken beforehand gives the
+// same result. returns the number of ticks crossed
+fn swap_and_simulate(
+    pool: &mut StoragePool,
+    zero_for_one: bool,
+    amount: I256,
+    price_limit: U256,
+) -> Result<u32, Vec<u8>> {
+    let snapshot = pool.snapshot();
+    let simulated = simulate_swap(&snapshot, zero_for_one, amount, price_limit);
+
+    match pool.swap(zero_for_one, amount, price_limit) {
+        Ok((amount_0, amount_1, tick)) => {
+            let simulated = simulated.unwrap();
+            assert_eq!(simulated.amount_0, amount_0);
+            assert_eq!(simulated.amount_1, amount_1);
+            assert_eq!(simulated.tick, tick);
+            assert_eq!(simulated.sqrt_price, pool.sqrt_price.get());
+            assert_eq!(simulated.liquidity, pool.liquidity.get().sys());
+
+            // the simulated pool should now match the real one
+            let mut snapshot = snapshot;
+            snapshot.apply(&simulated);
+            assert_eq!(snapshot, pool.snapshot());
+
+            Ok(simulated.ticks_crossed)
+        }
+        Err(r) => {
+            assert_eq!(Vec::<u8>::from(simulated.unwrap_err()), r);
+            Ok(0)
+        }
+    }
+}
+
+#[test]
+fn test_simulate_swap() -> Result<(), Vec<u8>> {
+    test_utils::with_storage::<_, StoragePool, _>(None, None, None, None, |storage| {
+        storage.init(
+            test_utils::encode_sqrt_price(100, 1), // price
+            0,
+            1,
+            u128::MAX,
+        )?;
+
+        storage.enabled.set(true);
+
+        let id = uint!(2_U256);
+        storage.create_position(
+            id,
+            tick_math::get_tick_at_sqrt_ratio(test_utils::encode_sqrt_price(50, 1))?,
+            tick_math::get_tick_at_sqrt_ratio(test_utils::encode_sqrt_price(150, 1))?,
+        )?;
+        storage.update_position(id, 100)?;
+
+        let id = uint!(3_U256);
+        storage.create_position(
+            id,
+            tick_math::get_tick_at_sqrt_ratio(test_utils::encode_sqrt_price(80, 1))?,
+            tick_math::get_tick_at_sqrt_ratio(test_utils::encode_sqrt_price(150, 1))?,
+        )?;
+        storage.update_position(id, 100)?;
+
+        swap_and_simulate(
+            storage,
+            true,
+            I256::unchecked_from(-10),
+            test_utils::encode_sqrt_price(60, 1),
+        )?;
+
+        swap_and_simulate(
+            storage,
+            true,
+            I256::unchecked_from(10),
+            test_utils::encode_sqrt_price(50, 1),
+        )?;
+
+        swap_and_simulate(
+            storage,
+            false,
+            I256::unchecked_from(10),
+            test_utils::encode_sqrt_price(120, 1),
+        )?;
+
+        swap_and_simulate(
+            storage,
+            false,
+            I256::unchecked_from(-10000),
+            test_utils::encode_sqrt_price(120, 1),
+        )?;
+
+        // errors should match too
+        swap_and_simulate(
+            storage,
+            false,
+            I256::unchecked_from(1),
+            tick_math::MAX_SQRT_RATIO,
+        )?;
+
+        swap_and_simulate(
+            storage,
+            true,
+            I256::unchecked_from(1),
+            tick_math::MIN_SQRT_RATIO,
+        )?;
+
+        Ok(())
+    })
+}
+
+#[test]
+fn test_simulate_swap_crossing_ticks() -> Result<(), Vec<u8>> {
+    test_utils::with_storage::<_, StoragePool, _>(None, None, None, None, |pool| {
+        pool.init(
+            test_utils::encode_sqrt_price(100_000, 1_000),
+            3000,
+            60,
+            u128::MAX,
+        )?;
+
+        pool.enabled.set(true);
+
+        // overlapping positions, so swaps cross several ticks
+        for (id, lower, upper) in [
+            (uint!(1_U256), 50_000, 150_000),
+            (uint!(2_U256), 90_000, 110_000),
+            (uint!(3_U256), 99_000, 200_000),
+        ] {
+            let lower =
+                tick_math::get_tick_at_sqrt_ratio(test_utils::encode_sqrt_price(lower, 1_000))?;
+            let upper =
+                tick_math::get_tick_at_sqrt_ratio(test_utils::encode_sqrt_price(upper, 1_000))?;
+
+            pool.create_position(id, lower - lower % 60, upper - upper % 60)?;
+            pool.update_position(id, 10i128.pow(18))?;
+        }
+
+        let swaps: [(bool, i128); 6] = [
+            (false, 10i128.pow(18)),
+            (true, 10i128.pow(18)),
+            (true, -(10i128.pow(17))),
+            (false, -(10i128.pow(17))),
+            (true, 10i128.pow(19)),
+            (false, 10i128.pow(20)),
+        ];
+
+        let mut ticks_crossed = 0;
+        for (zero_for_one, amount) in swaps {
+            ticks_crossed +=
+                swap_and_simulate(pool, zero_for_one, I256::unchecked_from(amount), U256::MAX)?;
+        }
+
+        assert!(ticks_crossed > 0);
+
+        Ok(())
+    })
+}
+
+#[test]
+fn test_simulate_swap_inside_liq_range() {
+    let delta = 10i128.pow(18);
+
+    let swap_amounts: Vec<i128> = (1..=10).map(|p| p * delta / 1_000).collect();
+
+    for swap_amount in &swap_amounts {
+        test_utils::with_storage::<_, StoragePool, _>(None, None, None, None, |pool| {
+            pool.init(
+                test_utils::encode_sqrt_price(100_000, 1_000),
+                3000,
+                60,
+                u128::MAX,
+            )
+            .unwrap();
+
+            pool.enabled.set(true);
+
+            let lower =
+                tick_math::get_tick_at_sqrt_ratio(test_utils::encode_sqrt_price(50_000, 1_000))
+                    .unwrap();
+            let upper =
+                tick_math::get_tick_at_sqrt_ratio(test_utils::encode_sqrt_price(150_000, 1_000))
+                    .unwrap();
+
+            let id = uint!(777_U256);
+            pool.create_position(id, lower - lower % 60, upper - upper % 60)
+                .unwrap();
+            pool.update_position(id, delta).unwrap();
+
+            swap_and_simulate(pool, true, I256::unchecked_from(*swap_amount), U256::MAX).unwrap();
+        });
+    }
+}
+
+#[test]
+fn test_simulate_swap_filling_range_orders() -> Result<(), Vec<u8>> {
+    test_utils::with_storage::<_, StoragePool, _>(None, None, None, None, |pool| {
+        pool.init(test_utils::encode_sqrt_price(1, 1), 3000, 10, u128::MAX)?;
+        pool.enabled.set(true);
+
+        for (id, lower, upper) in [(uint!(1_U256), -1200, 1200), (uint!(2_U256), -600, 1200)] {
+            pool.create_position(id, lower, upper)?;
+            pool.update_position(id, 10i128.pow(18))?;
+        }
+
+        // one order on ticks of its own, and one sharing its lower tick with a position
+        let owner = Address::ZERO;
+        pool.place_range_order(owner, 100, true, 10u128.pow(15))?;
+        pool.place_range_order(owner, -600, false, 10u128.pow(15))?;
+
+        swap_and_simulate(
+            pool,
+            false,
+            I256::unchecked_from(3 * 10i128.pow(16)),
+            U256::MAX,
+        )?;
+        assert!(pool.cur_tick.get().sys() > 110);
+        assert_eq!(pool.range_orders.pending(110, true), None);
+
+        swap_and_simulate(pool, true, I256::unchecked_from(10i128.pow(17)), U256::MAX)?;
+        assert!(pool.cur_tick.get().sys() < -600);
+        assert_eq!(pool.range_orders.pending(-600, false), None);
+
+        Ok(())
+    })
+}
+
+#[test]
+fn test_storage_dump_round_trip() -> Result<(), Vec<u8>> {
+    let setup = |pool: &mut StoragePool| -> Result<(), Vec<u8>> {
+        pool.init(
+            test_utils::encode_sqrt_price(100_000, 1_000),
+            3000,
+            60,
+            u128::MAX,
+        )?;
+        pool.enabled.set(true);
+        pool.set_fee_protocol(4, 5)?;
+
+        for (id, lower, upper) in [
+            (uint!(1_U256), 50_000, 150_000),
+            (uint!(2_U256), 90_000, 110_000),
+        ] {
+            let lower =
+                tick_math::get_tick_at_sqrt_ratio(test_utils::encode_sqrt_price(lower, 1_000))?;
+            let upper =
+                tick_math::get_tick_at_sqrt_ratio(test_utils::encode_sqrt_price(upper, 1_000))?;
+
+            pool.create_position(id, lower - lower % 60, upper - upper % 60)?;
+            pool.update_position(id, 10i128.pow(18))?;
+        }
+
+        pool.swap(false, I256::unchecked_from(10i128.pow(18)), U256::MAX)?;
+        Ok(())
+    };
+
+    let (dump, snapshot, next_swap) =
+        test_utils::with_storage::<_, StoragePool, _>(None, None, None, None, |pool| {
+            setup(pool)?;
+            let dump = StorageDump::capture();
+            let snapshot = pool.snapshot();
+            let next_swap = pool.swap(true, I256::unchecked_from(10i128.pow(17)), U256::MAX)?;
+            Ok::<_, Vec<u8>>((dump, snapshot, next_swap))
+        })?;
+
+    assert!(!dump.slots.is_empty());
+
+    // both formats should round trip
+    assert_eq!(StorageDump::from_json(&dump.to_json()), Ok(dump.clone()));
+    assert_eq!(StorageDump::from_bytes(&dump.to_bytes()), Ok(dump.clone()));
+
+    for name in ["seawater-dump-test.json", "seawater-dump-test.bin"] {
+        let path = std::env::temp_dir().join(name);
+        dump.write(&path).unwrap();
+        assert_eq!(StorageDump::read(&path), Ok(dump.clone()));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    // restoring the dump should give the same pool
+    test_utils::with_storage::<_, StoragePool, _>(None, None, None, None, |pool| {
+        dump.restore();
+
+        assert_eq!(pool.snapshot(), snapshot);
+        assert_eq!(pool.get_fee_protocol(), (4, 5));
+        assert_eq!(
+            pool.positions.positions.get(uint!(2_U256)).liquidity.get(),
+            U128::lib(&10u128.pow(18))
+        );
+        assert_eq!(
+            pool.swap(true, I256::unchecked_from(10i128.pow(17)), U256::MAX)?,
+            next_swap
+        );
+        Ok(())
+    })
+}
+
+#[test]
+fn test_storage_dump_reverts() {
+    assert_eq!(
+        StorageDump::from_json(r#"{"version": 2, "slots": {}}"#),
+        Err(DumpError::UnsupportedVersion(2))
+    );
+    assert_eq!(
+        StorageDump::from_json(r#"{"version": 1, "slots": {}}"#),
+        Ok(StorageDump::default())
+    );
+    assert_eq!(
+        StorageDump::from_json(r#"{"slots": {}}"#),
+        Err(DumpError::Malformed("missing version"))
+    );
+    assert_eq!(
+        StorageDump::from_json(r#"{"version": 1, "slots": {"0x01": "0x02"}}"#),
+        Err(DumpError::Malformed("bad word"))
+    );
+
+    assert_eq!(
+        StorageDump::from_bytes(b"SWSD\x02\x00\x00\x00\x00"),
+        Err(DumpError::UnsupportedVersion(2))
+    );
+    assert_eq!(
+        StorageDump::from_bytes(b"NOPE\x01\x00\x00\x00\x00"),
+        Err(DumpError::Malformed("bad magic"))
+    );
+    assert_eq!(
+        StorageDump::from_bytes(b"SWSD\x01\x00\x00\x00\x01"),
+        Err(DumpError::Malformed("slot count doesn't match length"))
+    );
+}
@@ -1,11 +1,13 @@
 // SPDX-Identifier: MIT
 pragma solidity 0.8.16;
 
+import "./ISeawaterErrors.sol";
 import "./ISeawaterEvents.sol";
 import "./ISeawaterExecutors.sol";
 
 // ISeawaterAMM is the public facing interface for the SeawaterAMM
 interface ISeawaterAMM is
+    ISeawaterErrors,
     ISeawaterEvents,
     ISeawaterExecutorSwap,
     ISeawaterExecutorSwapPermit2,
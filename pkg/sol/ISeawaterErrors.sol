@@ -0,0 +1,283 @@
+// SPDX-Identifier: MIT
+pragma solidity 0.8.16;
+
+// Generated from the Error enum in seawater/src/error.rs, don't edit this by hand!
+// Regenerate with UPDATE_ERRORS_SOL=1 cargo test -p seawater --features testing errors_sol
+
+interface ISeawaterErrors {
+    /// @notice Denominator is 0
+    /// @dev code 0 (0x00)
+    error DenominatorIsZero();
+
+    /// @notice Result is U256::MAX
+    /// @dev code 1 (0x01)
+    error ResultIsU256MAX();
+
+    /// @notice Sqrt price is 0
+    /// @dev code 2 (0x02)
+    error SqrtPriceIsZero();
+
+    /// @notice Sqrt price is less than or equal to quotient
+    /// @dev code 3 (0x03)
+    error SqrtPriceIsLteQuotient();
+
+    /// @notice Can not get most significant bit or least significant bit on zero value
+    /// @dev code 4 (0x04)
+    error ZeroValue();
+
+    /// @notice Liquidity is 0
+    /// @dev code 5 (0x05)
+    error LiquidityIsZero();
+
+    /// @notice require((product = amount * sqrtPX96) / amount == sqrtPX96 && numerator1 > product);
+    /// @dev code 6 (0x06)
+    error ProductDivAmount();
+
+    /// @notice Denominator is less than or equal to prod_1
+    /// @dev code 7 (0x07)
+    error DenominatorIsLteProdOne();
+
+    /// @notice Liquidity Sub
+    /// @dev code 8 (0x08)
+    error LiquiditySub();
+
+    /// @notice Liquidity Add
+    /// @dev code 9 (0x09)
+    error LiquidityAdd();
+
+    /// @notice The given tick must be less than, or equal to, the maximum tick
+    /// @dev code 10 (0x0a)
+    error T();
+
+    /// @notice Second inequality must be < because the price can never reach the price at the max tick
+    /// @dev code 11 (0x0b)
+    error R();
+
+    /// @notice Overflow when casting to U160
+    /// @dev code 12 (0x0c)
+    error SafeCastToU160Overflow();
+
+    /// @notice Liquidity higher than max
+    /// @dev code 13 (0x0d)
+    error LiquidityTooHigh();
+
+    /// @notice Fee growth sub overflow position
+    /// @dev code 14 (0x0e)
+    error FeeGrowthSubPos();
+
+    /// @notice ERC20 call reverted
+    /// @dev code 15 (0x0f)
+    error Erc20Revert(bytes data);
+
+    /// @notice ERC20 call reverted with no data
+    /// @dev code 16 (0x10)
+    error Erc20RevertNoData();
+
+    /// @notice Pool is already initialised
+    /// @dev code 17 (0x11)
+    error PoolAlreadyInitialised();
+
+    /// @notice Contract is already initialised
+    /// @dev code 18 (0x12)
+    error ContractAlreadyInitialised();
+
+    /// @notice Price limit too high
+    /// @dev code 19 (0x13)
+    error PriceLimitTooHigh(uint256 limit, uint256 price);
+
+    /// @notice Price limit too low
+    /// @dev code 20 (0x14)
+    error PriceLimitTooLow(uint256 limit, uint256 price);
+
+    /// @notice Checked abs called on an unexpected positive number
+    /// @dev code 21 (0x15)
+    error CheckedAbsIsNegative();
+
+    /// @notice Checked abs called on an unexpected negative number
+    /// @dev code 22 (0x16)
+    error CheckedAbsIsPositive();
+
+    /// @notice Checked abs called on uint.min
+    /// @dev code 23 (0x17)
+    error AbsTooLow();
+
+    /// @notice Fee result too high
+    /// @dev code 24 (0x18)
+    error FeeTooHigh();
+
+    /// @notice Swap result too high
+    /// @dev code 25 (0x19)
+    error SwapResultTooHigh();
+
+    /// @notice Internal swap amounts not matched
+    /// @dev code 26 (0x1a)
+    error InterimSwapNotEq();
+
+    /// @notice Internal swap result was positive
+    /// @dev code 27 (0x1b)
+    error InterimSwapPositive();
+
+    /// @notice Minimum out not reached
+    /// @dev code 28 (0x1c)
+    error MinOutNotReached();
+
+    /// @notice Only the position owner can use this
+    /// @dev code 29 (0x1d)
+    error PositionOwnerOnly();
+
+    /// @notice Only the NFT manager can use this
+    /// @dev code 30 (0x1e)
+    error NftManagerOnly();
+
+    /// @notice Only the Seawater admin can use this
+    /// @dev code 31 (0x1f)
+    error SeawaterAdminOnly();
+
+    /// @notice Operation unavailable when the pool is disabled
+    /// @dev code 32 (0x20)
+    error PoolDisabled();
+
+    /// @notice Invalid tick spacing
+    /// @dev code 33 (0x21)
+    error InvalidTickSpacing();
+
+    /// @notice Swap result too low
+    /// @dev code 34 (0x22)
+    error SwapResultTooLow();
+
+    /// @notice Liquidity amount too low or high to be a int128
+    /// @dev code 35 (0x23)
+    error LiquidityAmountTooWide();
+
+    /// @notice Invalid tick
+    /// @dev code 36 (0x24)
+    error InvalidTick();
+
+    /// @notice Pool enabled
+    /// @dev code 37 (0x25)
+    error PoolEnabled();
+
+    /// @notice Position is empty when it shouldn't be
+    /// @dev code 38 (0x26)
+    error EmptyPosition();
+
+    /// @notice Liquidity that was taken is too low
+    /// @dev code 39 (0x27)
+    error LiqResultTooLow();
+
+    /// @notice Fee growth sub overflow tick
+    /// @dev code 40 (0x28)
+    error FeeGrowthSubTick();
+
+    /// @notice The emergency council can only disable pools
+    /// @dev code 41 (0x29)
+    error SeawaterEmergencyOnlyDisable();
+
+    /// @notice Fee protocol must be 0, or between 4 and 10
+    /// @dev code 42 (0x2a)
+    error InvalidFeeProtocol();
+
+    /// @notice Swap path must have at least two tokens, with fUSDC on every hop
+    /// @dev code 43 (0x2b)
+    error InvalidSwapPath();
+
+    /// @notice Maximum in exceeded
+    /// @dev code 44 (0x2c)
+    error MaxInExceeded();
+
+    /// @notice Position index is out of bounds for the owner
+    /// @dev code 45 (0x2d)
+    error PositionIndexOutOfBounds();
+
+    /// @notice Only the pending admin can use this
+    /// @dev code 46 (0x2e)
+    error PendingAdminOnly();
+
+    /// @notice Timelocked call is already queued
+    /// @dev code 47 (0x2f)
+    error TimelockAlreadyQueued();
+
+    /// @notice Timelocked call is not queued
+    /// @dev code 48 (0x30)
+    error TimelockNotQueued();
+
+    /// @notice Timelocked call is not ready to be executed
+    /// @dev code 49 (0x31)
+    error TimelockNotReady();
+
+    /// @notice Timelocked call has expired
+    /// @dev code 50 (0x32)
+    error TimelockExpired();
+
+    /// @notice Account 0x0000000000000000000000000000000000000000 is missing role 0x0000000000000000000000000000000000000000000000000000000000000000
+    /// @dev code 51 (0x33)
+    error MissingRole(bytes32 role, address account);
+
+    /// @notice Operation is paused
+    /// @dev code 52 (0x34)
+    error OperationPaused();
+
+    /// @notice Pause flags are invalid
+    /// @dev code 53 (0x35)
+    error InvalidPauseFlags();
+
+    /// @notice Fee tier is not enabled
+    /// @dev code 54 (0x36)
+    error FeeTierNotEnabled();
+
+    /// @notice Fee tier must have a fee below 100%, and a non zero tick spacing
+    /// @dev code 55 (0x37)
+    error InvalidFeeTier();
+
+    /// @notice Fee tier is already enabled
+    /// @dev code 56 (0x38)
+    error FeeTierAlreadyEnabled();
+
+    /// @notice Pair pools need two different tokens, neither of which is the fluid token
+    /// @dev code 57 (0x39)
+    error InvalidTokenPair();
+
+    /// @notice Flash loan wasn't repaid with its fee
+    /// @dev code 58 (0x3a)
+    error FlashLoanNotRepaid();
+
+    /// @notice Pool's price oracle isn't initialised
+    /// @dev code 59 (0x3b)
+    error OracleNotInitialised();
+
+    /// @notice Observation is older than the oldest observation kept
+    /// @dev code 60 (0x3c)
+    error ObservationTooOld();
+
+    /// @notice Range order's range isn't entirely on one side of the price
+    /// @dev code 61 (0x3d)
+    error RangeOrderInRange();
+
+    /// @notice Range order has already been filled
+    /// @dev code 62 (0x3e)
+    error RangeOrderFilled();
+
+    /// @notice Range order hasn't been filled yet
+    /// @dev code 63 (0x3f)
+    error RangeOrderNotFilled();
+
+    /// @notice No range order for the caller in this epoch
+    /// @dev code 64 (0x40)
+    error RangeOrderEmpty();
+
+    /// @notice Tick isn't initialised
+    /// @dev code 65 (0x41)
+    error TickNotInitialised();
+
+    /// @notice Pools need a token that isn't the zero address or the fluid token
+    /// @dev code 66 (0x42)
+    error InvalidPoolToken();
+
+    /// @notice Flash loan is larger than the pool's reserves
+    /// @dev code 67 (0x43)
+    error FlashLoanTooLarge();
+
+    /// @notice Seawater is locked during a flash loan
+    /// @dev code 68 (0x44)
+    error FlashLoanLocked();
+}
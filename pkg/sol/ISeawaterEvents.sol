@@ -45,6 +45,52 @@ interface ISeawaterEvents {
         int256 token1
     );
 
+    /// @notice emitted when a user places a range order
+    /// @param user the user placing the order
+    /// @param pool the pool the order was placed in
+    /// @param epoch the epoch the order was batched into
+    /// @param lower the lower tick of the order's range
+    /// @param zeroForOne true if the order is selling token0 for token1, false otherwise
+    /// @param liquidity the liquidity added to the range
+    /// @param amount the amount of the token sold taken from the user
+    event RangeOrderPlaced(
+        address indexed user,
+        address indexed pool,
+        uint256 indexed epoch,
+        int32 lower,
+        bool zeroForOne,
+        uint128 liquidity,
+        uint256 amount
+    );
+
+    /// @notice emitted when a user cancels an unfilled range order
+    /// @param user the user cancelling the order
+    /// @param pool the pool the order was placed in
+    /// @param epoch the epoch the order was batched into
+    /// @param amount0 the amount of token0 sent to the user
+    /// @param amount1 the amount of token1 sent to the user
+    event RangeOrderCancelled(
+        address indexed user,
+        address indexed pool,
+        uint256 indexed epoch,
+        uint256 amount0,
+        uint256 amount1
+    );
+
+    /// @notice emitted when a user claims a filled range order
+    /// @param user the user claiming the order
+    /// @param pool the pool the order was placed in
+    /// @param epoch the epoch the order was batched into
+    /// @param amount0 the amount of token0 sent to the user
+    /// @param amount1 the amount of token1 sent to the user
+    event RangeOrderClaimed(
+        address indexed user,
+        address indexed pool,
+        uint256 indexed epoch,
+        uint256 amount0,
+        uint256 amount1
+    );
+
     /// @notice emitted when a liquidity provider collects the fees associated with a position
     /// @param id the id of the position whose liquidity is being collected
     /// @param pool the address of the pool the position is associated with
@@ -62,14 +108,36 @@ interface ISeawaterEvents {
     // admin
 
     /// @notice emitted when a new pool is created
-    /// @param token the token0 the pool is associated with (where token1 is a fluid token)
+    /// @param token the token0 the pool is associated with
     /// @param fee the fee being used for this pool
     /// @param decimals the decimals for the token
     /// @param tickSpacing the tick spacing for the pool
+    /// @param pool the id of the pool, which is the token for pools not created with a fee tier
+    /// @param token1 the token1 of the pool, which is the fluid token unless the pool is a pair pool
     event NewPool(
         address indexed token,
         uint32 indexed fee,
         uint8 decimals,
+        uint8 tickSpacing,
+        address pool,
+        address token1
+    );
+
+    /// @notice emitted when the number of observations the price oracle of a pool will keep increases
+    /// @param pool the pool whose oracle is growing
+    /// @param observationCardinalityNextOld the number of observations that would have been kept
+    /// @param observationCardinalityNextNew the number of observations that will be kept
+    event IncreaseObservationCardinalityNext(
+        address indexed pool,
+        uint16 observationCardinalityNextOld,
+        uint16 observationCardinalityNextNew
+    );
+
+    /// @notice emitted when the admin enables a fee tier, letting anyone create pools with it
+    /// @param fee the fee for pools in the tier
+    /// @param tickSpacing the tick spacing for pools in the tier
+    event FeeTierEnabled(
+        uint32 indexed fee,
         uint8 tickSpacing
     );
 
@@ -85,6 +153,157 @@ interface ISeawaterEvents {
         uint128 amount1
     );
 
+    /// @notice emitted when the protocol's share of swap fees is changed for a pool
+    /// @param pool the pool the protocol fee is being set for
+    /// @param feeProtocol0Old the previous protocol fee denominator for token0
+    /// @param feeProtocol1Old the previous protocol fee denominator for token1
+    /// @param feeProtocol0New the new protocol fee denominator for token0
+    /// @param feeProtocol1New the new protocol fee denominator for token1
+    event SetFeeProtocol(
+        address indexed pool,
+        uint8 feeProtocol0Old,
+        uint8 feeProtocol1Old,
+        uint8 feeProtocol0New,
+        uint8 feeProtocol1New
+    );
+
+    /// @notice emitted when the admin overrides the price of a pool
+    /// @param pool the pool whose price was overridden
+    /// @param oldPrice the previous sqrt price of the pool, as a Q64.96
+    /// @param newPrice the new sqrt price of the pool, as a Q64.96
+    event SqrtPriceOverridden(
+        address indexed pool,
+        uint256 oldPrice,
+        uint256 newPrice
+    );
+
+    /// @notice emitted when the NFT manager is changed
+    /// @param oldManager the previous NFT manager
+    /// @param newManager the new NFT manager
+    event NftManagerUpdated(
+        address indexed oldManager,
+        address indexed newManager
+    );
+
+    /// @notice emitted when the emergency council is changed
+    /// @param oldCouncil the previous emergency council
+    /// @param newCouncil the new emergency council
+    event EmergencyCouncilUpdated(
+        address indexed oldCouncil,
+        address indexed newCouncil
+    );
+
+    /// @notice emitted when a pool is enabled or disabled
+    /// @param pool the pool being enabled or disabled
+    /// @param enabled true if the pool was enabled, false if it was disabled
+    /// @param sender the admin, emergency council, or enabler that changed the pool
+    event SetPoolEnabled(
+        address indexed pool,
+        bool enabled,
+        address indexed sender
+    );
+
+    /// @notice emitted when an account is allowed or disallowed from enabling pools
+    /// @param enabler the account being authorised
+    /// @param enabled true if the account can now enable pools, false otherwise
+    event EnablerAuthorised(
+        address indexed enabler,
+        bool enabled
+    );
+
+    /// @notice emitted when the global pause is activated or lifted
+    /// @param paused true if swaps and liquidity adds are now paused in every pool
+    /// @param sender the account that changed the global pause
+    event GlobalPauseUpdated(
+        bool paused,
+        address indexed sender
+    );
+
+    /// @notice emitted when the operations paused in a pool are changed
+    /// @param pool the pool changed
+    /// @param flags the operations now paused, a combination of 1 (swaps), 2 (liquidity adds),
+    ///        4 (liquidity removals), and 8 (fee collection)
+    /// @param sender the account that changed the flags
+    event PoolPauseFlagsUpdated(
+        address indexed pool,
+        uint8 flags,
+        address indexed sender
+    );
+
+    /// @notice emitted when a role is granted to an account
+    /// @param role the keccak256 hash of the role's name
+    /// @param account the account granted the role
+    /// @param sender the account that granted the role
+    event RoleGranted(
+        bytes32 indexed role,
+        address indexed account,
+        address indexed sender
+    );
+
+    /// @notice emitted when a role is revoked from, or renounced by, an account
+    /// @param role the keccak256 hash of the role's name
+    /// @param account the account the role was revoked from
+    /// @param sender the account that revoked the role
+    event RoleRevoked(
+        bytes32 indexed role,
+        address indexed account,
+        address indexed sender
+    );
+
+    /// @notice emitted when the admin proposes a new admin
+    /// @param currentAdmin the admin proposing the transfer
+    /// @param pendingAdmin the proposed admin, or the zero address if the transfer was cancelled
+    event AdminTransferStarted(
+        address indexed currentAdmin,
+        address indexed pendingAdmin
+    );
+
+    /// @notice emitted when a proposed admin accepts the transfer
+    /// @param oldAdmin the previous admin
+    /// @param newAdmin the new admin
+    event AdminTransferred(
+        address indexed oldAdmin,
+        address indexed newAdmin
+    );
+
+    /// @notice emitted when the admin queues a timelocked call
+    /// @param id the keccak256 hash of the calldata of the call
+    /// @param eta the timestamp the call can be executed from
+    event TimelockedCallQueued(
+        bytes32 indexed id,
+        uint64 eta
+    );
+
+    /// @notice emitted when the admin cancels a queued timelocked call
+    /// @param id the keccak256 hash of the calldata of the call
+    event TimelockedCallCancelled(bytes32 indexed id);
+
+    /// @notice emitted when a queued timelocked call is executed
+    /// @param id the keccak256 hash of the calldata of the call
+    event TimelockedCallExecuted(bytes32 indexed id);
+
+    // migrations
+
+    /// @notice emitted when the admin withdraws tokens held by the AMM
+    /// @param token the token withdrawn
+    /// @param to the admin the tokens were sent to
+    /// @param amount the amount of the token withdrawn
+    event EmergencyWithdrawal(
+        address indexed token,
+        address indexed to,
+        uint256 amount
+    );
+
+    /// @notice emitted when the admin sends its own tokens to a user through the AMM
+    /// @param token the token sent
+    /// @param recipient the user the tokens were sent to
+    /// @param amount the amount of the token sent
+    event EmergencyDistribution(
+        address indexed token,
+        address indexed recipient,
+        uint256 amount
+    );
+
     // amm
 
     /// @notice emitted when a user swaps a nonfluid token for a nonfluid token (2-step swap)
@@ -107,6 +326,40 @@ interface ISeawaterEvents {
         int32 finalTick1
     );
 
+    /// @notice emitted when a user swaps along a path of tokens (multi-step swap)
+    /// @param user the user performing the swap
+    /// @param from the input token
+    /// @param to the output token
+    /// @param path the tokens swapped through, including the input and output tokens
+    /// @param amountIn the amount of `from` the user is paying
+    /// @param amountOut the amount of `to` the user is receiving
+    event SwapPath(
+        address indexed user,
+        address indexed from,
+        address indexed to,
+        address[] path,
+        uint256 amountIn,
+        uint256 amountOut
+    );
+
+    /// @notice emitted when a user takes a flash loan from a pool
+    /// @param user the user taking the loan, who was called back to repay it
+    /// @param pool the pool the loan was taken from
+    /// @param recipient the recipient of the loan
+    /// @param amount0 the amount of token0 lent
+    /// @param amount1 the amount of token1 lent
+    /// @param paid0 the amount of token0 paid as fees
+    /// @param paid1 the amount of token1 paid as fees
+    event Flash(
+        address indexed user,
+        address indexed pool,
+        address indexed recipient,
+        uint256 amount0,
+        uint256 amount1,
+        uint256 paid0,
+        uint256 paid1
+    );
+
     /// @notice emitted when a user swaps a token for the pool's fluid token, or vice-versa
     /// @param user the user performing the swap
     /// @param pool the token being swapped for the fluid token
@@ -16,8 +16,8 @@ interface ISeawaterExecutorSwap {
     ) external returns (int256, int256);
 
     /// @notice performs a two stage swap across two pools
-    /// @param from the input token
-    /// @param to the output token
+    /// @param from the pool of the input token, or the fluid token
+    /// @param to the pool of the output token, or the fluid token
     /// @param amount the amount of the input token to use
     /// @param minOut the minimum valid amount of the output token, reverts if not reached
     /// @return (amount in, amount out)
@@ -27,6 +27,59 @@ interface ISeawaterExecutorSwap {
         uint256 amount,
         uint256 minOut
     ) external returns (uint256, uint256);
+
+    /// @notice performs a two stage exact out swap across two pools
+    /// @param from the pool of the input token, or the fluid token
+    /// @param to the pool of the output token, or the fluid token
+    /// @param amountOut the exact amount of the output token to receive
+    /// @param maxIn the maximum valid amount of the input token, reverts if exceeded
+    /// @return (amount in, amount out)
+    function swap2ExactOut1758D1A7(
+        address from,
+        address to,
+        uint256 amountOut,
+        uint256 maxIn
+    ) external returns (uint256, uint256);
+
+    /// @notice performs an exact in swap along a path of tokens, where every hop is into or out of the fluid token
+    /// @param path the pools to swap through, or the fluid token, starting with the input and ending with the output
+    /// @param amount the amount of the input token to use
+    /// @param minOut the minimum valid amount of the output token, reverts if not reached
+    /// @return (amount in, amount out)
+    function swapPathExactIn1033D01B(
+        address[] memory path,
+        uint256 amount,
+        uint256 minOut
+    ) external returns (uint256, uint256);
+
+    /// @notice performs an exact out swap along a path of tokens, where every hop is into or out of the fluid token
+    /// @param path the pools to swap through, or the fluid token, starting with the input and ending with the output
+    /// @param amount the amount of the output token to receive
+    /// @param maxIn the maximum valid amount of the input token, reverts if exceeded
+    /// @return (amount in, amount out)
+    function swapPathExactOut100F69C2(
+        address[] memory path,
+        uint256 amount,
+        uint256 maxIn
+    ) external returns (uint256, uint256);
+
+    /// @notice lends the tokens of a pool, calling back the sender with
+    ///         IUniswapV3FlashCallback.uniswapV3FlashCallback, which must repay the loan plus fees
+    /// @dev the fees are credited to in range liquidity providers, less the protocol's share.
+    ///      the amounts can't be more than the pool's own reserves. swaps, positions, and flash
+    ///      loans are locked until the callback returns
+    /// @param pool the pool to borrow from
+    /// @param recipient the recipient of the loan
+    /// @param amount0 the amount of token0 to borrow
+    /// @param amount1 the amount of token1 to borrow
+    /// @param data passed to the callback
+    function flash1022F44D(
+        address pool,
+        address recipient,
+        uint256 amount0,
+        uint256 amount1,
+        bytes calldata data
+    ) external;
 }
 
 interface ISeawaterExecutorSwapPermit2 {
@@ -70,9 +123,43 @@ interface ISeawaterExecutorSwapPermit2 {
         uint256 deadline,
         bytes memory sig
     ) external returns (uint256, uint256);
+
+    /// @notice performs a two stage exact out swap across two pools using permit2 for token transfers
+    /// @param from the input token
+    /// @param to the output token
+    /// @param amountOut the exact amount of the output token to receive
+    /// @param maxIn the maximum valid amount of the input token, reverts if exceeded
+    /// @param nonce the permit2 nonce
+    /// @param deadline the permit2 deadline
+    /// @param sig the permit2 signature
+    /// @notice permit2's max amount must be set to `maxIn`
+    /// @return (amount in, amount out)
+    function swap2ExactOutPermit21161B87F(
+        address from,
+        address to,
+        uint256 amountOut,
+        uint256 maxIn,
+        uint256 nonce,
+        uint256 deadline,
+        bytes memory sig
+    ) external returns (uint256, uint256);
 }
 
 interface ISeawaterExecutorQuote {
+    /// @notice the result of a structured quote, always reverted with by quoteStructured2065E121
+    /// @param amount0 the amount of token 0 taken (positive) or given (negative) by the swap
+    /// @param amount1 the amount of token 1 taken (positive) or given (negative) by the swap
+    /// @param sqrtPriceX96 the price of the pool after the swap, encoded as a sqrtX96 price
+    /// @param tick the tick of the pool after the swap
+    /// @param initialisedTicksCrossed the number of initialised ticks crossed by the swap
+    error QuoteResult(
+        int256 amount0,
+        int256 amount1,
+        uint256 sqrtPriceX96,
+        int32 tick,
+        uint32 initialisedTicksCrossed
+    );
+
     /// @notice reverts with the expected amount of fUSDC or pool token for a swap with the given parameters
     /// @param pool the pool to swap on
     /// @param zeroForOne true if swapping token->fluid token
@@ -86,6 +173,19 @@ interface ISeawaterExecutorQuote {
         uint256 priceLimit
     ) external;
 
+    /// @notice reverts with the result of a swap with the given parameters, as a QuoteResult error
+    /// @param pool the pool to swap on
+    /// @param zeroForOne true if swapping token->fluid token
+    /// @param amount the amount of token to swap, positive if exactIn, negative if exactOut
+    /// @param priceLimit the price limit for swaps, encoded as a sqrtX96 price
+    /// @notice always revert with QuoteResult(amount0, amount1, sqrtPriceX96, tick, initialisedTicksCrossed)
+    function quoteStructured2065E121(
+        address pool,
+        bool zeroForOne,
+        int256 amount,
+        uint256 priceLimit
+    ) external;
+
     /// @notice reverts with the expected amount of tokenOut for a 2-token swap with the given parameters
     /// @param from the input token
     /// @param to the output token
@@ -98,6 +198,28 @@ interface ISeawaterExecutorQuote {
         uint256 amount,
         uint256 minOut
     ) external;
+
+    /// @notice reverts with the expected amount of the input token for a 2-token exact out swap with the given parameters
+    /// @param from the input token
+    /// @param to the output token
+    /// @param amountOut the exact amount of the output token to receive
+    /// @param maxIn the maximum valid amount of the input token, reverts if exceeded
+    /// @notice always revert with Error(string(amountIn))
+    function quote2ExactOut13FB992B(
+        address from,
+        address to,
+        uint256 amountOut,
+        uint256 maxIn
+    ) external;
+
+    /// @notice reverts with the expected amount of the other token for a swap along a path with the given parameters
+    /// @param path the tokens to swap through, starting with the input token and ending with the output token
+    /// @param amount the amount of token to swap, positive if exactIn, negative if exactOut
+    /// @notice always revert with Error(string(amountOut)) if exactIn, or Error(string(amountIn)) if exactOut
+    function quotePath101993E8(
+        address[] memory path,
+        int256 amount
+    ) external;
 }
 
 interface ISeawaterExecutorPosition {
@@ -135,6 +257,51 @@ interface ISeawaterExecutorPosition {
     /// @return the number of positions owned by the user
     function positionBalance4F32C7DB(address user) external returns (uint256);
 
+    /// @notice gets the id of a position owned by a user, by its index in the user's positions
+    /// @dev indexes change when the user's positions change, the last position is moved into the
+    ///      index of a removed position. positions granted before positions were enumerated
+    ///      are left out until they're backfilled
+    /// @param owner the user to get the position of
+    /// @param index the index of the position, less than the user's enumerated positions
+    /// @return the id of the position
+    function positionOfOwnerByIndex1068E3C7(address owner, uint256 index) external returns (uint256);
+
+    /// @notice gets a page of the ids of the positions owned by a user
+    /// @dev leaves out positions that aren't enumerated yet, like positionOfOwnerByIndex1068E3C7
+    /// @param owner the user to list the positions of
+    /// @param offset the index of the first position to return
+    /// @param limit the maximum number of positions to return
+    /// @return the ids of the positions, empty if offset is past the last position
+    function positionsOfOwner103ECF2F(
+        address owner,
+        uint256 offset,
+        uint256 limit
+    ) external returns (uint256[] memory);
+
+    /// @notice gets the full state of a position in one call
+    /// @dev fees include those earned since the position was last updated, without checkpointing
+    ///      them. positions that don't exist return zero for everything
+    /// @param pool the position belongs to
+    /// @param id the id of the position
+    /// @return owner the owner of the position
+    /// @return lower the lower tick of the position
+    /// @return upper the upper tick of the position
+    /// @return liquidity the liquidity of the position
+    /// @return amount0 the amount of token 0 the position's liquidity is worth at the current price
+    /// @return amount1 the amount of token 1 the position's liquidity is worth at the current price
+    /// @return feesOwed0 the fees of token 0 owed to the position
+    /// @return feesOwed1 the fees of token 1 owed to the position
+    function positionInfo110D9723(address pool, uint256 id) external returns (
+        address owner,
+        int32 lower,
+        int32 upper,
+        uint128 liquidity,
+        uint256 amount0,
+        uint256 amount1,
+        uint128 feesOwed0,
+        uint128 feesOwed1
+    );
+
     /// @notice gets the amount of liquidity in a position
     /// @param pool the position belongs to
     /// @param id the id of the position
@@ -217,25 +384,83 @@ interface ISeawaterExecutorUpdatePosition {
         uint256 amount1Max
     ) external returns (uint256, uint256);
 
+    /// @notice refreshes a position's fees, and adds liquidity using permit2 for token transfers,
+    /// @notice preventing less than the minimum from being taken.
+    /// @param token the pool of the token to use
+    /// @param id the id of the position
+    /// @param amount0Min minimum of amount0 to take from the user
+    /// @param amount1Min minimum of amount1 to take from the user
+    /// @param nonce0 the permit2 nonce for token0
+    /// @param deadline0 the permit2 deadline for token0
+    /// @param amount0Max the permit2 maxAmount for token0, used as the desired amount
+    /// @param sig0 the permit2 signature for token0
+    /// @param nonce1 the permit2 nonce for token1
+    /// @param deadline1 the permit2 deadline for token1
+    /// @param amount1Max the permit2 maxAmount for token1, used as the desired amount
+    /// @param sig1 the permit2 signature for token1
+    /// @return the deltas for token0, and token1
     function incrPositionPermit25468326E(
-        address /* token */,
-        uint256 /* id */,
-        uint256 /* amount0Min */,
-        uint256 /* amount1Min */,
-        uint256 /* nonce0 */,
-        uint256 /* deadline0 */,
-        uint256 /* amount0Max */,
-        bytes memory /* sig0 */,
-        uint256 /* nonce1 */,
-        uint256 /* deadline1 */,
-        uint256 /* amount1Max */,
-        bytes memory /* sig1 */
+        address token,
+        uint256 id,
+        uint256 amount0Min,
+        uint256 amount1Min,
+        uint256 nonce0,
+        uint256 deadline0,
+        uint256 amount0Max,
+        bytes memory sig0,
+        uint256 nonce1,
+        uint256 deadline1,
+        uint256 amount1Max,
+        bytes memory sig1
+    ) external returns (uint256, uint256);
+
+    /// @notice places a range order, selling a token over a single tick spacing range. the order
+    /// @notice is filled, and its liquidity removed from the pool, once a swap crosses the far
+    /// @notice side of the range.
+    /// @param pool to place the order in
+    /// @param lower the lower tick of the range, which must be above the current tick if selling
+    /// token0, or below it if selling token1
+    /// @param zeroForOne true if selling token0 for token1, false if selling token1 for token0
+    /// @param liquidity the amount of liquidity to add to the range
+    /// @return the epoch the order was batched into, and the amount of the token sold taken
+    function placeRangeOrder103B25D5(
+        address pool,
+        int32 lower,
+        bool zeroForOne,
+        uint128 liquidity
+    ) external returns (uint256, uint256);
+
+    /// @notice cancels the sender's unfilled range order in an epoch, sending them the tokens
+    /// @notice it's worth and its share of the fees it earned
+    /// @param pool the order was placed in
+    /// @param epoch the epoch the order was batched into
+    /// @return the amounts of token0 and token1 sent to the user
+    function cancelRangeOrder11EE2063(
+        address pool,
+        uint256 epoch
+    ) external returns (uint256, uint256);
+
+    /// @notice claims the sender's filled range order in an epoch, sending them the tokens it
+    /// @notice was filled with and its share of the fees it earned
+    /// @param pool the order was placed in
+    /// @param epoch the epoch the order was batched into
+    /// @return the amounts of token0 and token1 sent to the user
+    function claimRangeOrder11F9C034(
+        address pool,
+        uint256 epoch
     ) external returns (uint256, uint256);
 }
 
 /// @dev contains just the admin functions that are exposed directly
 interface ISeawaterExecutorAdminExposed {
-    /// @notice initialises a new pool. only usable by the seawater admin
+    struct TickInfo {
+        int32 tick;
+        uint128 liquidityGross;
+        int128 liquidityNet;
+        uint256 feeGrowthOutside0;
+        uint256 feeGrowthOutside1;
+    }
+    /// @notice initialises a new pool, with the token as its id. only usable by accounts with the POOL_CREATOR role
     /// @param pool the token to create the pool with
     /// @param sqrtPriceX96 the starting price for the pool
     /// @param fee the fee to use
@@ -249,6 +474,62 @@ interface ISeawaterExecutorAdminExposed {
         uint128 maxLiquidityPerTick
     ) external;
 
+    /// @notice creates a new, enabled, pool using an enabled fee tier. usable by anyone
+    /// @param token the token to create the pool with
+    /// @param sqrtPriceX96 the starting price for the pool
+    /// @param fee the fee to use, which determines the tick spacing of the pool
+    /// @return the id of the new pool
+    function createPool111360A2(address token, uint256 sqrtPriceX96, uint32 fee) external returns (address);
+
+    /// @notice creates a new, enabled, pool between two tokens that aren't the fluid token,
+    ///         using an enabled fee tier. the lower token address is token0. usable by anyone
+    /// @dev pair pools can't be used as hops in two stage or path swaps
+    /// @param tokenA one of the tokens of the pool
+    /// @param tokenB the other token of the pool
+    /// @param sqrtPriceX96 the starting price for the pool, of token1 in terms of token0
+    /// @param fee the fee to use, which determines the tick spacing of the pool
+    /// @return the id of the new pool
+    function createPairPool111D0A09(
+        address tokenA,
+        address tokenB,
+        uint256 sqrtPriceX96,
+        uint32 fee
+    ) external returns (address);
+
+    /// @notice gets the pool between two tokens, in either order, with a fee
+    /// @param tokenA one of the tokens of the pool
+    /// @param tokenB the other token of the pool
+    /// @param fee the fee of the pool
+    /// @return the id of the pool, or the zero address if it doesn't exist
+    function getPairPool11957B4F(address tokenA, address tokenB, uint32 fee) external returns (address);
+
+    /// @notice gets the pool for a token and fee
+    /// @param token the token of the pool
+    /// @param fee the fee of the pool
+    /// @return the id of the pool, or the zero address if it doesn't exist
+    function getPool12523831(address token, uint32 fee) external returns (address);
+
+    /// @notice gets the token of a pool
+    /// @param pool the id of the pool
+    /// @return the token, which is the pool id for pools created without a fee tier
+    function poolToken10162F39(address pool) external returns (address);
+
+    /// @notice gets both tokens of a pool
+    /// @param pool the id of the pool
+    /// @return token0 and token1 of the pool, where token1 is the fluid token unless the pool is a pair pool
+    function poolTokens10591443(address pool) external returns (address, address);
+
+    /// @notice enables a fee tier, letting anyone create pools with it. fee tiers can't be
+    ///         changed once enabled. only usable by the seawater admin
+    /// @param fee the fee for pools in the tier, in hundredths of a bip
+    /// @param tickSpacing the tick spacing for pools in the tier
+    function enableFeeTier120D0DDC(uint32 fee, uint8 tickSpacing) external;
+
+    /// @notice gets the tick spacing of a fee tier
+    /// @param fee of the tier
+    /// @return the tick spacing, or 0 if the tier isn't enabled
+    function feeTierTickSpacing10D66666(uint32 fee) external returns (uint8);
+
     /// @notice collects protocol fees. only usable by the seawater admin
     /// @param pool the pool to collect fees for
     /// @param amount0 the maximum amount of token0 fees to collect
@@ -262,6 +543,16 @@ interface ISeawaterExecutorAdminExposed {
         address recipient
     ) external returns (uint128, uint128);
 
+    /// @notice sets the protocol's share of swap fees. only usable by the seawater admin
+    /// @param pool the pool to set the protocol fee for
+    /// @param feeProtocol0 the denominator of the protocol's share of token0 fees, 0 or 4-10
+    /// @param feeProtocol1 the denominator of the protocol's share of token1 fees, 0 or 4-10
+    function setFeeProtocol1004ED95(
+        address pool,
+        uint8 feeProtocol0,
+        uint8 feeProtocol1
+    ) external;
+
     /// @notice feesOwed to a position ID given.
     /// @param pool to get the fees owed for
     /// @param id of the position to check for
@@ -278,6 +569,40 @@ interface ISeawaterExecutorAdminExposed {
     /// @return the current active tick in the pool
     function curTick181C6FD9(address pool) external returns (int32);
 
+    /// @notice gets the cumulative tick and seconds per liquidity of a pool at each of a number of
+    ///         seconds ago, for computing time weighted averages
+    /// @param pool to get from
+    /// @param secondsAgos how far back to get each observation from, in seconds
+    /// @return the tick cumulatives and the seconds per liquidity cumulatives (as X128 numbers)
+    function observe106B4AB6(
+        address pool,
+        uint32[] calldata secondsAgos
+    ) external returns (int56[] memory, uint160[] memory);
+
+    /// @notice gets the seconds per liquidity and the seconds that the price of the pool has spent
+    ///         inside a range. only meaningful compared to an earlier value taken while the
+    ///         ticks of the range were initialised
+    /// @param pool to get from
+    /// @param lower tick of the range
+    /// @param upper tick of the range
+    /// @return the seconds per liquidity inside (as an X128 number), and the seconds inside
+    function secondsPerLiquidityInside11B8A254(
+        address pool,
+        int32 lower,
+        int32 upper
+    ) external returns (uint160, uint32);
+
+    /// @notice gets the state of the price oracle of the pool
+    /// @param pool to get from
+    /// @return the index of the most recent observation, the number of observations kept, and
+    ///         the number that will be kept once the buffer wraps around
+    function observationState105820A6(address pool) external returns (uint16, uint16, uint16);
+
+    /// @notice increases the number of observations the price oracle of the pool keeps. usable by anyone
+    /// @param pool to increase the observations of
+    /// @param next the number of observations to keep
+    function increaseObservationCardinalityNext100D2AB9(address pool, uint16 next) external;
+
     /// @notice gets the tick spacing of the pool
     /// @param pool to get from
     /// @return the tick spacing of the pool
@@ -298,27 +623,109 @@ interface ISeawaterExecutorAdminExposed {
     /// @return the fee growth for fUSDC
     function feeGrowthGlobal1A33A5A1B(address pool) external returns (uint256);
 
+    /// @notice gets the initialised ticks of a pool within a range, for depth charts
+    /// @param pool to get from
+    /// @param from the lowest tick to return, inclusive
+    /// @param to the highest tick to return, inclusive
+    /// @param limit the maximum number of ticks to return
+    /// @return the ticks found, and the tick to use as `from` to get the next page, which is greater than `to` once every tick has been returned. Pages stop after reading 256 words of the tick bitmap, so a sparse range can return fewer than `limit` ticks before the end
+    function ticksInRange132ACCEE(
+        address pool,
+        int32 from,
+        int32 to,
+        uint32 limit
+    ) external returns (TickInfo[] memory, int32);
+
     /// @notice enables or disables a pool
     /// @param pool the pool to enable or disable
     /// @param enabled true to enable to pool, false to disable it
     function enablePool579DA658(address pool, bool enabled) external;
 
+    /// @notice pauses or unpauses swaps and liquidity adds in every pool, while still letting LPs
+    ///         withdraw their liquidity and fees
+    /// @dev pausing requires the PAUSER role, unpausing requires the admin
+    /// @param paused true to pause, false to unpause
+    function setGlobalPause10803050(bool paused) external;
+
+    /// @notice sets the operations paused in a pool
+    /// @dev pausing requires the PAUSER role, unpausing requires the POOL_CREATOR role
+    /// @param pool to set the flags for
+    /// @param flags the operations to pause, a combination of 1 (swaps), 2 (liquidity adds),
+    ///        4 (liquidity removals), and 8 (fee collection)
+    function setPoolPauseFlags132B43A7(address pool, uint8 flags) external;
+
+    /// @notice gets the pause state of a pool
+    /// @param pool to get the flags for
+    /// @return if the global pause is active, and the operations paused in the pool
+    function pauseState109451A3(address pool) external returns (bool, uint8);
+
     /// @notice authorise an address to create and enable pools on its own
+    /// @dev grants or revokes the POOL_CREATOR role
     /// @param enabled to set their status
     function authoriseEnabler5B17C274(address enabler, bool enabled) external;
 
     /// @notice set the sqrt price for a pool in the event of misconfiguration.
+    /// @dev timelocked, the keccak256 hash of the calldata must be queued with queueTimelockedCall10C89285
     /// @param pool to set
     /// @param price to use as the starting place
     function setSqrtPriceFF4DB98C(address pool, uint256 price) external;
 
     /// @notice set the NFT manager.
+    /// @dev timelocked, the keccak256 hash of the calldata must be queued with queueTimelockedCall10C89285
     /// @param manager address to set to in its new form
     function updateNftManager9BDF41F6(address manager) external;
 
     /// @notice updateEmergencyCouncil to a new address.
     /// @param newCouncil to set the emergency council to
     function updateEmergencyCouncil7D0C1C58(address newCouncil) external;
+
+    /// @notice grants a role to an account, letting it use some of the admin functions
+    /// @param role the keccak256 hash of the role's name, one of POOL_CREATOR, FEE_COLLECTOR,
+    ///        PAUSER, PRICE_SETTER, or MIGRATOR
+    /// @param account to grant the role to
+    function grantRole106ADFC9(bytes32 role, address account) external;
+
+    /// @notice revokes a role from an account
+    /// @param role the keccak256 hash of the role's name
+    /// @param account to revoke the role from
+    function revokeRole105C41F0(bytes32 role, address account) external;
+
+    /// @notice gives up a role held by the caller
+    /// @param role the keccak256 hash of the role's name
+    function renounceRole10A8689C(bytes32 role) external;
+
+    /// @notice checks if an account holds a role, including roles held implicitly by the admin
+    ///         (every role), the emergency council (PAUSER), and authorised enablers (POOL_CREATOR)
+    /// @param role the keccak256 hash of the role's name
+    /// @param account to check
+    /// @return true if the account holds the role
+    function hasRole10A57EB4(bytes32 role, address account) external returns (bool);
+
+    /// @notice proposes a new admin, who must accept the transfer before they have any control
+    /// @param newAdmin to propose, or the zero address to cancel a pending transfer
+    function transferAdmin1068832B(address newAdmin) external;
+
+    /// @notice accepts a proposed admin transfer, making the caller the admin
+    function acceptAdmin1023F2FA() external;
+
+    /// @notice gets the admin proposed by the current admin
+    /// @return the pending admin, or the zero address if there isn't one
+    function pendingAdmin1062B366() external returns (address);
+
+    /// @notice queues a timelocked admin call, which can be executed after a delay of 2 days, and
+    ///         expires 14 days after that
+    /// @param id the keccak256 hash of the calldata of the call
+    /// @return the timestamp the call can be executed from
+    function queueTimelockedCall10C89285(bytes32 id) external returns (uint64);
+
+    /// @notice cancels a queued timelocked admin call
+    /// @param id the keccak256 hash of the calldata of the call
+    function cancelTimelockedCall114DF1E3(bytes32 id) external;
+
+    /// @notice gets the timestamp a timelocked admin call can be executed from
+    /// @param id the keccak256 hash of the calldata of the call
+    /// @return the timestamp, or 0 if the call isn't queued
+    function timelockedCallEta106B4D2F(bytes32 id) external returns (uint64);
 }
 
 interface ISeawaterExecutorAdmin  is ISeawaterExecutorAdminExposed {
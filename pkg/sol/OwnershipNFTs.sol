@@ -178,6 +178,25 @@ contract OwnershipNFTs is IERC721Metadata {
         return balance;
     }
 
+    /**
+     * @notice tokenOfOwnerByIndex gets the id of a NFT owned by a user, by its
+     *         index in the user's NFTs. This is the only function of
+     *         ERC721Enumerable implemented, as Seawater doesn't track the
+     *         total supply
+     * @param _owner to look up the NFTs of
+     * @param _index of the NFT, less than the balance of `_owner`
+     */
+    function tokenOfOwnerByIndex(address _owner, uint256 _index) external view returns (uint256) {
+        (bool ok, bytes memory rc) = address(SEAWATER).staticcall(abi.encodeWithSelector(
+            SEAWATER.positionOfOwnerByIndex1068E3C7.selector,
+            _owner,
+            _index
+        ));
+        require(ok, "position of owner revert");
+        (uint256 id) = abi.decode(rc, (uint256));
+        return id;
+    }
+
     /// @inheritdoc IERC721Metadata
     function tokenURI(uint256 /* _tokenId */) external view returns (string memory) {
         return TOKEN_URI;
@@ -167,6 +167,55 @@ contract SeawaterAMM is ISeawaterAMM {
         directDelegate(_getExecutorAdmin());
     }
 
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function createPool111360A2(
+        address /* token */,
+        uint256 /* sqrtPriceX96 */,
+        uint32 /* fee */
+    ) external returns (address) {
+        directDelegate(_getExecutorAdmin());
+    }
+
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function createPairPool111D0A09(
+        address /* tokenA */,
+        address /* tokenB */,
+        uint256 /* sqrtPriceX96 */,
+        uint32 /* fee */
+    ) external returns (address) {
+        directDelegate(_getExecutorAdmin());
+    }
+
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function getPairPool11957B4F(address /* tokenA */, address /* tokenB */, uint32 /* fee */) external returns (address) {
+        directDelegate(_getExecutorAdmin());
+    }
+
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function getPool12523831(address /* token */, uint32 /* fee */) external returns (address) {
+        directDelegate(_getExecutorAdmin());
+    }
+
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function poolToken10162F39(address /* pool */) external returns (address) {
+        directDelegate(_getExecutorAdmin());
+    }
+
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function poolTokens10591443(address /* pool */) external returns (address, address) {
+        directDelegate(_getExecutorAdmin());
+    }
+
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function enableFeeTier120D0DDC(uint32 /* fee */, uint8 /* tickSpacing */) external {
+        directDelegate(_getExecutorAdmin());
+    }
+
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function feeTierTickSpacing10D66666(uint32 /* fee */) external returns (uint8) {
+        directDelegate(_getExecutorAdmin());
+    }
+
     /// @inheritdoc ISeawaterExecutorAdminExposed
     function collectProtocol7540FA9F(
         address /* pool */,
@@ -177,6 +226,15 @@ contract SeawaterAMM is ISeawaterAMM {
         directDelegate(_getExecutorAdmin());
     }
 
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function setFeeProtocol1004ED95(
+        address /* pool */,
+        uint8 /* feeProtocol0 */,
+        uint8 /* feeProtocol1 */
+    ) external {
+        directDelegate(_getExecutorAdmin());
+    }
+
     /// @inheritdoc ISeawaterExecutorAdminExposed
     function enablePool579DA658(
         address /* pool */,
@@ -208,6 +266,71 @@ contract SeawaterAMM is ISeawaterAMM {
         directDelegate(_getExecutorAdmin());
     }
 
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function setGlobalPause10803050(bool /* paused */) external {
+        directDelegate(_getExecutorAdmin());
+    }
+
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function setPoolPauseFlags132B43A7(address /* pool */, uint8 /* flags */) external {
+        directDelegate(_getExecutorAdmin());
+    }
+
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function pauseState109451A3(address /* pool */) external returns (bool, uint8) {
+        directDelegate(_getExecutorAdmin());
+    }
+
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function grantRole106ADFC9(bytes32 /* role */, address /* account */) external {
+        directDelegate(_getExecutorAdmin());
+    }
+
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function revokeRole105C41F0(bytes32 /* role */, address /* account */) external {
+        directDelegate(_getExecutorAdmin());
+    }
+
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function renounceRole10A8689C(bytes32 /* role */) external {
+        directDelegate(_getExecutorAdmin());
+    }
+
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function hasRole10A57EB4(bytes32 /* role */, address /* account */) external returns (bool) {
+        directDelegate(_getExecutorAdmin());
+    }
+
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function transferAdmin1068832B(address /* newAdmin */) external {
+        directDelegate(_getExecutorAdmin());
+    }
+
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function acceptAdmin1023F2FA() external {
+        directDelegate(_getExecutorAdmin());
+    }
+
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function pendingAdmin1062B366() external returns (address) {
+        directDelegate(_getExecutorAdmin());
+    }
+
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function queueTimelockedCall10C89285(bytes32 /* id */) external returns (uint64) {
+        directDelegate(_getExecutorAdmin());
+    }
+
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function cancelTimelockedCall114DF1E3(bytes32 /* id */) external {
+        directDelegate(_getExecutorAdmin());
+    }
+
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function timelockedCallEta106B4D2F(bytes32 /* id */) external returns (uint64) {
+        directDelegate(_getExecutorAdmin());
+    }
+
     // swap functions
 
     /// @inheritdoc ISeawaterExecutorSwap
@@ -221,11 +344,26 @@ contract SeawaterAMM is ISeawaterAMM {
     }
 
 
+    /// @inheritdoc ISeawaterExecutorQuote
+    function quoteStructured2065E121(address /* pool */, bool /* zeroForOne */, int256 /* amount */, uint256 /* priceLimit */) external {
+        directDelegate(_getExecutorQuote());
+    }
+
     /// @inheritdoc ISeawaterExecutorQuote
     function quote2CD06B86E(address /* to */, address /* from */, uint256 /* amount */, uint256 /* minOut*/) external {
         directDelegate(_getExecutorQuote());
     }
 
+    /// @inheritdoc ISeawaterExecutorQuote
+    function quote2ExactOut13FB992B(address /* from */, address /* to */, uint256 /* amountOut */, uint256 /* maxIn */) external {
+        directDelegate(_getExecutorQuote());
+    }
+
+    /// @inheritdoc ISeawaterExecutorQuote
+    function quotePath101993E8(address[] memory /* path */, int256 /* amount */) external {
+        directDelegate(_getExecutorQuote());
+    }
+
     /// @inheritdoc ISeawaterExecutorSwapPermit2
     function swapPermit2EE84AD91(
         address /* pool */,
@@ -245,6 +383,32 @@ contract SeawaterAMM is ISeawaterAMM {
         directDelegate(_getExecutorSwap());
     }
 
+    /// @inheritdoc ISeawaterExecutorSwap
+    function swap2ExactOut1758D1A7(address /* from */, address /* to */, uint256 /* amountOut */, uint256 /* maxIn */) external returns (uint256, uint256) {
+        directDelegate(_getExecutorSwap());
+    }
+
+    /// @inheritdoc ISeawaterExecutorSwap
+    function swapPathExactIn1033D01B(address[] memory /* path */, uint256 /* amount */, uint256 /* minOut */) external returns (uint256, uint256) {
+        directDelegate(_getExecutorSwap());
+    }
+
+    /// @inheritdoc ISeawaterExecutorSwap
+    function swapPathExactOut100F69C2(address[] memory /* path */, uint256 /* amount */, uint256 /* maxIn */) external returns (uint256, uint256) {
+        directDelegate(_getExecutorSwap());
+    }
+
+    /// @inheritdoc ISeawaterExecutorSwap
+    function flash1022F44D(
+        address /* pool */,
+        address /* recipient */,
+        uint256 /* amount0 */,
+        uint256 /* amount1 */,
+        bytes calldata /* data */
+    ) external {
+        directDelegate(_getExecutorSwap());
+    }
+
     /// @inheritdoc ISeawaterExecutorSwapPermit2
     function swap2ExactInPermit236B2FDD8(
         address /* from */,
@@ -258,6 +422,19 @@ contract SeawaterAMM is ISeawaterAMM {
         directDelegate(_getExecutorSwapPermit2());
     }
 
+    /// @inheritdoc ISeawaterExecutorSwapPermit2
+    function swap2ExactOutPermit21161B87F(
+        address /* from */,
+        address /* to */,
+        uint256 /* amountOut */,
+        uint256 /* maxIn */,
+        uint256 /* nonce */,
+        uint256 /* deadline */,
+        bytes memory /* sig */
+    ) external returns (uint256, uint256) {
+        directDelegate(_getExecutorSwapPermit2());
+    }
+
     /// @inheritdoc ISeawaterAMM
     function swapIn32502CA71(address token, uint256 amountIn, uint256 minOut) external returns (int256, int256) {
         (bool success, bytes memory data) = _getExecutorSwap().delegatecall(abi.encodeCall(
@@ -372,6 +549,37 @@ contract SeawaterAMM is ISeawaterAMM {
         directDelegate(_getExecutorPosition());
     }
 
+    /// @inheritdoc ISeawaterExecutorPosition
+    function positionInfo110D9723(address /* pool */, uint256 /* id */) external returns (
+        address,
+        int32,
+        int32,
+        uint128,
+        uint256,
+        uint256,
+        uint128,
+        uint128
+    ) {
+        directDelegate(_getExecutorPosition());
+    }
+
+    /// @inheritdoc ISeawaterExecutorPosition
+    function positionOfOwnerByIndex1068E3C7(
+        address /* owner */,
+        uint256 /* index */
+    ) external returns (uint256) {
+        directDelegate(_getExecutorPosition());
+    }
+
+    /// @inheritdoc ISeawaterExecutorPosition
+    function positionsOfOwner103ECF2F(
+        address /* owner */,
+        uint256 /* offset */,
+        uint256 /* limit */
+    ) external returns (uint256[] memory) {
+        directDelegate(_getExecutorPosition());
+    }
+
     /// @inheritdoc ISeawaterExecutorPosition
     function positionLiquidity8D11C045(
         address /* pool */,
@@ -414,6 +622,33 @@ contract SeawaterAMM is ISeawaterAMM {
         directDelegate(_getExecutorAdmin());
     }
 
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function observe106B4AB6(
+        address /* pool */,
+        uint32[] calldata /* secondsAgos */
+    ) external returns (int56[] memory, uint160[] memory) {
+        directDelegate(_getExecutorAdmin());
+    }
+
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function secondsPerLiquidityInside11B8A254(
+        address /* pool */,
+        int32 /* lower */,
+        int32 /* upper */
+    ) external returns (uint160, uint32) {
+        directDelegate(_getExecutorAdmin());
+    }
+
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function observationState105820A6(address /* pool */) external returns (uint16, uint16, uint16) {
+        directDelegate(_getExecutorAdmin());
+    }
+
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function increaseObservationCardinalityNext100D2AB9(address /* pool */, uint16 /* next */) external {
+        directDelegate(_getExecutorAdmin());
+    }
+
     /// @inheritdoc ISeawaterExecutorAdminExposed
     function tickSpacing653FE28F(address /* pool */) external returns (uint8) {
         directDelegate(_getExecutorAdmin());
@@ -434,6 +669,16 @@ contract SeawaterAMM is ISeawaterAMM {
         directDelegate(_getExecutorAdmin());
     }
 
+    /// @inheritdoc ISeawaterExecutorAdminExposed
+    function ticksInRange132ACCEE(
+        address /* pool */,
+        int32 /* from */,
+        int32 /* to */,
+        uint32 /* limit */
+    ) external returns (TickInfo[] memory, int32) {
+        directDelegate(_getExecutorAdmin());
+    }
+
     /// @inheritdoc ISeawaterExecutorPosition
     function collectSingleTo6D76575F(
         address /* pool */,
@@ -501,6 +746,32 @@ contract SeawaterAMM is ISeawaterAMM {
         directDelegate(_getExecutorUpdatePosition());
     }
 
+    /// @inheritdoc ISeawaterExecutorUpdatePosition
+    function placeRangeOrder103B25D5(
+        address /* pool */,
+        int32 /* lower */,
+        bool /* zeroForOne */,
+        uint128 /* liquidity */
+    ) external returns (uint256, uint256) {
+        directDelegate(_getExecutorUpdatePosition());
+    }
+
+    /// @inheritdoc ISeawaterExecutorUpdatePosition
+    function cancelRangeOrder11EE2063(
+        address /* pool */,
+        uint256 /* epoch */
+    ) external returns (uint256, uint256) {
+        directDelegate(_getExecutorUpdatePosition());
+    }
+
+    /// @inheritdoc ISeawaterExecutorUpdatePosition
+    function claimRangeOrder11F9C034(
+        address /* pool */,
+        uint256 /* epoch */
+    ) external returns (uint256, uint256) {
+        directDelegate(_getExecutorUpdatePosition());
+    }
+
     // fallback!
     fallback() external {
         require(msg.data.length > 3);